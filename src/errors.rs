@@ -19,6 +19,9 @@ pub enum PropertyLookupError {
     NullableObject {
         nullable_type: Entity,
     },
+    /// An entity found during lookup does not support
+    /// an operation, as with a malformed property.
+    EntityAccess(EntityAccessError),
 }
 
 impl Debug for PropertyLookupError {
@@ -45,4 +48,192 @@ impl Debug for MethodOverrideError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "MethodOverrideError()")
     }
-}
\ No newline at end of file
+}
+
+#[derive(Clone)]
+pub enum TypeSubstitutionError {
+    Defer,
+    WrongParameterCount {
+        expected: usize,
+        actual: usize,
+    },
+    ConstraintNotSatisfied {
+        type_param: Entity,
+        constraint: Entity,
+        substitute_type: Entity,
+    },
+}
+
+impl Debug for TypeSubstitutionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TypeSubstitutionError()")
+    }
+}
+
+#[derive(Clone)]
+pub enum ApplyTypeError {
+    /// The entity is not a type, variable slot, virtual slot or method slot.
+    EntityAccess(EntityAccessError),
+    /// The substitute types of a type after substitution are invalid.
+    TypeSubstitution(TypeSubstitutionError),
+}
+
+impl Debug for ApplyTypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ApplyTypeError()")
+    }
+}
+
+#[derive(Clone)]
+pub enum TypeInferenceError {
+    Defer,
+    /// A type parameter has been inferred as incompatible types.
+    Conflict {
+        type_param: Entity,
+        inferred_types: Vec<Entity>,
+    },
+    /// A type parameter does not occur in any argument.
+    NotInferred {
+        type_param: Entity,
+    },
+    /// The inferred substitute types do not match the count of type parameters.
+    WrongParameterCount {
+        expected: usize,
+        actual: usize,
+    },
+    ConstraintNotSatisfied {
+        type_param: Entity,
+        constraint: Entity,
+        substitute_type: Entity,
+    },
+}
+
+impl Debug for TypeInferenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TypeInferenceError()")
+    }
+}
+
+#[derive(Clone)]
+pub enum ConstantEvaluationError {
+    Defer,
+    /// An expression is not a compile-time constant.
+    NotConstant {
+        location: Location,
+    },
+    /// A constant `BigInt` division or remainder has a zero divisor.
+    DivisionByZero {
+        location: Location,
+    },
+    /// A `CONFIG::name` reference names no configuration constant.
+    UndefinedConfigConstant {
+        name: String,
+        location: Location,
+    },
+    /// A configuration constant depends on itself.
+    CyclicConfigConstant {
+        name: String,
+    },
+    /// The value of a configuration constant is not a valid expression.
+    InvalidConfigConstant {
+        name: String,
+    },
+}
+
+impl Debug for ConstantEvaluationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ConstantEvaluationError()")
+    }
+}
+
+/// Error returned by the fallible counterparts of `Entity` methods,
+/// such as `Entity::try_object()`, when the entity kind does not
+/// support the method.
+#[derive(Clone, PartialEq)]
+pub struct EntityAccessError {
+    /// The entity kind, as in `"ClassType"`.
+    pub entity_kind: &'static str,
+    /// The method name, as in `"object"`.
+    pub method: &'static str,
+}
+
+impl Debug for EntityAccessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EntityAccessError({}::{})", self.entity_kind, self.method)
+    }
+}
+
+#[derive(Clone)]
+pub enum TypeParseError {
+    /// The string is not a valid type expression or qualified name.
+    Syntax {
+        messages: Vec<String>,
+    },
+    /// A name could not be resolved. `segment` is the name
+    /// up to and including the unresolved part, as in `flash.display.Spirte`.
+    Unresolved {
+        segment: String,
+        location: Location,
+    },
+    /// A name resolves to more than one definition.
+    AmbiguousReference {
+        segment: String,
+        location: Location,
+    },
+    /// A name does not resolve to a type.
+    NotAType {
+        segment: String,
+        location: Location,
+    },
+    /// A qualifier does not resolve to a namespace.
+    NotANamespace {
+        segment: String,
+        location: Location,
+    },
+    TypeSubstitution(TypeSubstitutionError),
+}
+
+impl Debug for TypeParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Syntax { messages } => write!(f, "TypeParseError::Syntax({})", messages.join("; ")),
+            Self::Unresolved { segment, .. } => write!(f, "TypeParseError::Unresolved({segment})"),
+            Self::AmbiguousReference { segment, .. } => write!(f, "TypeParseError::AmbiguousReference({segment})"),
+            Self::NotAType { segment, .. } => write!(f, "TypeParseError::NotAType({segment})"),
+            Self::NotANamespace { segment, .. } => write!(f, "TypeParseError::NotANamespace({segment})"),
+            Self::TypeSubstitution(_) => write!(f, "TypeParseError::TypeSubstitution"),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum RenameError {
+    /// The new name is not a valid identifier.
+    InvalidName {
+        name: String,
+    },
+    /// A definition to rename is not a named definition declared in source code,
+    /// as with definitions from a library.
+    NotRenamable {
+        definition: Entity,
+    },
+    /// A renamed definition would conflict with an existing definition in
+    /// the same `Names` table, in a subclass, superclass or interface implementor.
+    Conflict {
+        definition: Entity,
+        existing: Entity,
+    },
+    /// A reference to a renamed definition would resolve to an existing definition
+    /// visible from the referencing scope.
+    Shadowed {
+        definition: Entity,
+        existing: Entity,
+        location: Location,
+    },
+}
+
+impl Debug for RenameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RenameError()")
+    }
+}
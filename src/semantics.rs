@@ -105,6 +105,8 @@ pub use database::*;
 mod entity;
 pub use entity::*;
 
+mod entity_access;
+
 mod factory;
 pub use factory::*;
 
@@ -115,4 +117,64 @@ mod apply_type;
 pub use apply_type::*;
 
 mod unused;
-pub use unused::*;
\ No newline at end of file
+pub use unused::*;
+
+mod class_hierarchy;
+pub use class_hierarchy::*;
+
+mod type_inference;
+pub use type_inference::*;
+
+mod common_supertype;
+pub use common_supertype::*;
+
+mod nullability;
+pub use nullability::*;
+
+mod dataflow;
+pub use dataflow::*;
+
+mod definite_assignment;
+pub use definite_assignment::*;
+
+mod control_flow_check;
+pub use control_flow_check::*;
+
+mod dot_export;
+pub use dot_export::*;
+
+mod capture_analysis;
+pub use capture_analysis::*;
+
+mod constant_evaluation;
+pub use constant_evaluation::*;
+
+mod config_constants;
+pub use config_constants::*;
+
+mod type_printer;
+pub use type_printer::*;
+
+mod type_parser;
+pub use type_parser::*;
+
+mod fully_qualified_name;
+pub use fully_qualified_name::*;
+
+mod position_index;
+pub use position_index::*;
+
+mod references;
+pub use references::*;
+
+mod rename;
+pub use rename::*;
+
+mod completion;
+pub use completion::*;
+
+mod subexpressions;
+pub use subexpressions::*;
+
+#[cfg(test)]
+mod test_fixtures;
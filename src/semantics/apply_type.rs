@@ -4,36 +4,84 @@ use crate::ns::*;
 pub struct ApplyType<'a>(pub &'a Database);
 
 impl<'a> ApplyType<'a> {
+    /// Applies types to a type, variable slot, virtual slot or method slot.
+    ///
+    /// A type after substitution whose new substitute types do not satisfy
+    /// the constraints of its type parameters results into the invalidation
+    /// entity. While the constraints cannot be resolved yet, the type
+    /// after substitution is interned without verification.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the entity is of another kind. Use `try_exec()` for
+    /// entities of unknown kind.
     pub fn exec(&mut self, thing: &Entity, type_params: &SharedArray<Entity>, substitute_types: &SharedArray<Entity>) -> Entity {
-        if thing.is::<UnresolvedEntity>() || thing.is::<InvalidationEntity>() {
-            return thing.clone();
+        match self.apply(thing, type_params, substitute_types, true) {
+            Ok(r) => r,
+            Err(ApplyTypeError::TypeSubstitution(TypeSubstitutionError::Defer)) => {
+                self.apply(thing, type_params, substitute_types, false).unwrap()
+            },
+            Err(ApplyTypeError::TypeSubstitution(_)) => self.0.invalidation_entity(),
+            Err(ApplyTypeError::EntityAccess(error)) => panic!("{error:?}"),
+        }
+    }
+
+    /// Applies types to a type, variable slot, virtual slot or method slot,
+    /// returning an error for entities of another kind and for types after
+    /// substitution whose new substitute types are invalid.
+    pub fn try_exec(&mut self, thing: &Entity, type_params: &SharedArray<Entity>, substitute_types: &SharedArray<Entity>) -> Result<Entity, ApplyTypeError> {
+        self.apply(thing, type_params, substitute_types, true)
+    }
+
+    fn apply(&mut self, thing: &Entity, type_params: &SharedArray<Entity>, substitute_types: &SharedArray<Entity>, verify: bool) -> Result<Entity, ApplyTypeError> {
+        Ok(if thing.is::<UnresolvedEntity>() || thing.is::<InvalidationEntity>() {
+            thing.clone()
         } else if thing.is::<Type>() {
             if thing.is::<FunctionType>() {
-                let result_type = thing.result_type().apply_type(self.0, type_params, substitute_types);
+                let result_type = self.apply(&thing.result_type(), type_params, substitute_types, verify)?;
                 let mut params: Vec<Rc<SemanticFunctionTypeParameter>> = Vec::new();
                 for param in thing.params().iter() {
-                    params.push(Rc::new(param.apply_type(self.0, type_params, substitute_types)));
+                    let static_type = self.apply(&param.static_type, type_params, substitute_types, verify)?;
+                    params.push(Rc::new(SemanticFunctionTypeParameter { kind: param.kind, static_type }));
+                }
+                // Substituting the own type parameters of a generic
+                // function type leaves only those not substituted.
+                if let Some(own_type_params) = thing.type_params() {
+                    let remaining: SharedArray<Entity> = own_type_params.iter().filter(|t| !type_params.includes(t)).collect();
+                    if remaining.length() != 0 {
+                        return Ok(self.0.factory().create_parameterized_function_type(&remaining, params, result_type));
+                    }
                 }
-                return self.0.factory().create_function_type(params, result_type);
+                return Ok(self.0.factory().create_function_type(params, result_type));
             } else if thing.is::<NullableType>() {
-                let base = &thing.base().apply_type(self.0, type_params, substitute_types);
-                return self.0.factory().create_nullable_type(base);
+                let base = &self.apply(&thing.base(), type_params, substitute_types, verify)?;
+                return Ok(self.0.factory().create_nullable_type(base));
             } else if thing.is::<NonNullableType>() {
-                let base = &thing.base().apply_type(self.0, type_params, substitute_types);
-                return self.0.factory().create_non_nullable_type(base);
+                let base = &self.apply(&thing.base(), type_params, substitute_types, verify)?;
+                return Ok(self.0.factory().create_non_nullable_type(base));
             } else if thing.is::<TupleType>() {
-                let el: Vec<Entity> = thing.element_types().iter().map(|t| t.apply_type(self.0, type_params, substitute_types)).collect();
-                return self.0.factory().create_tuple_type(el);
+                let mut el: Vec<Entity> = vec![];
+                for t in thing.element_types().iter() {
+                    el.push(self.apply(&t, type_params, substitute_types, verify)?);
+                }
+                return Ok(self.0.factory().create_tuple_type(el));
             } else if thing.is::<TypeAfterSubstitution>() {
-                let new_substitute_types: SharedArray<Entity> = thing.substitute_types().iter().map(|t| t.apply_type(self.0, type_params, substitute_types)).collect();
-                return self.0.factory().create_type_after_substitution(&thing.origin(), &new_substitute_types);
+                let mut new_substitute_types: SharedArray<Entity> = shared_array![];
+                for t in thing.substitute_types().iter() {
+                    new_substitute_types.push(self.apply(&t, type_params, substitute_types, verify)?);
+                }
+                if !verify {
+                    return Ok(self.0.factory().intern_type_after_substitution(&thing.origin(), &new_substitute_types));
+                }
+                return self.0.factory().try_create_type_after_substitution(&thing.origin(), &new_substitute_types)
+                    .map_err(ApplyTypeError::TypeSubstitution);
             } else if thing.is::<TypeParameterType>() {
                 let i = type_params.index_of(&thing);
                 if let Some(i) = i {
-                    return substitute_types.get(i).unwrap();
+                    return Ok(substitute_types.get(i).unwrap());
                 }
             }
-            return thing.clone();
+            thing.clone()
         } else if thing.is::<VariableSlot>() {
             self.0.factory().create_variable_slot_after_substitution(thing, type_params, substitute_types)
         } else if thing.is::<VirtualSlot>() {
@@ -41,7 +89,110 @@ impl<'a> ApplyType<'a> {
         } else if thing.is::<MethodSlot>() {
             self.0.factory().create_method_slot_after_substitution(thing, type_params, substitute_types)
         } else {
-            panic!()
+            return Err(ApplyTypeError::EntityAccess(EntityAccessError {
+                entity_kind: thing.kind_name(),
+                method: "apply_type",
+            }));
+        })
+    }
+
+    /// Verifies that substitute types match the count of type parameters
+    /// and satisfy their constraints.
+    pub fn verify_substitute_types(&mut self, type_params: &SharedArray<Entity>, substitute_types: &SharedArray<Entity>) -> Result<(), TypeSubstitutionError> {
+        if type_params.length() != substitute_types.length() {
+            return Err(TypeSubstitutionError::WrongParameterCount {
+                expected: type_params.length(),
+                actual: substitute_types.length(),
+            });
+        }
+        for (type_param, substitute_type) in type_params.iter().zip(substitute_types.iter()) {
+            let Some(constraint) = type_param.constraint() else {
+                continue;
+            };
+
+            // Defer if unresolved
+            constraint.defer().map_err(|_| TypeSubstitutionError::Defer)?;
+            substitute_type.defer().map_err(|_| TypeSubstitutionError::Defer)?;
+
+            // The constraint may refer to the type parameters themselves,
+            // as in `T extends Comparable.<T>`.
+            let constraint = constraint.apply_type(self.0, type_params, substitute_types);
+
+            if !self.satisfies_constraint(&substitute_type, &constraint).map_err(|_| TypeSubstitutionError::Defer)? {
+                return Err(TypeSubstitutionError::ConstraintNotSatisfied {
+                    type_param: type_param.clone(),
+                    constraint,
+                    substitute_type,
+                });
+            }
         }
+        Ok(())
+    }
+
+    /// Indicates whether a substitute type satisfies a type parameter constraint.
+    pub fn satisfies_constraint(&mut self, substitute_type: &Entity, constraint: &Entity) -> Result<bool, DeferError> {
+        if substitute_type.is::<AnyType>() || substitute_type.is::<InvalidationEntity>() || constraint.is::<InvalidationEntity>() {
+            return Ok(true);
+        }
+        let substitute_type = substitute_type.escape_of_nullable_or_non_nullable();
+        let constraint = constraint.escape_of_nullable_or_non_nullable();
+        substitute_type.is_equals_or_subtype_of(&constraint, self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn define_class(db: &Database, local_name: &str) -> Entity {
+        let top_level_package = db.top_level_package();
+        let public_ns = top_level_package.public_ns().unwrap();
+        let name = db.factory().create_qname(&public_ns, local_name.into());
+        let class = db.factory().create_class_type(name.clone(), &public_ns);
+        top_level_package.properties(db).set(name, class.clone());
+        class
+    }
+
+    fn define_type_param(db: &Database, local_name: &str, constraint: Option<Entity>) -> Entity {
+        let public_ns = db.top_level_package().public_ns().unwrap();
+        let type_param = db.factory().create_type_parameter_type(&db.factory().create_qname(&public_ns, local_name.into()));
+        type_param.set_constraint(constraint);
+        type_param
+    }
+
+    #[test]
+    fn applies_to_type_after_substitution() {
+        let db = Database::new(DatabaseOptions::default());
+        let a = define_class(&db, "A");
+        let boxed = define_class(&db, "Box");
+        boxed.set_type_params(Some(shared_array![define_type_param(&db, "T", Some(a.clone()))]));
+        let u = define_type_param(&db, "U", None);
+        let box_u = db.factory().create_type_after_substitution(&boxed, &shared_array![u.clone()]);
+
+        let r = ApplyType(&db).try_exec(&box_u, &shared_array![u.clone()], &shared_array![a.clone()]).unwrap();
+        assert_eq!(r, db.factory().create_type_after_substitution(&boxed, &shared_array![a.clone()]));
+    }
+
+    #[test]
+    fn verifies_constraints_of_type_after_substitution() {
+        let db = Database::new(DatabaseOptions::default());
+        let a = define_class(&db, "A");
+        let b = define_class(&db, "B");
+        let boxed = define_class(&db, "Box");
+        boxed.set_type_params(Some(shared_array![define_type_param(&db, "T", Some(a.clone()))]));
+        let u = define_type_param(&db, "U", None);
+        let box_u = db.factory().create_type_after_substitution(&boxed, &shared_array![u.clone()]);
+
+        let r = ApplyType(&db).try_exec(&box_u, &shared_array![u.clone()], &shared_array![b.clone()]);
+        assert!(matches!(r, Err(ApplyTypeError::TypeSubstitution(TypeSubstitutionError::ConstraintNotSatisfied { .. }))));
+        assert_eq!(ApplyType(&db).exec(&box_u, &shared_array![u], &shared_array![b]), db.invalidation_entity());
+    }
+
+    #[test]
+    fn reports_entities_of_other_kinds() {
+        let db = Database::new(DatabaseOptions::default());
+        let ns = db.top_level_package().public_ns().unwrap();
+        let r = ApplyType(&db).try_exec(&ns, &shared_array![], &shared_array![]);
+        assert!(matches!(r, Err(ApplyTypeError::EntityAccess(_))));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,309 @@
+use crate::ns::*;
+
+/// Closure capture and escape analysis over nested activations.
+///
+/// Starting from an activation, the analysis visits the lines of its control flow
+/// graph and those of every nested activation, found through function expressions
+/// (mapped to a `LambdaObject`) and nested function definitions (mapped to
+/// a method slot with an `activation()`), and determines:
+///
+/// * which properties of an activation, or of a block scope inside it,
+///   are referenced from a nested activation;
+/// * which activations have their `this` object referenced from a nested activation;
+/// * which activations must therefore be allocated in the heap.
+///
+/// The analysis updates `Activation::property_has_capture()` for every
+/// analyzed activation, clearing flags set by a previous analysis.
+pub struct CaptureAnalysis<'a>(pub &'a Database);
+
+impl<'a> CaptureAnalysis<'a> {
+    pub fn analyze(&self, activation: &Entity) -> CaptureAnalysisResult {
+        let mut result = CaptureAnalysisResult {
+            closures: vec![],
+            captured_properties: vec![],
+            captured_this: vec![],
+        };
+        let mut pending: Vec<Entity> = vec![activation.clone()];
+        let mut i = 0usize;
+        while i < pending.len() {
+            let current = pending[i].clone();
+            i += 1;
+            let mut visitor = CaptureVisitor {
+                db: self.0,
+                activation: current.clone(),
+                closures: vec![],
+                result: &mut result,
+            };
+            for block in current.control_flow_graph().blocks().iter() {
+                for line in block.lines().iter() {
+                    visitor.directive(line);
+                }
+            }
+            for closure in visitor.closures {
+                if !pending.contains(&closure) {
+                    pending.push(closure);
+                }
+            }
+        }
+        result.closures = pending[1..].to_vec();
+        for activation in pending.iter() {
+            activation.clear_property_has_capture();
+        }
+        for (activation, property) in result.captured_properties.iter() {
+            activation.set_property_has_capture(property, true);
+        }
+        result
+    }
+}
+
+pub struct CaptureAnalysisResult {
+    closures: Vec<Entity>,
+    captured_properties: Vec<(Entity, Entity)>,
+    captured_this: Vec<Entity>,
+}
+
+impl CaptureAnalysisResult {
+    /// Nested activations, in breadth-first order.
+    pub fn closures(&self) -> Vec<Entity> {
+        self.closures.clone()
+    }
+
+    /// Properties captured from an activation.
+    pub fn captured_properties_of(&self, activation: &Entity) -> Vec<Entity> {
+        self.captured_properties.iter().filter(|(a, _)| a == activation).map(|(_, p)| p.clone()).collect()
+    }
+
+    pub fn is_captured(&self, activation: &Entity, property: &Entity) -> bool {
+        self.captured_properties.iter().any(|(a, p)| a == activation && p == property)
+    }
+
+    /// Indicates whether the `this` object of an activation is referenced
+    /// from a nested activation.
+    pub fn has_captured_this(&self, activation: &Entity) -> bool {
+        self.captured_this.contains(activation)
+    }
+
+    /// Indicates whether an activation must be allocated in the heap, that is,
+    /// whether any of its properties or its `this` object is captured.
+    pub fn requires_heap_allocation(&self, activation: &Entity) -> bool {
+        self.has_captured_this(activation) || self.captured_properties.iter().any(|(a, _)| a == activation)
+    }
+
+    /// Activations that must be allocated in the heap.
+    pub fn heap_activations(&self) -> Vec<Entity> {
+        let mut r: Vec<Entity> = vec![];
+        for a in self.captured_properties.iter().map(|(a, _)| a).chain(self.captured_this.iter()) {
+            if !r.contains(a) {
+                r.push(a.clone());
+            }
+        }
+        r
+    }
+}
+
+struct CaptureVisitor<'a, 'b> {
+    db: &'a Database,
+    activation: Entity,
+    closures: Vec<Entity>,
+    result: &'b mut CaptureAnalysisResult,
+}
+
+impl<'a, 'b> CaptureVisitor<'a, 'b> {
+    fn capture_property(&mut self, base: &Entity, property: &Entity) {
+        let Some(owner) = base.search_activation() else {
+            return;
+        };
+        if owner != self.activation && !self.result.is_captured(&owner, property) {
+            self.result.captured_properties.push((owner, property.clone()));
+        }
+    }
+
+    fn capture_this(&mut self, this: &Entity) {
+        let owner = self.activation.descending_scope_hierarchy()
+            .find(|scope| scope.is::<Activation>() && scope.this().as_ref() == Some(this));
+        if let Some(owner) = owner {
+            if owner != self.activation && !self.result.captured_this.contains(&owner) {
+                self.result.captured_this.push(owner);
+            }
+        }
+    }
+
+    fn reference(&mut self, r: Option<Entity>) {
+        let Some(r) = r else {
+            return;
+        };
+        if r.is::<ThisObject>() {
+            self.capture_this(&r);
+        } else if r.is::<ScopeReferenceValue>() {
+            self.capture_property(&r.base(), &r.property());
+        } else if r.is::<FixtureReferenceValue>() && r.base().is::<ThisObject>() {
+            self.capture_this(&r.base());
+        }
+    }
+
+    /// Visits a line. Sub-directives of compound statements are
+    /// lines of other blocks and are not visited.
+    fn directive(&mut self, directive: &Rc<Directive>) {
+        match directive.as_ref() {
+            Directive::ExpressionStatement(d) => self.expression(&d.expression),
+            Directive::IfStatement(d) => self.expression(&d.test),
+            Directive::WhileStatement(d) => self.expression(&d.test),
+            Directive::DoStatement(d) => self.expression(&d.test),
+            Directive::SwitchStatement(d) => {
+                self.expression(&d.discriminant);
+                for case in d.cases.iter() {
+                    for label in case.labels.iter() {
+                        if let CaseLabel::Case((exp, _)) = label {
+                            self.expression(exp);
+                        }
+                    }
+                }
+            },
+            Directive::WithStatement(d) => self.expression(&d.object),
+            Directive::ThrowStatement(d) => self.expression(&d.expression),
+            Directive::DefaultXmlNamespaceStatement(d) => self.expression(&d.right),
+            Directive::ReturnStatement(d) => {
+                if let Some(exp) = d.expression.as_ref() {
+                    self.expression(exp);
+                }
+            },
+            Directive::SuperStatement(d) => {
+                for arg in d.arguments.iter() {
+                    self.expression(arg);
+                }
+            },
+            Directive::VariableDefinition(d) => {
+                for binding in d.bindings.iter() {
+                    self.binding(binding);
+                }
+            },
+            Directive::ForStatement(d) => {
+                match d.init.as_ref() {
+                    Some(ForInitializer::Expression(exp)) => self.expression(exp),
+                    Some(ForInitializer::VariableDefinition(defn)) => {
+                        for binding in defn.bindings.iter() {
+                            self.binding(binding);
+                        }
+                    },
+                    None => {},
+                }
+                if let Some(exp) = d.test.as_ref() {
+                    self.expression(exp);
+                }
+                if let Some(exp) = d.update.as_ref() {
+                    self.expression(exp);
+                }
+            },
+            Directive::ForInStatement(d) => {
+                self.expression(&d.right);
+                match &d.left {
+                    ForInBinding::Expression(exp) => self.expression(exp),
+                    ForInBinding::VariableDefinition(defn) => {
+                        for binding in defn.bindings.iter() {
+                            self.binding(binding);
+                        }
+                    },
+                }
+            },
+            Directive::FunctionDefinition(_) => {
+                let slot = self.db.node_mapping().get(directive).filter(|slot| slot.is::<MethodSlot>());
+                if let Some(activation) = slot.and_then(|slot| slot.activation()) {
+                    self.closures.push(activation);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fn binding(&mut self, binding: &Rc<VariableBinding>) {
+        self.expression(&binding.destructuring.destructuring);
+        if let Some(init) = binding.initializer.as_ref() {
+            self.expression(init);
+        }
+    }
+
+    fn expression(&mut self, exp: &Rc<Expression>) {
+        match exp.as_ref() {
+            Expression::QualifiedIdentifier(_) | Expression::ThisLiteral(_) => self.reference(self.db.node_mapping().get(exp)),
+            Expression::Function(_) => {
+                let value = self.db.node_mapping().get(exp).filter(|value| value.is::<LambdaObject>());
+                if let Some(activation) = value.and_then(|value| value.activation()) {
+                    self.closures.push(activation);
+                }
+            },
+            Expression::ObjectInitializer(e) => {
+                for field in e.fields.iter() {
+                    if let InitializerField::Field { value: None, .. } = field.as_ref() {
+                        // Shorthand field
+                        self.reference(self.db.node_mapping().get(field));
+                    }
+                }
+            },
+            _ => {},
+        }
+        for exp in subexpressions(exp) {
+            self.expression(&exp);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+    use crate::semantics::test_fixtures::*;
+
+    /// Analyzes a closure, nested in an activation defining a local `x`,
+    /// whose body consists of the given lines, and returns whether `x`
+    /// is captured.
+    fn captures_local(source: &str) -> bool {
+        let db = Database::new(DatabaseOptions::default());
+        let outer = define_activation(&db);
+        let x = define_local(&db, &outer, "x");
+        let inner = define_activation(&db);
+        inner.set_parent(Some(outer.clone()));
+        let directives = parse_directives(source);
+        let r = db.factory().create_scope_reference_value(&outer, &x).unwrap();
+        map_identifiers(&db, &directives, &|name| if name == "x" { Some(r.clone()) } else { None });
+        add_block(&inner.control_flow_graph(), directives);
+        CaptureAnalysis(&db).analyze(&inner).is_captured(&outer, &x)
+    }
+
+    #[test]
+    fn captures_through_embedded_xml_expressions() {
+        assert!(captures_local("<a>{x}</a>;"));
+        assert!(captures_local("<a b={x}/>;"));
+        assert!(captures_local("<>{x}</>;"));
+        assert!(!captures_local("<a>x</a>;"));
+    }
+
+    #[test]
+    fn captures_through_computed_qualifiers() {
+        assert!(captures_local("ns::[x];"));
+        assert!(captures_local("o.ns::[x];"));
+        assert!(captures_local("x::y;"));
+    }
+
+    #[test]
+    fn captures_through_object_keys() {
+        assert!(captures_local("({[x]: 0});"));
+        assert!(captures_local("({x});"));
+        assert!(!captures_local("({x: 0});"));
+    }
+
+    #[test]
+    fn captures_through_default_xml_namespace_and_case_labels() {
+        assert!(captures_local("default xml namespace = x;"));
+        assert!(captures_local("switch (0) { case x: }"));
+    }
+
+    #[test]
+    fn clears_captures_of_any_entity() {
+        let db = Database::new(DatabaseOptions::default());
+        let activation = define_activation(&db);
+        let x = define_local(&db, &activation, "x");
+        activation.set_property_has_capture(&x, true);
+        activation.clear_property_has_capture();
+        assert!(!activation.property_has_capture(&x));
+        x.clear_property_has_capture();
+    }
+}
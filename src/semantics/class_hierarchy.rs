@@ -0,0 +1,264 @@
+use crate::ns::*;
+
+/// Whole-program class hierarchy queries, based on the
+/// `known_subclasses()`, `known_implementors()` and `overriden_by()`
+/// logs, used for devirtualization.
+///
+/// The results are only complete once every class of the program
+/// has been verified, as these logs are filled during verification.
+pub struct ClassHierarchy<'a>(pub &'a Database);
+
+impl<'a> ClassHierarchy<'a> {
+    /// Returns all known descendant classes of a class, in
+    /// breadth-first order, not including the class itself.
+    pub fn all_descendants(&self, class: &Entity) -> Vec<Entity> {
+        let mut r: Vec<Entity> = vec![];
+        let mut pending: Vec<Entity> = vec![self.original_type(class)];
+        let mut i = 0usize;
+        while i < pending.len() {
+            let class = pending[i].clone();
+            i += 1;
+            if !class.is::<ClassType>() {
+                continue;
+            }
+            for subclass in class.known_subclasses().iter() {
+                if !r.contains(&subclass) {
+                    r.push(subclass.clone());
+                    pending.push(subclass);
+                }
+            }
+        }
+        r
+    }
+
+    /// Returns the known descendant classes of a class that have
+    /// no known subclasses. If the class itself has no known subclasses,
+    /// the result consists of that class only.
+    pub fn leaf_classes(&self, class: &Entity) -> Vec<Entity> {
+        let mut classes = vec![self.original_type(class)];
+        classes.extend(self.all_descendants(class));
+        classes.into_iter().filter(|c| !c.is::<ClassType>() || c.known_subclasses().length() == 0).collect()
+    }
+
+    /// Returns all known classes implementing an interface, either directly,
+    /// through a subinterface, or through inheritance from an implementor.
+    pub fn all_implementors(&self, itrfc: &Entity) -> Vec<Entity> {
+        let mut r: Vec<Entity> = vec![];
+        let mut visited_interfaces: Vec<Entity> = vec![];
+        self.all_implementors_1(&self.original_type(itrfc), &mut visited_interfaces, &mut r);
+        r
+    }
+
+    fn all_implementors_1(&self, itrfc: &Entity, visited_interfaces: &mut Vec<Entity>, r: &mut Vec<Entity>) {
+        if visited_interfaces.contains(itrfc) || !itrfc.is::<InterfaceType>() {
+            return;
+        }
+        visited_interfaces.push(itrfc.clone());
+        for implementor in itrfc.known_implementors().iter() {
+            let implementor = self.original_type(&implementor);
+            if implementor.is::<InterfaceType>() {
+                self.all_implementors_1(&implementor, visited_interfaces, r);
+                continue;
+            }
+            if !r.contains(&implementor) {
+                r.push(implementor.clone());
+            }
+            for subclass in self.all_descendants(&implementor) {
+                if !r.contains(&subclass) {
+                    r.push(subclass);
+                }
+            }
+        }
+    }
+
+    /// Returns the concrete (non abstract) method implementations
+    /// that a call to a method slot may dispatch to at runtime.
+    ///
+    /// For an interface method, the result consists of the
+    /// implementations found in every known implementor.
+    pub fn dispatch_targets(&self, method: &Entity) -> Result<Vec<Entity>, DeferError> {
+        let mut r: Vec<Entity> = vec![];
+        if method.is_static() || method.is_constructor() {
+            r.push(method.clone());
+            return Ok(r);
+        }
+        let parent = method.parent();
+        if parent.as_ref().map(|p| p.is::<InterfaceType>()).unwrap_or(false) {
+            let itrfc = parent.unwrap();
+            for class in self.all_implementors(&itrfc) {
+                if let Some(m) = self.lookup_implementation(&class, method)? {
+                    self.collect_overrides(&m, &mut r);
+                }
+            }
+            return Ok(r);
+        }
+        self.collect_overrides(method, &mut r);
+        Ok(r)
+    }
+
+    /// Indicates whether a call to a method slot always dispatches
+    /// to a single implementation, allowing for a direct call.
+    pub fn is_monomorphic(&self, method: &Entity) -> Result<bool, DeferError> {
+        if method.is_static() || method.is_constructor() || method.is_final() {
+            return Ok(true);
+        }
+        if let Some(parent) = method.parent() {
+            if parent.is_class_type_possibly_after_sub() && parent.is_final() {
+                return Ok(true);
+            }
+        }
+        Ok(self.dispatch_targets(method)?.len() == 1)
+    }
+
+    /// Returns the single implementation a call to a method slot
+    /// dispatches to, if the call is monomorphic.
+    pub fn devirtualize(&self, method: &Entity) -> Result<Option<Entity>, DeferError> {
+        if !self.is_monomorphic(method)? {
+            return Ok(None);
+        }
+        if !method.is_abstract() && !method.parent().map(|p| p.is::<InterfaceType>()).unwrap_or(false) {
+            return Ok(Some(method.clone()));
+        }
+        Ok(self.dispatch_targets(method)?.first().cloned())
+    }
+
+    fn collect_overrides(&self, method: &Entity, r: &mut Vec<Entity>) {
+        if !method.is_abstract() && !r.contains(method) {
+            r.push(method.clone());
+        }
+        for m in method.overriden_by(self.0).iter() {
+            self.collect_overrides(&m, r);
+        }
+    }
+
+    /// Looks up the method implementing an interface method in a class,
+    /// including inherited methods.
+    fn lookup_implementation(&self, class: &Entity, itrfc_method: &Entity) -> Result<Option<Entity>, DeferError> {
+        let local_name = itrfc_method.name().local_name();
+        let itrfc_virtual_slot = itrfc_method.of_virtual_slot(self.0);
+        for class in class.descending_class_hierarchy(self.0).collect::<Vec<_>>() {
+            // Defer if unresolved
+            class.defer()?;
+
+            let Some(prop) = class.prototype(self.0).get_in_any_public_ns(&local_name).ok().unwrap_or(None) else {
+                continue;
+            };
+
+            if let Some(itrfc_virtual_slot) = itrfc_virtual_slot.as_ref() {
+                if !prop.is::<VirtualSlot>() {
+                    return Ok(None);
+                }
+                let is_getter = Some(itrfc_method.clone()) == itrfc_virtual_slot.getter(self.0);
+                return Ok(if is_getter { prop.getter(self.0) } else { prop.setter(self.0) });
+            }
+
+            return Ok(if prop.is::<MethodSlot>() { Some(prop) } else { None });
+        }
+        Ok(None)
+    }
+
+    fn original_type(&self, type_thing: &Entity) -> Entity {
+        if type_thing.is::<TypeAfterSubstitution>() {
+            type_thing.origin()
+        } else {
+            type_thing.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn public_qname(db: &Database, local_name: &str) -> QName {
+        let package = db.factory().create_package(["p"]);
+        db.factory().create_qname(&package.public_ns().unwrap(), local_name.into())
+    }
+
+    fn define_class(db: &Database, local_name: &str, base: Option<&Entity>) -> Entity {
+        let package = db.factory().create_package(["p"]);
+        let class = db.factory().create_class_type(public_qname(db, local_name), &package.public_ns().unwrap());
+        if let Some(base) = base {
+            class.set_extends_class(Some(base.clone()));
+            base.known_subclasses().push(class.clone());
+        }
+        class
+    }
+
+    /// Defines an instance method, overriding the method of
+    /// the same name in a base class, if any.
+    fn define_method(db: &Database, parent: &Entity, local_name: &str, overrides: Option<&Entity>) -> Entity {
+        let name = public_qname(db, local_name);
+        let signature = db.factory().create_function_type(vec![], db.void_type());
+        let method = db.factory().create_method_slot(&name, &signature);
+        method.set_parent(Some(parent.clone()));
+        parent.prototype(db).set(name, method.clone());
+        if let Some(overriden) = overrides {
+            method.set_overrides_method(Some(overriden.clone()));
+            overriden.overriden_by(db).push(method.clone());
+        }
+        method
+    }
+
+    #[test]
+    fn descendants_and_leaves() {
+        let db = Database::new(DatabaseOptions::default());
+        let a = define_class(&db, "A", None);
+        let b = define_class(&db, "B", Some(&a));
+        let c = define_class(&db, "C", Some(&b));
+        let d = define_class(&db, "D", Some(&a));
+        assert_eq!(ClassHierarchy(&db).all_descendants(&a), vec![b.clone(), d.clone(), c.clone()]);
+        assert_eq!(ClassHierarchy(&db).leaf_classes(&a), vec![d, c.clone()]);
+        assert_eq!(ClassHierarchy(&db).leaf_classes(&c), vec![c]);
+    }
+
+    #[test]
+    fn implementors_through_subinterfaces_and_subclasses() {
+        let db = Database::new(DatabaseOptions::default());
+        let i = db.factory().create_interface_type(public_qname(&db, "I"));
+        let j = db.factory().create_interface_type(public_qname(&db, "J"));
+        i.known_implementors().push(j.clone());
+        let a = define_class(&db, "A", None);
+        let b = define_class(&db, "B", Some(&a));
+        j.known_implementors().push(a.clone());
+        assert_eq!(ClassHierarchy(&db).all_implementors(&i), vec![a, b]);
+    }
+
+    #[test]
+    fn devirtualization() {
+        let db = Database::new(DatabaseOptions::default());
+        let a = define_class(&db, "A", None);
+        let b = define_class(&db, "B", Some(&a));
+        let a_m = define_method(&db, &a, "m", None);
+        let a_n = define_method(&db, &a, "n", None);
+        let b_m = define_method(&db, &b, "m", Some(&a_m));
+
+        assert_eq!(ClassHierarchy(&db).dispatch_targets(&a_m).unwrap(), vec![a_m.clone(), b_m.clone()]);
+        assert!(!ClassHierarchy(&db).is_monomorphic(&a_m).unwrap());
+        assert_eq!(ClassHierarchy(&db).devirtualize(&a_m).unwrap(), None);
+
+        assert_eq!(ClassHierarchy(&db).devirtualize(&a_n).unwrap(), Some(a_n));
+        assert_eq!(ClassHierarchy(&db).devirtualize(&b_m).unwrap(), Some(b_m.clone()));
+
+        a_m.set_is_final(true);
+        assert!(ClassHierarchy(&db).is_monomorphic(&a_m).unwrap());
+    }
+
+    #[test]
+    fn interface_method_dispatches_to_implementations() {
+        let db = Database::new(DatabaseOptions::default());
+        let i = db.factory().create_interface_type(public_qname(&db, "I"));
+        let i_m = define_method(&db, &i, "m", None);
+        let a = define_class(&db, "A", None);
+        let b = define_class(&db, "B", Some(&a));
+        i.known_implementors().push(a.clone());
+        let a_m = define_method(&db, &a, "m", None);
+
+        assert_eq!(ClassHierarchy(&db).dispatch_targets(&i_m).unwrap(), vec![a_m.clone()]);
+        assert_eq!(ClassHierarchy(&db).devirtualize(&i_m).unwrap(), Some(a_m.clone()));
+
+        let b_m = define_method(&db, &b, "m", Some(&a_m));
+        assert_eq!(ClassHierarchy(&db).dispatch_targets(&i_m).unwrap(), vec![a_m, b_m]);
+        assert_eq!(ClassHierarchy(&db).devirtualize(&i_m).unwrap(), None);
+    }
+}
@@ -0,0 +1,371 @@
+use crate::ns::*;
+
+/// Operation for computing the least upper bound of several static types,
+/// as required by conditional expressions and array and `Vector` literals.
+pub struct CommonSupertype<'a>(pub &'a Database);
+
+impl<'a> CommonSupertype<'a> {
+    /// Returns the common supertype of a list of types.
+    ///
+    /// Items may also be values, in which case their static type is used,
+    /// except for the `null` and `undefined` constants:
+    ///
+    /// * `null` makes the resulting type nullable.
+    /// * `undefined` and `void` result in `*`, unless all items are `void` or `undefined`,
+    ///   which results in `void`.
+    ///
+    /// The result is `*` if the list is empty or consists of `null` only,
+    /// or if any of the types is `*`.
+    pub fn common_supertype(&self, types: &[Entity]) -> Result<Entity, DeferError> {
+        let mut bases: Vec<Entity> = vec![];
+        let mut has_null = false;
+        let mut has_void = false;
+        let mut any_nullable = false;
+        let mut all_non_nullable = true;
+
+        for t in types {
+            // Defer if unresolved
+            t.defer()?;
+
+            let t = if t.is::<Value>() {
+                if t.is::<NullConstant>() {
+                    has_null = true;
+                    continue;
+                }
+                if t.is::<UndefinedConstant>() {
+                    has_void = true;
+                    continue;
+                }
+                t.static_type(self.0).defer()?
+            } else {
+                t.clone()
+            };
+
+            if t.is::<InvalidationEntity>() {
+                return Ok(t);
+            }
+            if t.is::<AnyType>() {
+                return Ok(self.0.any_type());
+            }
+            if t.is::<VoidType>() {
+                has_void = true;
+                continue;
+            }
+            if t.is::<NullableType>() {
+                any_nullable = true;
+            }
+            if !t.is::<NonNullableType>() {
+                all_non_nullable = false;
+            }
+
+            let base = t.escape_of_nullable_or_non_nullable();
+            if !bases.contains(&base) {
+                bases.push(base);
+            }
+        }
+
+        if has_void {
+            return Ok(if bases.is_empty() && !has_null { self.0.void_type() } else { self.0.any_type() });
+        }
+        if bases.is_empty() {
+            return Ok(self.0.any_type());
+        }
+
+        let r = self.common_supertype_of_bases(&bases)?;
+
+        if any_nullable || (has_null && !r.includes_null(self.0)?) {
+            return Ok(self.0.factory().create_nullable_type(&r));
+        }
+        if all_non_nullable && !has_null {
+            return Ok(self.0.factory().create_non_nullable_type(&r));
+        }
+        Ok(r)
+    }
+
+    /// Common supertype of types that are not `*`, `void`,
+    /// nullable or non-nullable.
+    fn common_supertype_of_bases(&self, bases: &[Entity]) -> Result<Entity, DeferError> {
+        let first = &bases[0];
+        if bases.len() == 1 {
+            return Ok(first.clone());
+        }
+
+        // Integer types involving a 64-bit or arbitrary-precision integer type
+        // are joined into the narrowest integer type holding all of them;
+        // other numeric types are promoted to Number.
+        let numeric_types = self.0.numeric_types()?;
+        if bases.iter().all(|t| numeric_types.contains(t)) {
+            if let Some(r) = self.common_integer_type(bases)? {
+                return Ok(r);
+            }
+            return self.0.number_type().defer();
+        }
+
+        // Tuples are joined element-wise
+        if bases.iter().all(|t| t.is::<TupleType>()) {
+            let element_count = first.element_types().length();
+            if bases.iter().all(|t| t.element_types().length() == element_count) {
+                let mut element_types: Vec<Entity> = vec![];
+                for i in 0..element_count {
+                    let list: Vec<Entity> = bases.iter().map(|t| t.element_types().get(i).unwrap()).collect();
+                    element_types.push(self.common_supertype(&list)?);
+                }
+                return Ok(self.0.factory().create_tuple_type(element_types));
+            }
+            return self.0.array_type_of_any();
+        }
+
+        // Types after substitution of the same origin are joined into the
+        // origin applied to the joined substitute types, as in
+        // `Vector.<Number>` for `Vector.<int>` and `Vector.<Number>`. If the
+        // joined substitute types do not satisfy the constraints of the
+        // origin, the ascending types are walked instead.
+        if first.is::<TypeAfterSubstitution>() {
+            let origin = first.origin();
+            if bases.iter().all(|t| t.type_after_sub_has_origin(&origin)) {
+                let mut substitute_types: SharedArray<Entity> = shared_array![];
+                for i in 0..first.substitute_types().length() {
+                    let list: Vec<Entity> = bases.iter().map(|t| t.substitute_types().get(i).unwrap()).collect();
+                    substitute_types.push(self.common_supertype(&list)?);
+                }
+                match self.0.factory().try_create_type_after_substitution(&origin, &substitute_types) {
+                    Ok(r) => return Ok(r),
+                    Err(TypeSubstitutionError::Defer) => return Err(DeferError(None)),
+                    Err(_) => {},
+                }
+            }
+        }
+
+        // If one of the types is a supertype of all others, that is the result.
+        // This covers structural subtyping of function types.
+        for t in bases {
+            if self.all_equals_or_subtype_of(bases, t)? {
+                return Ok(t.clone());
+            }
+        }
+
+        let object_type = self.0.object_type().defer()?;
+
+        // Nearest common ascending class
+        if first.is_class_type_possibly_after_sub() || first.is::<EnumType>() || first.is::<FunctionType>() {
+            for class in first.descending_class_hierarchy(self.0).collect::<Vec<_>>() {
+                // Defer if unresolved
+                class.defer()?;
+
+                if class == object_type {
+                    break;
+                }
+                if self.all_equals_or_subtype_of(bases, &class)? {
+                    return Ok(class);
+                }
+            }
+        }
+
+        // Unique most specific common interface
+        let mut candidates: Vec<Entity> = vec![];
+        for t in first.all_ascending_types(self.0) {
+            // Defer if unresolved
+            t.defer()?;
+
+            if t.is_interface_type_possibly_after_sub() && self.all_equals_or_subtype_of(bases, &t)? {
+                candidates.push(t);
+            }
+        }
+        let mut most_specific: Vec<Entity> = vec![];
+        for c in candidates.iter() {
+            let mut is_most_specific = true;
+            for c2 in candidates.iter() {
+                if c2 != c && c2.is_subtype_of(c, self.0)? {
+                    is_most_specific = false;
+                    break;
+                }
+            }
+            if is_most_specific {
+                most_specific.push(c.clone());
+            }
+        }
+        if most_specific.len() == 1 {
+            return Ok(most_specific.remove(0));
+        }
+
+        Ok(object_type)
+    }
+
+    /// Joins integer types when one of them is `long`, `ulong` or `BigInt`,
+    /// returning `None` if any type is not an integer type or if none of
+    /// them is wider than 32 bits.
+    fn common_integer_type(&self, bases: &[Entity]) -> Result<Option<Entity>, DeferError> {
+        // Integer types from narrowest to widest, with their ranges;
+        // BigInt is unbounded.
+        let integer_types: [(Entity, Option<(i128, i128)>); 5] = [
+            (self.0.int_type().defer()?, Some((i32::MIN as i128, i32::MAX as i128))),
+            (self.0.uint_type().defer()?, Some((0, u32::MAX as i128))),
+            (self.0.long_type().defer()?, Some((i64::MIN as i128, i64::MAX as i128))),
+            (self.0.ulong_type().defer()?, Some((0, u64::MAX as i128))),
+            (self.0.big_int_type().defer()?, None),
+        ];
+        let mut ranges: Vec<Option<(i128, i128)>> = vec![];
+        for t in bases {
+            let Some((_, range)) = integer_types.iter().find(|(t1, _)| t1 == t) else {
+                return Ok(None);
+            };
+            ranges.push(*range);
+        }
+        if !bases.iter().any(|t| integer_types[2..].iter().any(|(t1, _)| t1 == t)) {
+            return Ok(None);
+        }
+        for (t, range) in integer_types.iter() {
+            let holds_all = match range {
+                Some((min, max)) => ranges.iter().all(|r| r.map(|(min1, max1)| min <= &min1 && &max1 <= max).unwrap_or(false)),
+                None => true,
+            };
+            if holds_all {
+                return Ok(Some(t.clone()));
+            }
+        }
+        Ok(None)
+    }
+
+    fn all_equals_or_subtype_of(&self, types: &[Entity], possibly_ascending_type: &Entity) -> Result<bool, DeferError> {
+        for t in types {
+            if !t.is_equals_or_subtype_of(possibly_ascending_type, self.0)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn define_class(db: &Database, local_name: &str, base: Option<&Entity>) -> Entity {
+        let top_level_package = db.top_level_package();
+        let public_ns = top_level_package.public_ns().unwrap();
+        let name = db.factory().create_qname(&public_ns, local_name.into());
+        let class = db.factory().create_class_type(name.clone(), &public_ns);
+        class.set_extends_class(base.cloned());
+        top_level_package.properties(db).set(name, class.clone());
+        class
+    }
+
+    fn define_interface(db: &Database, local_name: &str) -> Entity {
+        let top_level_package = db.top_level_package();
+        let public_ns = top_level_package.public_ns().unwrap();
+        let name = db.factory().create_qname(&public_ns, local_name.into());
+        let itrfc = db.factory().create_interface_type(name.clone());
+        top_level_package.properties(db).set(name, itrfc.clone());
+        itrfc
+    }
+
+    /// Defines a generic class with a single type parameter.
+    fn define_generic_class(db: &Database, local_name: &str, constraint: Option<&Entity>) -> Entity {
+        let class = define_class(db, local_name, None);
+        let public_ns = db.top_level_package().public_ns().unwrap();
+        let type_param = db.factory().create_type_parameter_type(&db.factory().create_qname(&public_ns, "T".into()));
+        type_param.set_constraint(constraint.cloned());
+        class.set_type_params(Some(shared_array![type_param]));
+        class
+    }
+
+    /// Defines `Object`, `Boolean` and the numeric types.
+    fn define_builtins(db: &Database) -> Entity {
+        let object_type = define_class(db, "Object", None);
+        for name in ["Boolean", "Number", "int", "uint", "float", "long", "ulong", "BigInt"] {
+            define_class(db, name, Some(&object_type));
+        }
+        object_type
+    }
+
+    #[test]
+    fn joins_numeric_types() {
+        let db = Database::new(DatabaseOptions::default());
+        define_builtins(&db);
+        let cs = CommonSupertype(&db);
+        assert_eq!(cs.common_supertype(&[db.int_type(), db.number_type()]).unwrap(), db.number_type());
+        assert_eq!(cs.common_supertype(&[db.int_type(), db.uint_type()]).unwrap(), db.number_type());
+        assert_eq!(cs.common_supertype(&[db.uint_type(), db.long_type()]).unwrap(), db.long_type());
+        assert_eq!(cs.common_supertype(&[db.int_type(), db.ulong_type()]).unwrap(), db.big_int_type());
+        assert_eq!(cs.common_supertype(&[db.long_type(), db.float_type()]).unwrap(), db.number_type());
+    }
+
+    #[test]
+    fn joins_classes_and_interfaces() {
+        let db = Database::new(DatabaseOptions::default());
+        let object_type = define_builtins(&db);
+        let a = define_class(&db, "A", Some(&object_type));
+        let b = define_class(&db, "B", Some(&a));
+        let c = define_class(&db, "C", Some(&a));
+        let cs = CommonSupertype(&db);
+        assert_eq!(cs.common_supertype(&[b.clone(), c.clone()]).unwrap(), a);
+        assert_eq!(cs.common_supertype(&[b.clone(), a.clone()]).unwrap(), a);
+
+        let i = define_interface(&db, "I");
+        let d = define_class(&db, "D", Some(&object_type));
+        let e = define_class(&db, "E", Some(&object_type));
+        d.implements(&db).push(i.clone());
+        e.implements(&db).push(i.clone());
+        assert_eq!(cs.common_supertype(&[d.clone(), e]).unwrap(), i);
+        assert_eq!(cs.common_supertype(&[d, b]).unwrap(), object_type);
+    }
+
+    #[test]
+    fn joins_null_void_and_any() {
+        let db = Database::new(DatabaseOptions::default());
+        let object_type = define_builtins(&db);
+        let a = define_class(&db, "A", Some(&object_type));
+        let null = db.factory().create_null_constant(&db.any_type());
+        let undefined = db.factory().create_undefined_constant(&db.any_type());
+        let cs = CommonSupertype(&db);
+        assert_eq!(cs.common_supertype(&[]).unwrap(), db.any_type());
+        assert_eq!(cs.common_supertype(std::slice::from_ref(&null)).unwrap(), db.any_type());
+        assert_eq!(cs.common_supertype(&[a.clone(), null.clone()]).unwrap(), a);
+        assert_eq!(cs.common_supertype(&[db.int_type(), null]).unwrap(), db.factory().create_nullable_type(&db.int_type()));
+        assert_eq!(cs.common_supertype(&[db.void_type(), undefined]).unwrap(), db.void_type());
+        assert_eq!(cs.common_supertype(&[a.clone(), db.void_type()]).unwrap(), db.any_type());
+        assert_eq!(cs.common_supertype(&[a.clone(), db.any_type()]).unwrap(), db.any_type());
+
+        let non_nullable_a = db.factory().create_non_nullable_type(&a);
+        assert_eq!(cs.common_supertype(&[non_nullable_a.clone(), non_nullable_a.clone()]).unwrap(), non_nullable_a);
+        assert_eq!(cs.common_supertype(&[non_nullable_a, db.factory().create_nullable_type(&a)]).unwrap(), db.factory().create_nullable_type(&a));
+    }
+
+    #[test]
+    fn joins_types_after_substitution_of_the_same_origin() {
+        let db = Database::new(DatabaseOptions::default());
+        let object_type = define_builtins(&db);
+        let a = define_class(&db, "A", Some(&object_type));
+        let b = define_class(&db, "B", Some(&a));
+        let c = define_class(&db, "C", Some(&a));
+        let boxed = define_generic_class(&db, "Box", None);
+        let of = |t: &Entity| db.factory().create_type_after_substitution(&boxed, &shared_array![t.clone()]);
+        let cs = CommonSupertype(&db);
+        assert_eq!(cs.common_supertype(&[of(&db.int_type()), of(&db.int_type())]).unwrap(), of(&db.int_type()));
+        assert_eq!(cs.common_supertype(&[of(&db.int_type()), of(&db.number_type())]).unwrap(), of(&db.number_type()));
+        assert_eq!(cs.common_supertype(&[of(&b), of(&c)]).unwrap(), of(&a));
+
+        // Another origin: walk ascending types
+        let other = define_generic_class(&db, "Other", None);
+        let other_b = db.factory().create_type_after_substitution(&other, &shared_array![b.clone()]);
+        assert_eq!(cs.common_supertype(&[of(&b), other_b]).unwrap(), object_type);
+    }
+
+    #[test]
+    fn walks_ascending_types_when_joined_substitute_types_are_invalid() {
+        let db = Database::new(DatabaseOptions::default());
+        let object_type = define_builtins(&db);
+        let i = define_interface(&db, "I");
+        let a = define_class(&db, "A", Some(&object_type));
+        let b = define_class(&db, "B", Some(&a));
+        let c = define_class(&db, "C", Some(&a));
+        b.implements(&db).push(i.clone());
+        c.implements(&db).push(i.clone());
+
+        // Box.<T extends I>: A does not implement I
+        let boxed = define_generic_class(&db, "Box", Some(&i));
+        let box_b = db.factory().create_type_after_substitution(&boxed, &shared_array![b]);
+        let box_c = db.factory().create_type_after_substitution(&boxed, &shared_array![c]);
+        assert_eq!(CommonSupertype(&db).common_supertype(&[box_b, box_c]).unwrap(), object_type);
+    }
+}
@@ -0,0 +1,354 @@
+use crate::ns::*;
+
+/// Kind of a completion candidate.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CompletionKind {
+    Package,
+    Class,
+    Interface,
+    Enum,
+    /// A type other than a class, interface or enum, such as a type parameter.
+    Type,
+    Variable,
+    Constant,
+    /// A virtual slot (getter and/or setter).
+    Property,
+    Method,
+    Namespace,
+}
+
+/// Accessibility of a completion candidate, given by the namespace
+/// qualifying its name.
+#[derive(Clone, PartialEq, Debug)]
+pub enum CompletionAccessibility {
+    Public,
+    Private,
+    Protected,
+    Internal,
+    StaticProtected,
+    /// A user or explicit namespace.
+    Namespace(Entity),
+}
+
+/// A completion candidate.
+#[derive(Clone, Debug)]
+pub struct CompletionCandidate {
+    pub name: String,
+    /// The definition, with aliases resolved.
+    pub definition: Entity,
+    pub kind: CompletionKind,
+    /// The type of a variable or virtual slot, the signature
+    /// of a method, or the property static type of other definitions.
+    /// `None` if unresolved.
+    pub static_type: Option<Entity>,
+    /// Whether the definition has `[Deprecated]` meta-data or a `@deprecated` ASDoc tag.
+    pub deprecated: bool,
+    pub accessibility: CompletionAccessibility,
+}
+
+/// Code completion over scopes and types.
+///
+/// Completion at a scope lists the definitions visible through the scope chain,
+/// from innermost to outermost: scope locals, `with` object members, `this` members,
+/// class statics, package definitions and imported definitions. A local name found
+/// in an inner scope hides the same local name in outer scopes.
+///
+/// Completion over a base lists the members of a package, the static members
+/// of a class or enum, or the instance members of a value. Overriden members
+/// and interface members implemented by a class appear once.
+///
+/// In both cases, definitions are filtered by the open namespace set of the scope
+/// chain, as in `PropertyLookup`. Nothing is marked as used nor recorded as referenced.
+/// Unresolved definitions, such as those of a broken import, are left out
+/// instead of failing the whole completion.
+pub struct Completion<'a>(pub &'a Database);
+
+impl<'a> Completion<'a> {
+    /// Returns the completion candidates at a scope, or the members
+    /// of a base package, type or value accessible from the scope.
+    pub fn complete(&self, scope: &Entity, base: Option<&Entity>) -> Result<Vec<CompletionCandidate>, DeferError> {
+        if let Some(base) = base {
+            self.complete_members(base, scope)
+        } else {
+            self.complete_in_scope(scope)
+        }
+    }
+
+    /// Returns the definitions visible from a scope, innermost scope first,
+    /// then by name.
+    pub fn complete_in_scope(&self, scope: &Entity) -> Result<Vec<CompletionCandidate>, DeferError> {
+        let open_ns_set = scope.concat_open_ns_set_of_scope_chain();
+        let mut r: Vec<CompletionCandidate> = vec![];
+        let mut hidden_names: Vec<String> = vec![];
+        let mut scope = Some(scope.clone());
+        while let Some(scope_1) = scope {
+            let mut definitions: Vec<Entity> = vec![];
+
+            // A "with" scope whose object is of a dynamic type contributes nothing known.
+            if scope_1.is::<WithScope>() {
+                let obj_static_type = scope_1.object().static_type(self.0).defer()?.escape_of_non_nullable();
+                if ![self.0.any_type(), self.0.xml_type(), self.0.xml_list_type()].contains(&obj_static_type) {
+                    definitions.extend(self.instance_definitions(&obj_static_type, &open_ns_set)?);
+                }
+            }
+
+            definitions.extend(self.accessible_names(&scope_1.properties(self.0), &open_ns_set));
+
+            if scope_1.is::<Activation>() {
+                if let Some(this) = scope_1.this() {
+                    let this_type = this.static_type(self.0).defer()?.escape_of_non_nullable();
+                    definitions.extend(self.instance_definitions(&this_type, &open_ns_set)?);
+                }
+            }
+
+            if scope_1.is::<ClassScope>() || scope_1.is::<EnumScope>() {
+                definitions.extend(scope_1.class().available_static_definitions(self.0, &open_ns_set)?);
+            }
+
+            if scope_1.is::<PackageScope>() {
+                definitions.extend(self.package_definitions(&scope_1.package(), &open_ns_set)?);
+            }
+
+            for import in scope_1.import_list().iter() {
+                if import.is::<PackageWildcardImport>() {
+                    definitions.extend(self.package_definitions(&import.package(), &open_ns_set)?);
+                } else if import.is::<PackageRecursiveImport>() {
+                    definitions.extend(self.package_definitions_recursive(&import.package(), &open_ns_set)?);
+                } else if let Ok(property) = import.try_property() {
+                    if property.is::<UnresolvedEntity>() || property.is::<InvalidationEntity>() {
+                        continue;
+                    }
+                    if property.try_name().map(|name| name.accessible_from_ns_set(self.0, &open_ns_set)).unwrap_or(false) {
+                        definitions.push(property);
+                    }
+                }
+            }
+
+            let scope_candidates = self.candidates(definitions);
+            let scope_candidates: Vec<CompletionCandidate> = scope_candidates.into_iter().filter(|c| !hidden_names.contains(&c.name)).collect();
+            for candidate in scope_candidates {
+                if !r.iter().any(|c| c.name == candidate.name && c.accessibility == candidate.accessibility) {
+                    r.push(candidate);
+                }
+            }
+            hidden_names = r.iter().map(|c| c.name.clone()).collect();
+
+            scope = scope_1.parent();
+        }
+        Ok(r)
+    }
+
+    /// Returns the members of a base package, type or value
+    /// accessible from a scope, ordered by name.
+    pub fn complete_members(&self, base: &Entity, scope: &Entity) -> Result<Vec<CompletionCandidate>, DeferError> {
+        let open_ns_set = scope.concat_open_ns_set_of_scope_chain();
+
+        if base.is::<Package>() {
+            let mut r = self.candidates(self.package_definitions(base, &open_ns_set)?);
+            for (name, subpackage) in base.subpackages().borrow().iter() {
+                if !r.iter().any(|c| &c.name == name) {
+                    r.push(CompletionCandidate {
+                        name: name.clone(),
+                        definition: subpackage.clone(),
+                        kind: CompletionKind::Package,
+                        static_type: None,
+                        deprecated: false,
+                        accessibility: CompletionAccessibility::Public,
+                    });
+                }
+            }
+            r.sort_by(|a, b| a.name.cmp(&b.name));
+            return Ok(r);
+        }
+
+        let base = if base.is::<TypeConstant>() {
+            base.referenced_type()
+        } else {
+            base.clone()
+        };
+
+        if base.is::<Type>() {
+            return Ok(self.candidates(base.available_static_definitions(self.0, &open_ns_set)?));
+        }
+
+        if base.is::<Value>() {
+            let base_type = base.static_type(self.0).defer()?.escape_of_non_nullable();
+            return Ok(self.candidates(self.instance_definitions(&base_type, &open_ns_set)?));
+        }
+
+        Ok(vec![])
+    }
+
+    fn instance_definitions(&self, base_type: &Entity, open_ns_set: &SharedArray<Entity>) -> Result<Vec<Entity>, DeferError> {
+        // Resolve members of a type parameter through its constraint.
+        let mut base_type = base_type.clone();
+        let mut visited_type_params: Vec<Entity> = vec![];
+        while base_type.is::<TypeParameterType>() && !visited_type_params.contains(&base_type) {
+            let Some(constraint) = base_type.constraint() else {
+                break;
+            };
+            visited_type_params.push(base_type.clone());
+            base_type = constraint.defer()?.escape_of_nullable_or_non_nullable();
+        }
+        if base_type == self.0.any_type() {
+            return Ok(vec![]);
+        }
+        base_type.available_prototype_definitions(self.0, open_ns_set)
+    }
+
+    fn package_definitions(&self, package: &Entity, open_ns_set: &SharedArray<Entity>) -> Result<Vec<Entity>, DeferError> {
+        let mut r = package.available_definitions_in_package(self.0, open_ns_set)?;
+        for concatp in package.package_concats().iter() {
+            r.extend(self.package_definitions(&concatp, open_ns_set)?);
+        }
+        // Vector is not a property of the top-level package.
+        if package == &self.0.top_level_package() {
+            let vector_type = self.0.vector_type();
+            if !vector_type.is::<UnresolvedEntity>() {
+                r.push(vector_type);
+            }
+        }
+        Ok(r)
+    }
+
+    fn package_definitions_recursive(&self, package: &Entity, open_ns_set: &SharedArray<Entity>) -> Result<Vec<Entity>, DeferError> {
+        let mut r = self.package_definitions(package, open_ns_set)?;
+        for subpackage in package.subpackages().borrow().values() {
+            r.extend(self.package_definitions_recursive(subpackage, open_ns_set)?);
+        }
+        Ok(r)
+    }
+
+    fn accessible_names(&self, names: &Names, open_ns_set: &SharedArray<Entity>) -> Vec<Entity> {
+        names.borrow().iter()
+            .filter(|(name, _)| name.accessible_from_ns_set(self.0, open_ns_set))
+            .map(|(_, definition)| definition.clone())
+            .collect()
+    }
+
+    /// Converts definitions into candidates ordered by name, keeping the first
+    /// definition for each name, which is the most derived in a class hierarchy.
+    /// Unresolved definitions, such as aliases to unresolved imports, are skipped.
+    fn candidates(&self, definitions: Vec<Entity>) -> Vec<CompletionCandidate> {
+        let mut r: Vec<CompletionCandidate> = vec![];
+        for definition in definitions {
+            if definition.is::<InvalidationEntity>() {
+                continue;
+            }
+            let Ok(name) = definition.try_name() else {
+                continue;
+            };
+            let local_name = name.local_name();
+            let accessibility = Self::accessibility(&name.namespace());
+            if r.iter().any(|c| c.name == local_name && c.accessibility == accessibility) {
+                continue;
+            }
+            let definition = definition.resolve_alias();
+            if definition.is::<UnresolvedEntity>() {
+                continue;
+            }
+            r.push(CompletionCandidate {
+                name: local_name,
+                kind: self.kind(&definition),
+                static_type: self.static_type(&definition),
+                deprecated: self.is_deprecated(&definition),
+                accessibility,
+                definition,
+            });
+        }
+        r.sort_by(|a, b| a.name.cmp(&b.name));
+        r
+    }
+
+    fn kind(&self, definition: &Entity) -> CompletionKind {
+        if definition.is::<Package>() {
+            CompletionKind::Package
+        } else if definition.is::<Namespace>() {
+            CompletionKind::Namespace
+        } else if definition.is::<VariableSlot>() {
+            if definition.read_only(self.0) {
+                CompletionKind::Constant
+            } else {
+                CompletionKind::Variable
+            }
+        } else if definition.is::<VirtualSlot>() {
+            CompletionKind::Property
+        } else if definition.is::<MethodSlot>() {
+            CompletionKind::Method
+        } else if definition.is_interface_type_possibly_after_sub() {
+            CompletionKind::Interface
+        } else if definition.is::<EnumType>() {
+            CompletionKind::Enum
+        } else if definition.is_class_or_equivalent() {
+            CompletionKind::Class
+        } else {
+            CompletionKind::Type
+        }
+    }
+
+    fn static_type(&self, definition: &Entity) -> Option<Entity> {
+        let r = if definition.is::<MethodSlot>() {
+            definition.try_signature(self.0).ok()?
+        } else if definition.is::<VariableSlot>() || definition.is::<VirtualSlot>() {
+            definition.try_static_type(self.0).ok()?
+        } else {
+            definition.try_property_static_type(self.0).ok()?
+        };
+        if r.is::<UnresolvedEntity>() { None } else { Some(r) }
+    }
+
+    fn is_deprecated(&self, definition: &Entity) -> bool {
+        let definition = if definition.is_entity_after_substitution() {
+            definition.origin()
+        } else {
+            definition.clone()
+        };
+        if definition.is::<VirtualSlot>() {
+            return definition.getter(self.0).map(|m| self.is_deprecated(&m)).unwrap_or(false)
+                || definition.setter(self.0).map(|m| self.is_deprecated(&m)).unwrap_or(false);
+        }
+        if let Ok(metadata) = definition.try_metadata() {
+            if metadata.iter().any(|m| m.name.0 == "Deprecated") {
+                return true;
+            }
+        }
+        definition.asdoc().map(|asdoc| asdoc.tags.iter().any(|(tag, _)| matches!(tag, AsdocTag::Deprecated { .. }))).unwrap_or(false)
+    }
+
+    fn accessibility(ns: &Entity) -> CompletionAccessibility {
+        match ns.system_ns_kind() {
+            Some(SystemNamespaceKind::Public) => CompletionAccessibility::Public,
+            Some(SystemNamespaceKind::Private) => CompletionAccessibility::Private,
+            Some(SystemNamespaceKind::Protected) => CompletionAccessibility::Protected,
+            Some(SystemNamespaceKind::Internal) => CompletionAccessibility::Internal,
+            Some(SystemNamespaceKind::StaticProtected) => CompletionAccessibility::StaticProtected,
+            None => CompletionAccessibility::Namespace(ns.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_unresolved_imports() {
+        let db = Database::new(DatabaseOptions::default());
+        let p = db.factory().create_package(["p"]);
+        let public_ns = p.public_ns().unwrap();
+        let x_name = db.factory().create_qname(&public_ns, "x".into());
+        let x = db.factory().create_variable_slot(&x_name, false, &db.any_type());
+        p.properties(&db).set(x_name, x.clone());
+        let broken_alias = db.factory().create_alias(db.factory().create_qname(&public_ns, "y".into()), db.unresolved_entity());
+
+        let scope = db.factory().create_package_scope(&db.factory().create_package(["q"]));
+        scope.import_list().push(db.factory().create_package_property_import(&db.unresolved_entity(), None));
+        scope.import_list().push(db.factory().create_package_property_import(&broken_alias, None));
+        scope.import_list().push(db.factory().create_package_property_import(&x, None));
+
+        let candidates = Completion(&db).complete_in_scope(&scope).unwrap();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].name, "x");
+        assert_eq!(candidates[0].definition, x);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::ns::*;
+
+/// Evaluation of configuration constants for conditional compilation.
+///
+/// Configuration constants are defined in `Database::config_constants()` with keys
+/// in the form `NS::name` (as in `-define=CONFIG::debug,true`) and values consisting of
+/// expression source text. Each value is parsed and evaluated in
+/// `Database::const_eval_scope()`; it may refer to other configuration constants.
+/// Results are cached in `Database::config_constants_result()`.
+pub struct ConfigConstants<'a>(pub &'a Database);
+
+impl<'a> ConfigConstants<'a> {
+    /// Evaluates a configuration constant, returning `None` if it is not defined.
+    pub fn evaluate(&self, name: &str) -> Result<Option<Entity>, ConstantEvaluationError> {
+        if let Some(r) = self.0.config_constants_result().get(&name.to_owned()) {
+            return Ok(Some(r));
+        }
+        let Some(source) = self.0.config_constants().get(&name.to_owned()) else {
+            return Ok(None);
+        };
+        if self.0.config_constants_in_evaluation.borrow().iter().any(|n| n == name) {
+            return Err(ConstantEvaluationError::CyclicConfigConstant { name: name.to_owned() });
+        }
+
+        let cu = CompilationUnit::new(None, source);
+        let exp = ParserFacade(&cu, ParserOptions::default()).parse_expression();
+        if cu.invalidated() {
+            return Err(ConstantEvaluationError::InvalidConfigConstant { name: name.to_owned() });
+        }
+
+        self.0.config_constants_in_evaluation.borrow_mut().push(name.to_owned());
+        let r = ConstantEvaluation(self.0).evaluate(&exp, None);
+        self.0.config_constants_in_evaluation.borrow_mut().pop();
+
+        let r = r?;
+        self.0.config_constants_result().set(name.to_owned(), r.clone());
+        Ok(Some(r))
+    }
+
+    /// Evaluates every configuration constant, returning the errors
+    /// of those that failed, ordered by name.
+    pub fn evaluate_all(&self) -> Vec<(String, ConstantEvaluationError)> {
+        let mut names: Vec<String> = self.0.config_constants().borrow().keys().cloned().collect();
+        names.sort();
+        let mut errors = vec![];
+        for name in names {
+            if let Err(error) = self.evaluate(&name) {
+                errors.push((name, error));
+            }
+        }
+        errors
+    }
+
+    /// Indicates whether a `CONFIG::x` guarded directive is included,
+    /// that is, whether the configuration constant converts to `true`.
+    pub fn is_included(&self, directive: &ConfigurationDirective) -> Result<bool, ConstantEvaluationError> {
+        let name = format!("{}::{}", directive.namespace.0, directive.constant_name.0);
+        let Some(constant) = self.evaluate(&name)? else {
+            return Err(ConstantEvaluationError::UndefinedConfigConstant { name, location: directive.constant_name.1.clone() });
+        };
+        Ok(ConstantEvaluation(self.0).to_boolean(&constant))
+    }
+
+    /// Indicates whether a directive is included. Only configuration
+    /// directives may be excluded.
+    pub fn is_directive_included(&self, directive: &Rc<Directive>) -> Result<bool, ConstantEvaluationError> {
+        match directive.as_ref() {
+            Directive::ConfigurationDirective(d) => self.is_included(d),
+            _ => Ok(true),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+    use crate::semantics::test_fixtures::*;
+
+    fn database(constants: &[(&str, &str)]) -> Database {
+        let db = Database::new(DatabaseOptions::default());
+        define_builtins(&db);
+        for (name, source) in constants {
+            db.config_constants().set((*name).to_owned(), (*source).to_owned());
+        }
+        db
+    }
+
+    #[test]
+    fn evaluates_references_to_other_constants() {
+        let db = database(&[
+            ("CONFIG::a", "CONFIG::b * 2"),
+            ("CONFIG::b", "CONFIG::c + 1"),
+            ("CONFIG::c", "20"),
+            ("CONFIG::debug", "!CONFIG::release"),
+            ("CONFIG::release", "false"),
+        ]);
+        let a = ConfigConstants(&db).evaluate("CONFIG::a").unwrap().unwrap();
+        assert_eq!(a.number_value().force_double(), 42.0);
+        assert_eq!(db.config_constants_result().get(&"CONFIG::b".to_owned()).unwrap().number_value().force_double(), 21.0);
+        assert!(ConfigConstants(&db).evaluate("CONFIG::debug").unwrap().unwrap().boolean_value());
+        assert!(ConfigConstants(&db).evaluate("CONFIG::none").unwrap().is_none());
+        assert!(ConfigConstants(&db).evaluate_all().is_empty());
+    }
+
+    #[test]
+    fn detects_cycles() {
+        let db = database(&[
+            ("CONFIG::a", "CONFIG::b"),
+            ("CONFIG::b", "CONFIG::c && true"),
+            ("CONFIG::c", "CONFIG::a"),
+            ("CONFIG::d", "CONFIG::d + 1"),
+            ("CONFIG::e", "CONFIG::f"),
+            ("CONFIG::f", "1"),
+        ]);
+        let cyclic = |r: Result<Option<Entity>, ConstantEvaluationError>, expected: &str| {
+            matches!(r, Err(ConstantEvaluationError::CyclicConfigConstant { name }) if name == expected)
+        };
+        assert!(cyclic(ConfigConstants(&db).evaluate("CONFIG::a"), "CONFIG::a"));
+        assert!(cyclic(ConfigConstants(&db).evaluate("CONFIG::b"), "CONFIG::b"));
+        assert!(cyclic(ConfigConstants(&db).evaluate("CONFIG::d"), "CONFIG::d"));
+        assert!(db.config_constants_in_evaluation.borrow().is_empty());
+        assert!(db.config_constants_result().get(&"CONFIG::a".to_owned()).is_none());
+
+        let errors = ConfigConstants(&db).evaluate_all();
+        let names: Vec<&str> = errors.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["CONFIG::a", "CONFIG::b", "CONFIG::c", "CONFIG::d"]);
+        assert!(errors.iter().all(|(_, e)| matches!(e, ConstantEvaluationError::CyclicConfigConstant { .. })));
+        assert_eq!(ConfigConstants(&db).evaluate("CONFIG::e").unwrap().unwrap().number_value().force_double(), 1.0);
+    }
+
+    #[test]
+    fn reports_undefined_and_invalid_constants() {
+        let db = database(&[
+            ("CONFIG::a", "CONFIG::undefined || true"),
+            ("CONFIG::b", "1 +"),
+        ]);
+        assert!(matches!(ConfigConstants(&db).evaluate("CONFIG::a"),
+            Err(ConstantEvaluationError::UndefinedConfigConstant { name, .. }) if name == "CONFIG::undefined"));
+        assert!(matches!(ConfigConstants(&db).evaluate("CONFIG::b"),
+            Err(ConstantEvaluationError::InvalidConfigConstant { name }) if name == "CONFIG::b"));
+    }
+}
@@ -0,0 +1,719 @@
+use crate::ns::*;
+use num_traits::{ToPrimitive, Zero};
+
+/// Compile-time constant folding over expressions.
+///
+/// The result of a successful evaluation is one of `NumberConstant`,
+/// `StringConstant`, `BooleanConstant`, `NullConstant`, `UndefinedConstant` or
+/// `NamespaceConstant`. Identifiers and member expressions are constant if
+/// they resolve to a constant or to a reference to a slot with a constant value,
+/// either through the node mapping or, for nodes with no mapping, through
+/// a lookup in `Database::const_eval_scope()`. `CONFIG::name` references are
+/// resolved through [`ConfigConstants`].
+pub struct ConstantEvaluation<'a>(pub &'a Database);
+
+/// Maximum size of a folded `BigInt` power, in bits.
+const MAX_BIG_INT_POWER_BITS: u64 = 1 << 20;
+
+impl<'a> ConstantEvaluation<'a> {
+    /// Evaluates an expression. Numeric literals assume the context type if it is
+    /// a numeric type, and `Number` otherwise (or `float` for the `f` suffix).
+    pub fn evaluate(&self, exp: &Rc<Expression>, context_type: Option<&Entity>) -> Result<Entity, ConstantEvaluationError> {
+        let not_constant = || ConstantEvaluationError::NotConstant { location: exp.location() };
+        match exp.as_ref() {
+            Expression::Paren(e) => self.evaluate(&e.expression, context_type),
+            Expression::NullLiteral(_) => {
+                let t = match context_type {
+                    Some(t) if t.includes_null(self.0).map_err(|_| ConstantEvaluationError::Defer)? => t.clone(),
+                    _ => self.0.any_type(),
+                };
+                Ok(self.0.factory().create_null_constant(&t))
+            },
+            Expression::BooleanLiteral(e) => self.boolean(e.value),
+            Expression::StringLiteral(e) => self.string(e.value.clone()),
+            Expression::NumericLiteral(e) => self.numeric_literal(e, false, context_type),
+            Expression::QualifiedIdentifier(id) => self.identifier(exp, id),
+            Expression::Member(_) => {
+                self.referenced_constant(exp)?.ok_or_else(not_constant)
+            },
+            Expression::Sequence(e) => {
+                self.evaluate(&e.left, None)?;
+                self.evaluate(&e.right, context_type)
+            },
+            Expression::Conditional(e) => {
+                let test = self.evaluate(&e.test, None)?;
+                if self.to_boolean(&test) {
+                    self.evaluate(&e.consequent, context_type)
+                } else {
+                    self.evaluate(&e.alternative, context_type)
+                }
+            },
+            Expression::Unary(e) => self.unary(exp, e.operator, &e.expression, context_type),
+            Expression::Binary(e) => self.binary(exp, e.operator, &e.left, &e.right, context_type),
+            _ => Err(not_constant()),
+        }
+    }
+
+    /// Converts a constant to a Boolean using the `ToBoolean` semantics.
+    pub fn to_boolean(&self, constant: &Entity) -> bool {
+        if constant.is::<BooleanConstant>() {
+            constant.boolean_value()
+        } else if constant.is::<NumberConstant>() {
+            let v = constant.number_value();
+            !(v.is_zero() || v.is_nan())
+        } else if constant.is::<StringConstant>() {
+            !constant.string_value().is_empty()
+        } else {
+            !(constant.is::<NullConstant>() || constant.is::<UndefinedConstant>())
+        }
+    }
+
+    /// Converts a constant to a double-precision floating point using the
+    /// `ToNumber` semantics. Returns `None` for namespace constants.
+    pub fn to_double(&self, constant: &Entity) -> Option<f64> {
+        if constant.is::<NumberConstant>() {
+            Some(constant.number_value().force_double())
+        } else if constant.is::<BooleanConstant>() {
+            Some(if constant.boolean_value() { 1.0 } else { 0.0 })
+        } else if constant.is::<StringConstant>() {
+            Some(Number::from_string(&constant.string_value()).force_double())
+        } else if constant.is::<NullConstant>() {
+            Some(0.0)
+        } else if constant.is::<UndefinedConstant>() {
+            Some(f64::NAN)
+        } else {
+            None
+        }
+    }
+
+    /// Converts a constant to a string using the `ToString` semantics.
+    /// Returns `None` for namespace constants.
+    pub fn to_string(&self, constant: &Entity) -> Option<String> {
+        if constant.is::<StringConstant>() {
+            Some(constant.string_value())
+        } else if constant.is::<NumberConstant>() {
+            Some(match constant.number_value() {
+                Number::Int(v) => v.to_string(),
+                Number::Uint(v) => v.to_string(),
+                Number::Long(v) => v.to_string(),
+                Number::Ulong(v) => v.to_string(),
+                Number::Float(v) => double_to_string(v as f64),
+                Number::Number(v) => double_to_string(v),
+                Number::BigInt(v) => v.to_string(),
+            })
+        } else if constant.is::<BooleanConstant>() {
+            Some(constant.boolean_value().to_string())
+        } else if constant.is::<NullConstant>() {
+            Some("null".into())
+        } else if constant.is::<UndefinedConstant>() {
+            Some("undefined".into())
+        } else {
+            None
+        }
+    }
+
+    fn boolean(&self, value: bool) -> Result<Entity, ConstantEvaluationError> {
+        let boolean_type = self.0.boolean_type().defer().map_err(|_| ConstantEvaluationError::Defer)?;
+        Ok(self.0.factory().create_boolean_constant(value, &boolean_type))
+    }
+
+    fn string(&self, value: String) -> Result<Entity, ConstantEvaluationError> {
+        let string_type = self.0.string_type().defer().map_err(|_| ConstantEvaluationError::Defer)?;
+        Ok(self.0.factory().create_string_constant(value, &string_type))
+    }
+
+    fn number(&self, value: Number) -> Result<Entity, ConstantEvaluationError> {
+        let t = match value {
+            Number::Number(_) => self.0.number_type(),
+            Number::Float(_) => self.0.float_type(),
+            Number::Int(_) => self.0.int_type(),
+            Number::Uint(_) => self.0.uint_type(),
+            Number::Long(_) => self.0.long_type(),
+            Number::Ulong(_) => self.0.ulong_type(),
+            Number::BigInt(_) => self.0.big_int_type(),
+        };
+        let t = t.defer().map_err(|_| ConstantEvaluationError::Defer)?;
+        Ok(self.0.factory().create_number_constant(value, &t))
+    }
+
+    fn undefined(&self) -> Entity {
+        self.0.factory().create_undefined_constant(&self.0.void_type())
+    }
+
+    fn numeric_literal(&self, literal: &NumericLiteral, negative: bool, context_type: Option<&Entity>) -> Result<Entity, ConstantEvaluationError> {
+        let not_constant = || ConstantEvaluationError::NotConstant { location: literal.location.clone() };
+        let number_type = self.0.number_type().defer().map_err(|_| ConstantEvaluationError::Defer)?;
+        let numeric_types = self.0.numeric_types().map_err(|_| ConstantEvaluationError::Defer)?;
+        let t = if literal.suffix == NumberSuffix::F {
+            self.0.float_type().defer().map_err(|_| ConstantEvaluationError::Defer)?
+        } else {
+            context_type
+                .map(|t| t.escape_of_nullable_or_non_nullable())
+                .filter(|t| numeric_types.contains(t))
+                .unwrap_or(number_type.clone())
+        };
+        let value = if t == self.0.float_type() {
+            Number::Float(literal.parse_float(negative).map_err(|_| not_constant())?)
+        } else if t == self.0.int_type() {
+            literal.parse_int(negative).map(Number::Int).ok()
+                .unwrap_or(Number::Number(literal.parse_double(negative).map_err(|_| not_constant())?))
+        } else if t == self.0.uint_type() && !negative {
+            literal.parse_uint().map(Number::Uint).ok()
+                .unwrap_or(Number::Number(literal.parse_double(negative).map_err(|_| not_constant())?))
+        } else if t == self.0.long_type() {
+            literal.parse_long(negative).map(Number::Long).ok()
+                .unwrap_or(Number::Number(literal.parse_double(negative).map_err(|_| not_constant())?))
+        } else if t == self.0.ulong_type() && !negative {
+            literal.parse_big_int(false).ok().and_then(|v| v.to_u64()).map(Number::Ulong)
+                .unwrap_or(Number::Number(literal.parse_double(negative).map_err(|_| not_constant())?))
+        } else {
+            Number::Number(literal.parse_double(negative).map_err(|_| not_constant())?)
+        };
+        self.number(value)
+    }
+
+    /// Returns the constant an identifier or member expression refers to, if any.
+    fn referenced_constant(&self, exp: &Rc<Expression>) -> Result<Option<Entity>, ConstantEvaluationError> {
+        let Some(r) = self.resolve(exp)? else {
+            return Ok(None);
+        };
+        if r.is::<InvalidationEntity>() {
+            return Ok(None);
+        }
+        if r.is::<Constant>() {
+            return Ok(Some(r));
+        }
+        let slot = if r.is::<FixtureReferenceValue>() { r.property() } else { r };
+        if slot.is::<VariableSlot>() && slot.read_only(self.0) {
+            if let Some(constant) = slot.var_constant() {
+                return Ok(Some(constant));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Resolves an identifier or member expression through the node mapping,
+    /// or, if it has no mapping, through a lookup in `Database::const_eval_scope()`.
+    fn resolve(&self, exp: &Rc<Expression>) -> Result<Option<Entity>, ConstantEvaluationError> {
+        if let Some(r) = self.0.node_mapping().get(exp) {
+            return Ok(Some(r));
+        }
+        let scope = self.0.const_eval_scope();
+        let r = match exp.as_ref() {
+            Expression::Paren(e) => return self.resolve(&e.expression),
+            Expression::QualifiedIdentifier(id) => {
+                let Some((name, _)) = id.to_identifier_name() else {
+                    return Ok(None);
+                };
+                PropertyLookup(self.0).lookup_in_scope_chain(&scope, None, &PropertyLookupKey::LocalName(name))
+            },
+            Expression::Member(e) => {
+                let Some(base) = self.resolve(&e.base)? else {
+                    return Ok(None);
+                };
+                let Some((name, _)) = e.identifier.to_identifier_name() else {
+                    return Ok(None);
+                };
+                let open_ns_set = scope.concat_open_ns_set_of_scope_chain();
+                PropertyLookup(self.0).lookup_in_object(&base, &open_ns_set, None, &PropertyLookupKey::LocalName(name), false)
+            },
+            _ => return Ok(None),
+        };
+        match r {
+            Ok(r) => Ok(r),
+            Err(PropertyLookupError::Defer) => Err(ConstantEvaluationError::Defer),
+            Err(_) => Ok(None),
+        }
+    }
+
+    fn identifier(&self, exp: &Rc<Expression>, id: &QualifiedIdentifier) -> Result<Entity, ConstantEvaluationError> {
+        let not_constant = || ConstantEvaluationError::NotConstant { location: exp.location() };
+
+        // CONFIG::name
+        if let (Some(q), QualifiedIdentifierIdentifier::Id((name, _))) = (id.qualifier.as_ref(), &id.id) {
+            if let (Some((q, _)), false) = (q.to_identifier_name(), id.attribute) {
+                let key = format!("{q}::{name}");
+                if let Some(constant) = ConfigConstants(self.0).evaluate(&key)? {
+                    return Ok(constant);
+                }
+                if let Some(constant) = self.referenced_constant(exp)? {
+                    return Ok(constant);
+                }
+                return Err(ConstantEvaluationError::UndefinedConfigConstant { name: key, location: exp.location() });
+            }
+        }
+
+        if let Some(constant) = self.referenced_constant(exp)? {
+            return Ok(constant);
+        }
+        match id.to_identifier_name().map(|(name, _)| name).as_deref() {
+            Some("undefined") => Ok(self.undefined()),
+            Some("NaN") => self.number(Number::Number(f64::NAN)),
+            Some("Infinity") => self.number(Number::Number(f64::INFINITY)),
+            _ => Err(not_constant()),
+        }
+    }
+
+    fn unary(&self, exp: &Rc<Expression>, operator: Operator, operand: &Rc<Expression>, context_type: Option<&Entity>) -> Result<Entity, ConstantEvaluationError> {
+        let not_constant = || ConstantEvaluationError::NotConstant { location: exp.location() };
+
+        // Negative numeric literals are parsed as a whole, so that
+        // for example `-2147483648` fits into `int`.
+        if operator == Operator::Negative {
+            if let Expression::NumericLiteral(literal) = operand.as_ref() {
+                return self.numeric_literal(literal, true, context_type);
+            }
+        }
+
+        let v = self.evaluate(operand, if operator == Operator::LogicalNot { None } else { context_type })?;
+        match operator {
+            Operator::LogicalNot => self.boolean(!self.to_boolean(&v)),
+            Operator::Void => Ok(self.undefined()),
+            Operator::Typeof => self.string(if v.is::<NumberConstant>() {
+                "number"
+            } else if v.is::<StringConstant>() {
+                "string"
+            } else if v.is::<BooleanConstant>() {
+                "boolean"
+            } else if v.is::<UndefinedConstant>() {
+                "undefined"
+            } else {
+                "object"
+            }.into()),
+            Operator::Positive => {
+                if v.is::<NumberConstant>() {
+                    return Ok(v);
+                }
+                self.number(Number::Number(self.to_double(&v).ok_or_else(not_constant)?))
+            },
+            Operator::Negative => {
+                if v.is::<NumberConstant>() {
+                    return self.number(match v.number_value() {
+                        Number::Int(v) => v.checked_neg().map(Number::Int).unwrap_or(Number::Number(-(v as f64))),
+                        Number::Uint(v) => Number::Number(-(v as f64)),
+                        Number::Ulong(v) => Number::Ulong(v.wrapping_neg()),
+                        v => -v,
+                    });
+                }
+                self.number(Number::Number(-self.to_double(&v).ok_or_else(not_constant)?))
+            },
+            Operator::BitwiseNot => {
+                if let Some(v @ (Number::Long(_) | Number::Ulong(_) | Number::BigInt(_))) = self.to_numeric(&v) {
+                    return self.number(v.bitwise_not());
+                }
+                let v = Number::Number(self.to_double(&v).ok_or_else(not_constant)?);
+                self.number(Number::Int(v.force_int()).bitwise_not())
+            },
+            _ => Err(not_constant()),
+        }
+    }
+
+    fn binary(&self, exp: &Rc<Expression>, operator: Operator, left: &Rc<Expression>, right: &Rc<Expression>, context_type: Option<&Entity>) -> Result<Entity, ConstantEvaluationError> {
+        let not_constant = || ConstantEvaluationError::NotConstant { location: exp.location() };
+
+        // Logical operators result in one of their operands
+        match operator {
+            Operator::LogicalAnd => {
+                let l = self.evaluate(left, context_type)?;
+                return if self.to_boolean(&l) { self.evaluate(right, context_type) } else { Ok(l) };
+            },
+            Operator::LogicalOr => {
+                let l = self.evaluate(left, context_type)?;
+                return if self.to_boolean(&l) { Ok(l) } else { self.evaluate(right, context_type) };
+            },
+            Operator::NullCoalescing => {
+                let l = self.evaluate(left, context_type)?;
+                return if l.is::<NullConstant>() || l.is::<UndefinedConstant>() { self.evaluate(right, context_type) } else { Ok(l) };
+            },
+            Operator::LogicalXor => {
+                let l = self.evaluate(left, None)?;
+                let r = self.evaluate(right, None)?;
+                return self.boolean(self.to_boolean(&l) != self.to_boolean(&r));
+            },
+            _ => {},
+        }
+
+        let numeric_context = matches!(operator,
+            Operator::Add | Operator::Subtract | Operator::Multiply | Operator::Divide |
+            Operator::Remainder | Operator::Power);
+        let operand_context = if numeric_context { context_type } else { None };
+        let l = self.evaluate(left, operand_context)?;
+        let r = self.evaluate(right, operand_context)?;
+
+        match operator {
+            Operator::Add if l.is::<StringConstant>() || r.is::<StringConstant>() => {
+                let l = self.to_string(&l).ok_or_else(not_constant)?;
+                let r = self.to_string(&r).ok_or_else(not_constant)?;
+                self.string(l + &r)
+            },
+            Operator::Add | Operator::Subtract | Operator::Multiply |
+            Operator::Divide | Operator::Remainder | Operator::Power => {
+                let l = self.to_numeric(&l).ok_or_else(not_constant)?;
+                let r = self.to_numeric(&r).ok_or_else(not_constant)?;
+                if matches!(l, Number::BigInt(_)) != matches!(r, Number::BigInt(_)) {
+                    return Err(not_constant());
+                }
+                if let (Number::BigInt(a), Number::BigInt(b)) = (&l, &r) {
+                    if matches!(operator, Operator::Divide | Operator::Remainder) && b.is_zero() {
+                        return Err(ConstantEvaluationError::DivisionByZero { location: exp.location() });
+                    }
+                    if operator == Operator::Power && !Self::big_int_power_in_range(a, b) {
+                        return Err(not_constant());
+                    }
+                }
+                self.number(self.arithmetic(operator, l, r))
+            },
+            Operator::BitwiseAnd | Operator::BitwiseOr | Operator::BitwiseXor |
+            Operator::ShiftLeft | Operator::ShiftRight | Operator::ShiftRightUnsigned => {
+                let l = self.to_numeric(&l).ok_or_else(not_constant)?;
+                let r = self.to_numeric(&r).ok_or_else(not_constant)?;
+
+                // `BigInt` operates only with `BigInt`, and an operand of a 64-bit
+                // integer type results in that type (`ulong` if either operand is `ulong`).
+                let wide = match (&l, &r) {
+                    (Number::BigInt(_), Number::BigInt(_)) => Some(l.clone()),
+                    (Number::BigInt(_), _) | (_, Number::BigInt(_)) => return Err(not_constant()),
+                    (Number::Ulong(_), _) | (_, Number::Ulong(_)) => Some(Number::Ulong(l.force_ulong())),
+                    (Number::Long(_), _) | (_, Number::Long(_)) => Some(Number::Long(l.force_long())),
+                    _ => None,
+                };
+                if let Some(l) = wide {
+                    return self.number(match operator {
+                        Operator::BitwiseAnd => l & r,
+                        Operator::BitwiseOr => l | r,
+                        Operator::BitwiseXor => l ^ r,
+                        Operator::ShiftLeft => l << r,
+                        Operator::ShiftRight => l >> r,
+                        _ => l.shift_right_unsigned(&r),
+                    });
+                }
+                let l = Number::Number(l.force_double());
+                let r = Number::Number(r.force_double());
+                let shift_count = Number::Uint(r.force_uint() & 0x1F);
+                self.number(match operator {
+                    Operator::ShiftLeft => Number::Int(l.force_int()) << Number::Int(shift_count.force_int()),
+                    Operator::ShiftRight => Number::Int(l.force_int()) >> Number::Int(shift_count.force_int()),
+                    Operator::ShiftRightUnsigned => Number::Uint(l.force_uint()).shift_right_unsigned(&shift_count),
+                    _ => {
+                        let (l, r) = (Number::Int(l.force_int()), Number::Int(r.force_int()));
+                        match operator {
+                            Operator::BitwiseAnd => l & r,
+                            Operator::BitwiseOr => l | r,
+                            _ => l ^ r,
+                        }
+                    },
+                })
+            },
+            Operator::Lt | Operator::Gt | Operator::Le | Operator::Ge => {
+                if l.is::<StringConstant>() && r.is::<StringConstant>() {
+                    let (l, r) = (l.string_value(), r.string_value());
+                    return self.boolean(match operator {
+                        Operator::Lt => l < r,
+                        Operator::Gt => l > r,
+                        Operator::Le => l <= r,
+                        _ => l >= r,
+                    });
+                }
+                let l = self.to_double(&l).ok_or_else(not_constant)?;
+                let r = self.to_double(&r).ok_or_else(not_constant)?;
+                self.boolean(match operator {
+                    Operator::Lt => l < r,
+                    Operator::Gt => l > r,
+                    Operator::Le => l <= r,
+                    _ => l >= r,
+                })
+            },
+            Operator::Equals => self.boolean(self.loosely_equals(&l, &r)),
+            Operator::NotEquals => self.boolean(!self.loosely_equals(&l, &r)),
+            Operator::StrictEquals => self.boolean(self.strictly_equals(&l, &r)),
+            Operator::StrictNotEquals => self.boolean(!self.strictly_equals(&l, &r)),
+            _ => Err(not_constant()),
+        }
+    }
+
+    /// Converts a constant to a numeric value, preserving its numeric type
+    /// if it is a number constant, and resulting in `Number` otherwise.
+    fn to_numeric(&self, constant: &Entity) -> Option<Number> {
+        if constant.is::<NumberConstant>() {
+            Some(constant.number_value())
+        } else {
+            self.to_double(constant).map(Number::Number)
+        }
+    }
+
+    /// Indicates whether a `BigInt` power has a non-negative exponent and
+    /// a result of at most `MAX_BIG_INT_POWER_BITS` bits.
+    fn big_int_power_in_range(base: &num_bigint::BigInt, exponent: &num_bigint::BigInt) -> bool {
+        let Some(exponent) = exponent.to_u32() else {
+            return false;
+        };
+        base.bits() <= 1 || base.bits().saturating_mul(exponent as u64) <= MAX_BIG_INT_POWER_BITS
+    }
+
+    /// Arithmetic with mixed numeric types.
+    ///
+    /// * `Number` with any numeric type results in `Number`.
+    /// * `float` with `float`, `int` or `uint` results in `float`.
+    /// * `int` or `uint` operands of the same type result in that type if the
+    ///   result is representable by it, except for division; otherwise
+    ///   they result in `Number`.
+    /// * `long` or `ulong` operands of the same type result in that type, wrapping
+    ///   on overflow as `Number` arithmetic does, except for division, a zero
+    ///   remainder divisor and a negative exponent, which result in `Number`.
+    /// * Mixing different integer types results in `Number`.
+    /// * `BigInt` operands result in `BigInt`, with truncating division.
+    ///   `BigInt` is not mixed with other types.
+    fn arithmetic(&self, operator: Operator, l: Number, r: Number) -> Number {
+        match (&l, &r) {
+            (Number::Number(_), _) | (_, Number::Number(_)) => {
+                Number::Number(double_arithmetic(operator, l.force_double(), r.force_double()))
+            },
+            (Number::Float(_), _) | (_, Number::Float(_)) => {
+                let (l, r) = (Number::Float(l.force_float()), Number::Float(r.force_float()));
+                match operator {
+                    Operator::Add => l + r,
+                    Operator::Subtract => l - r,
+                    Operator::Multiply => l * r,
+                    Operator::Divide => l / r,
+                    Operator::Remainder => l % r,
+                    _ => Number::Float(l.force_float().powf(r.force_float())),
+                }
+            },
+            (Number::BigInt(_), Number::BigInt(b)) => {
+                match operator {
+                    Operator::Add => l + r,
+                    Operator::Subtract => l - r,
+                    Operator::Multiply => l * r,
+                    Operator::Divide => l / r,
+                    Operator::Remainder => l % r,
+                    _ => Number::BigInt(l.force_big_int().pow(b.to_u32().unwrap())),
+                }
+            },
+            (Number::Int(a), Number::Int(b)) if operator != Operator::Divide => {
+                let v = double_arithmetic(operator, *a as f64, *b as f64);
+                if v.fract() == 0.0 && v >= i32::MIN as f64 && v <= i32::MAX as f64 && !(v == 0.0 && v.is_sign_negative()) {
+                    Number::Int(v as i32)
+                } else {
+                    Number::Number(v)
+                }
+            },
+            (Number::Uint(a), Number::Uint(b)) if operator != Operator::Divide => {
+                let v = double_arithmetic(operator, *a as f64, *b as f64);
+                if v.fract() == 0.0 && v >= 0.0 && v <= u32::MAX as f64 {
+                    Number::Uint(v as u32)
+                } else {
+                    Number::Number(v)
+                }
+            },
+            (Number::Long(_), Number::Long(_)) | (Number::Ulong(_), Number::Ulong(_))
+                if long_arithmetic_wraps(operator, &r) => {
+                match operator {
+                    Operator::Add => l + r,
+                    Operator::Subtract => l - r,
+                    Operator::Multiply => l * r,
+                    Operator::Remainder => l % r,
+                    _ => match l {
+                        Number::Long(a) => Number::Long(wrapping_pow(a as u64, r.force_ulong()) as i64),
+                        _ => Number::Ulong(wrapping_pow(l.force_ulong(), r.force_ulong())),
+                    },
+                }
+            },
+            _ => Number::Number(double_arithmetic(operator, l.force_double(), r.force_double())),
+        }
+    }
+
+    fn loosely_equals(&self, l: &Entity, r: &Entity) -> bool {
+        let l_nullish = l.is::<NullConstant>() || l.is::<UndefinedConstant>();
+        let r_nullish = r.is::<NullConstant>() || r.is::<UndefinedConstant>();
+        if l_nullish || r_nullish {
+            return l_nullish && r_nullish;
+        }
+        if l.is::<StringConstant>() && r.is::<StringConstant>() {
+            return l.string_value() == r.string_value();
+        }
+        if l.is::<NamespaceConstant>() || r.is::<NamespaceConstant>() {
+            return self.strictly_equals(l, r);
+        }
+        match (self.to_double(l), self.to_double(r)) {
+            (Some(l), Some(r)) => l == r,
+            _ => false,
+        }
+    }
+
+    fn strictly_equals(&self, l: &Entity, r: &Entity) -> bool {
+        if l.is::<NumberConstant>() && r.is::<NumberConstant>() {
+            l.number_value().force_double() == r.number_value().force_double()
+        } else if l.is::<StringConstant>() && r.is::<StringConstant>() {
+            l.string_value() == r.string_value()
+        } else if l.is::<BooleanConstant>() && r.is::<BooleanConstant>() {
+            l.boolean_value() == r.boolean_value()
+        } else if l.is::<NamespaceConstant>() && r.is::<NamespaceConstant>() {
+            l.referenced_ns() == r.referenced_ns()
+        } else {
+            (l.is::<NullConstant>() && r.is::<NullConstant>()) || (l.is::<UndefinedConstant>() && r.is::<UndefinedConstant>())
+        }
+    }
+}
+
+fn double_arithmetic(operator: Operator, l: f64, r: f64) -> f64 {
+    match operator {
+        Operator::Add => l + r,
+        Operator::Subtract => l - r,
+        Operator::Multiply => l * r,
+        Operator::Divide => l / r,
+        Operator::Remainder => l % r,
+        _ => l.powf(r),
+    }
+}
+
+/// Indicates whether arithmetic on `long` or `ulong` operands of the same
+/// type results in that type: not for division, a zero remainder divisor
+/// or a negative exponent.
+fn long_arithmetic_wraps(operator: Operator, r: &Number) -> bool {
+    match operator {
+        Operator::Divide => false,
+        Operator::Remainder => !r.is_zero(),
+        Operator::Power => !matches!(r, Number::Long(v) if *v < 0),
+        _ => true,
+    }
+}
+
+/// Exponentiation modulo 2<sup>64</sup>.
+fn wrapping_pow(mut base: u64, mut exponent: u64) -> u64 {
+    let mut r: u64 = 1;
+    while exponent != 0 {
+        if exponent & 1 == 1 {
+            r = r.wrapping_mul(base);
+        }
+        base = base.wrapping_mul(base);
+        exponent >>= 1;
+    }
+    r
+}
+
+/// `ToString` applied to a double-precision floating point.
+fn double_to_string(v: f64) -> String {
+    if v.is_nan() {
+        "NaN".into()
+    } else if v.is_infinite() {
+        if v < 0.0 { "-Infinity".into() } else { "Infinity".into() }
+    } else if v == 0.0 {
+        "0".into()
+    } else if v.abs() >= 1e21 || v.abs() < 1e-6 {
+        let s = format!("{v:e}");
+        if s.contains("e-") { s } else { s.replace('e', "e+") }
+    } else {
+        v.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+    use crate::semantics::test_fixtures::*;
+
+    fn database() -> Database {
+        let db = Database::new(DatabaseOptions::default());
+        define_builtins(&db);
+        db
+    }
+
+    fn number(db: &Database, value: Number) -> Entity {
+        let t = match value {
+            Number::Int(_) => db.int_type(),
+            Number::Uint(_) => db.uint_type(),
+            Number::Long(_) => db.long_type(),
+            Number::Ulong(_) => db.ulong_type(),
+            Number::BigInt(_) => db.big_int_type(),
+            _ => db.number_type(),
+        };
+        db.factory().create_number_constant(value, &t)
+    }
+
+    /// Evaluates an expression whose identifiers name the given constants.
+    fn evaluate(db: &Database, constants: &[(&str, Number)], source: &str) -> Result<Number, ConstantEvaluationError> {
+        let exp = parse_expression(source);
+        let constants: Vec<(&str, Entity)> = constants.iter().map(|(name, value)| (*name, number(db, value.clone()))).collect();
+        map_identifiers_in_expression(db, &exp, &|name| constants.iter().find(|(n, _)| *n == name).map(|(_, c)| c.clone()));
+        let r = ConstantEvaluation(db).evaluate(&exp, None)?;
+        Ok(r.number_value())
+    }
+
+    fn is_not_constant(r: Result<Number, ConstantEvaluationError>) -> bool {
+        matches!(r, Err(ConstantEvaluationError::NotConstant { .. }))
+    }
+
+    #[test]
+    fn does_not_mix_big_int_with_other_types() {
+        let db = database();
+        let constants = [
+            ("b", Number::BigInt(3.into())),
+            ("i", Number::Int(2)),
+        ];
+        assert!(evaluate(&db, &constants, "b + b").unwrap() == Number::BigInt(6.into()));
+        assert!(evaluate(&db, &constants, "b << b").unwrap() == Number::BigInt(24.into()));
+        for source in ["b + 1", "1 * b", "b - i", "i % b", "b ** 2", "b & 1", "1 | b", "b << i", "i >> b"] {
+            assert!(is_not_constant(evaluate(&db, &constants, source)), "{source}");
+        }
+    }
+
+    #[test]
+    fn types_bitwise_operations_symmetrically() {
+        let db = database();
+        let constants = [
+            ("l", Number::Long(1 << 40)),
+            ("n", Number::Long(40)),
+            ("u", Number::Ulong(1 << 40)),
+            ("i", Number::Int(-1)),
+        ];
+        assert!(evaluate(&db, &constants, "l | 1").unwrap() == Number::Long((1 << 40) | 1));
+        assert!(evaluate(&db, &constants, "1 | l").unwrap() == Number::Long((1 << 40) | 1));
+        assert!(evaluate(&db, &constants, "i & l").unwrap() == Number::Long(1 << 40));
+        assert!(evaluate(&db, &constants, "l & i").unwrap() == Number::Long(1 << 40));
+        assert!(evaluate(&db, &constants, "1 << n").unwrap() == Number::Long(1 << 40));
+        assert!(evaluate(&db, &constants, "u ^ l").unwrap() == Number::Ulong(0));
+        assert!(evaluate(&db, &constants, "l ^ u").unwrap() == Number::Ulong(0));
+        assert!(evaluate(&db, &constants, "i ^ u").unwrap() == Number::Ulong(!(1 << 40)));
+        assert!(evaluate(&db, &constants, "i & 3").unwrap() == Number::Int(3));
+    }
+
+    #[test]
+    fn wraps_long_and_ulong_overflow() {
+        let db = database();
+        let constants = [
+            ("max", Number::Long(i64::MAX)),
+            ("one", Number::Long(1)),
+            ("two", Number::Long(2)),
+            ("zero", Number::Long(0)),
+            ("umax", Number::Ulong(u64::MAX)),
+            ("uone", Number::Ulong(1)),
+            ("u64", Number::Ulong(64)),
+            ("utwo", Number::Ulong(2)),
+        ];
+        assert!(evaluate(&db, &constants, "max + max").unwrap() == Number::Long(-2));
+        assert!(evaluate(&db, &constants, "max * two").unwrap() == Number::Long(-2));
+        assert!(evaluate(&db, &constants, "zero - max - two").unwrap() == Number::Long(i64::MAX));
+        assert!(evaluate(&db, &constants, "two ** max").unwrap() == Number::Long(0));
+        assert!(evaluate(&db, &constants, "umax + uone").unwrap() == Number::Ulong(0));
+        assert!(evaluate(&db, &constants, "uone - utwo").unwrap() == Number::Ulong(u64::MAX));
+        assert!(evaluate(&db, &constants, "utwo ** u64").unwrap() == Number::Ulong(0));
+        assert!(evaluate(&db, &constants, "-uone").unwrap() == Number::Ulong(u64::MAX));
+        assert!(evaluate(&db, &constants, "-(zero - max - one)").unwrap() == Number::Long(i64::MIN));
+
+        // Division, a zero remainder divisor and a negative exponent
+        // result in `Number`
+        assert!(evaluate(&db, &constants, "max / two").unwrap() == Number::Number(i64::MAX as f64 / 2.0));
+        assert!(evaluate(&db, &constants, "two % zero").unwrap().is_nan());
+        assert!(evaluate(&db, &constants, "two ** (zero - two)").unwrap() == Number::Number(0.25));
+        assert!(evaluate(&db, &constants, "max + 1").unwrap() == Number::Number(i64::MAX as f64 + 1.0));
+    }
+
+    #[test]
+    fn converts_strings_to_numbers() {
+        let db = database();
+        assert!(evaluate(&db, &[], "+' 0x1F '").unwrap() == Number::Number(31.0));
+        assert!(evaluate(&db, &[], "'1e3' - 0").unwrap() == Number::Number(1000.0));
+        assert!(evaluate(&db, &[], "+'-Infinity'").unwrap() == Number::Number(f64::NEG_INFINITY));
+        assert!(evaluate(&db, &[], "+'1_0'").unwrap().is_nan());
+    }
+}
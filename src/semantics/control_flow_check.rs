@@ -0,0 +1,199 @@
+use crate::ns::*;
+
+/// Unreachable code and missing return detection over the control flow
+/// graph of an activation.
+pub struct ControlFlowCheck<'a>(pub &'a Database);
+
+impl<'a> ControlFlowCheck<'a> {
+    /// Returns the directives that are never executed: the lines of blocks
+    /// not reachable from the entry block, and the lines that follow
+    /// a `return`, `throw`, `break` or `continue` statement in the same block.
+    pub fn unreachable_directives(&self, activation: &Entity) -> Vec<Rc<Directive>> {
+        let cfg = activation.control_flow_graph();
+        let reachable_blocks = cfg.reachable_blocks();
+        let mut r: Vec<Rc<Directive>> = vec![];
+        for block in cfg.blocks().iter() {
+            let lines = block.lines();
+            if !reachable_blocks.contains(&block) {
+                r.extend(lines.iter().cloned());
+                continue;
+            }
+            if let Some(i) = lines.iter().position(is_jump) {
+                r.extend(lines[(i + 1)..].iter().cloned());
+            }
+        }
+        r
+    }
+
+    /// Returns the reachable exit blocks through which control falls off
+    /// the end of the activation without a `return` or `throw` statement.
+    pub fn falls_off_end(&self, activation: &Entity) -> Vec<ControlFlowBlock> {
+        self.exit_blocks_without_return(activation, false)
+    }
+
+    /// Returns the reachable exit blocks without a `throw` statement or
+    /// a `return` statement, which must have a value if `with_value` is true.
+    fn exit_blocks_without_return(&self, activation: &Entity, with_value: bool) -> Vec<ControlFlowBlock> {
+        let cfg = activation.control_flow_graph();
+        let reachable_blocks = cfg.reachable_blocks();
+        cfg.exit_blocks().into_iter().filter(|block| {
+            reachable_blocks.contains(block) && !block.lines().iter().any(|line| {
+                match line.as_ref() {
+                    Directive::ReturnStatement(d) => !with_value || d.expression.is_some(),
+                    Directive::ThrowStatement(_) => true,
+                    _ => false,
+                }
+            })
+        }).collect()
+    }
+
+    /// Indicates whether a method must return a value on every path.
+    ///
+    /// This is the case if the result type is neither `void` nor `*`. For an `async`
+    /// method, the result type is the `T` in `Promise.<T>`. Generator methods
+    /// and constructors never need to return a value.
+    pub fn requires_return_value(&self, method: &Entity) -> Result<bool, DeferError> {
+        if method.is_generator() || method.is_constructor() {
+            return Ok(false);
+        }
+        let signature = method.signature(self.0).defer()?;
+        let mut result_type = signature.result_type().defer()?;
+        if method.is_async() {
+            let Some(t) = result_type.promise_result_type(self.0)? else {
+                return Ok(false);
+            };
+            result_type = t;
+        }
+        Ok(!(result_type.is::<VoidType>() || result_type.is::<AnyType>() || result_type.is::<InvalidationEntity>()))
+    }
+
+    /// Indicates whether the method of an activation must return a value
+    /// and some path falls off the end or returns without a value.
+    pub fn is_missing_return(&self, activation: &Entity) -> Result<bool, DeferError> {
+        let method = activation.of_method();
+        if !self.requires_return_value(&method)? {
+            return Ok(false);
+        }
+        let cfg = activation.control_flow_graph();
+        Ok(cfg.blocks().length() == 0 || !self.exit_blocks_without_return(activation, true).is_empty())
+    }
+}
+
+fn is_jump(directive: &Rc<Directive>) -> bool {
+    matches!(directive.as_ref(),
+        Directive::ReturnStatement(_) |
+        Directive::ThrowStatement(_) |
+        Directive::BreakStatement(_) |
+        Directive::ContinueStatement(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::semantics::test_fixtures::*;
+
+    fn define_class(db: &Database, local_name: &str) -> Entity {
+        let top_level_package = db.top_level_package();
+        let public_ns = top_level_package.public_ns().unwrap();
+        let name = db.factory().create_qname(&public_ns, local_name.into());
+        let class = db.factory().create_class_type(name.clone(), &public_ns);
+        top_level_package.properties(db).set(name, class.clone());
+        class
+    }
+
+    /// Creates an activation whose method results into a type and
+    /// whose body consists of the directives forming a single block.
+    fn define_activation_returning(db: &Database, result_type: &Entity, source: &str) -> Entity {
+        let activation = define_activation(db);
+        activation.of_method().set_signature(&db.factory().create_function_type(vec![], result_type.clone()));
+        add_block(&activation.control_flow_graph(), parse_directives(source));
+        activation
+    }
+
+    #[test]
+    fn reports_code_after_jumps() {
+        let db = Database::new(DatabaseOptions::default());
+        let activation = define_activation(&db);
+        let cfg = activation.control_flow_graph();
+        let directives = parse_directives("throw e; f(); g();");
+        let entry = add_block(&cfg, directives.clone());
+        let unreachable = parse_directives("h();");
+        add_block(&cfg, unreachable.clone());
+        let directives_2 = parse_directives("while (c) { break; i(); }");
+        let Directive::WhileStatement(d) = directives_2[0].as_ref() else {
+            panic!("expected a while statement");
+        };
+        let body_directives = block_directives(&d.body);
+        let body = add_block(&cfg, body_directives.clone());
+        add_edge(&cfg, &entry, &body);
+
+        let r = ControlFlowCheck(&db).unreachable_directives(&activation);
+        let expected: Vec<Rc<Directive>> = vec![directives[1].clone(), directives[2].clone(), unreachable[0].clone(), body_directives[1].clone()];
+        assert_eq!(r.len(), expected.len());
+        assert!(expected.iter().all(|d| r.iter().any(|d2| Rc::ptr_eq(d, d2))));
+    }
+
+    #[test]
+    fn reports_missing_return_values() {
+        let db = Database::new(DatabaseOptions::default());
+        let a = define_class(&db, "A");
+        let check = ControlFlowCheck(&db);
+        assert!(!check.is_missing_return(&define_activation_returning(&db, &a, "return new A;")).unwrap());
+        assert!(!check.is_missing_return(&define_activation_returning(&db, &a, "throw e;")).unwrap());
+        assert!(check.is_missing_return(&define_activation_returning(&db, &a, "f();")).unwrap());
+        assert!(check.is_missing_return(&define_activation_returning(&db, &a, "return;")).unwrap());
+        assert!(!check.is_missing_return(&define_activation_returning(&db, &db.void_type(), "return;")).unwrap());
+        assert!(!check.is_missing_return(&define_activation_returning(&db, &db.any_type(), "f();")).unwrap());
+
+        // A method without blocks falls off the end
+        let activation = define_activation(&db);
+        activation.of_method().set_signature(&db.factory().create_function_type(vec![], a));
+        assert!(check.is_missing_return(&activation).unwrap());
+
+        // `return;` does not fall off the end
+        assert!(check.falls_off_end(&define_activation_returning(&db, &db.void_type(), "return;")).is_empty());
+    }
+
+    #[test]
+    fn requires_return_value_from_async_result_type() {
+        let db = Database::new(DatabaseOptions::default());
+        let a = define_class(&db, "A");
+        let promise = define_class(&db, "Promise");
+        let ns = db.top_level_package().public_ns().unwrap();
+        let t = db.factory().create_type_parameter_type(&db.factory().create_qname(&ns, "T".into()));
+        promise.set_type_params(Some(shared_array![t]));
+        let check = ControlFlowCheck(&db);
+
+        let promise_a = db.factory().create_type_after_substitution(&promise, &shared_array![a.clone()]);
+        let activation = define_activation_returning(&db, &promise_a, "return;");
+        activation.of_method().set_is_async(true);
+        assert!(check.requires_return_value(&activation.of_method()).unwrap());
+        assert!(check.is_missing_return(&activation).unwrap());
+
+        let promise_void = db.factory().create_type_after_substitution(&promise, &shared_array![db.void_type()]);
+        let activation = define_activation_returning(&db, &promise_void, "return;");
+        activation.of_method().set_is_async(true);
+        assert!(!check.requires_return_value(&activation.of_method()).unwrap());
+
+        // An async method whose result type is not a promise
+        let activation = define_activation_returning(&db, &a, "f();");
+        activation.of_method().set_is_async(true);
+        assert!(!check.is_missing_return(&activation).unwrap());
+    }
+
+    #[test]
+    fn generators_and_constructors_need_no_return_value() {
+        let db = Database::new(DatabaseOptions::default());
+        let a = define_class(&db, "A");
+        let check = ControlFlowCheck(&db);
+
+        let activation = define_activation_returning(&db, &a, "f();");
+        activation.of_method().set_is_generator(true);
+        assert!(!check.requires_return_value(&activation.of_method()).unwrap());
+        assert!(!check.is_missing_return(&activation).unwrap());
+
+        let activation = define_activation_returning(&db, &a, "f();");
+        activation.of_method().set_is_constructor(true);
+        assert!(!check.is_missing_return(&activation).unwrap());
+    }
+}
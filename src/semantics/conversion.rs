@@ -11,9 +11,19 @@ pub enum ConversionKind {
     /// Implicit conversion between number types,
     /// where the base and target are as they are
     /// (not marked nullable or non-nullable).
+    /// 
+    /// `BigInt` is not involved in this conversion, and `long` and `ulong`
+    /// are involved only as the target of a widening conversion:
+    /// from `int` to `long`, or from `uint` to `long` or `ulong`.
     BetweenNumber,
 
-    /// Implicit conversion to covariant type.
+    /// Implicit conversion from `int`, `uint`, `long` or `ulong` to `BigInt`,
+    /// where the base and target are as they are
+    /// (not marked nullable or non-nullable).
+    IntegerToBigInt,
+
+    /// Implicit conversion to covariant type, including structural
+    /// subtypes of function and tuple types.
     /// 
     /// Involved types either both include null or both do not include null.
     ToCovariant,
@@ -106,6 +116,15 @@ pub enum ConversionKind {
 
     /// Explicit type conversion.
     ToInt,
+
+    /// Explicit type conversion.
+    ToLong,
+
+    /// Explicit type conversion.
+    ToUlong,
+
+    /// Explicit type conversion.
+    ToBigInt,
 }
 
 impl ConversionKind {
@@ -114,6 +133,7 @@ impl ConversionKind {
             Self::FromAny,
             Self::ToAny,
             Self::BetweenNumber,
+            Self::IntegerToBigInt,
             Self::ToCovariant,
             Self::ItrfcToObject,
             Self::NonNullableToNullable,
@@ -128,7 +148,10 @@ pub struct ConversionMethods<'a>(pub &'a Database);
 
 impl<'a> ConversionMethods<'a> {
     pub fn constant(&self, value: &Entity, target_type: &Entity) -> Result<Option<Entity>, DeferError> {
-        let from_type = value.static_type(self.0);
+        // Only values have a static type
+        let Ok(from_type) = value.try_static_type(self.0) else {
+            return Ok(None);
+        };
         if &from_type == target_type {
             return Ok(Some(value.clone()));
         }
@@ -163,9 +186,10 @@ impl<'a> ConversionMethods<'a> {
             return Ok(Some(self.0.factory().create_number_constant(value.number_value(), target_type)));
         }
 
-        if value.is::<NumberConstant>() && self.0.numeric_types()?.contains(&target_esc_type) {
-            let v = value.number_value().convert_type(target_type, self.0)?;
-            return Ok(Some(self.0.factory().create_number_constant(v, target_type)));
+        if value.is::<NumberConstant>() && self.is_implicit_between_numbers(&from_type, &target_esc_type)? {
+            if let Some(v) = value.number_value().try_convert_type(target_type, self.0)? {
+                return Ok(Some(self.0.factory().create_number_constant(v, target_type)));
+            }
         }
 
         // From T or T! constant to T?, or
@@ -181,7 +205,10 @@ impl<'a> ConversionMethods<'a> {
     }
 
     pub fn implicit(&self, value: &Entity, target_type: &Entity, optional: bool) -> Result<Option<Entity>, DeferError> {
-        let from_type = value.static_type(self.0);
+        // Only values have a static type
+        let Ok(from_type) = value.try_static_type(self.0) else {
+            return Ok(None);
+        };
         if &from_type == target_type {
             return Ok(Some(value.clone()));
         }
@@ -201,9 +228,10 @@ impl<'a> ConversionMethods<'a> {
             return Ok(Some(self.0.factory().create_conversion_value(value, ConversionKind::ToAny, optional, target_type)?));
         }
 
-        // Between number types
-        if self.0.numeric_types()?.contains(&from_type) && self.0.numeric_types()?.contains(&target_type) {
-            return Ok(Some(self.0.factory().create_conversion_value(value, ConversionKind::BetweenNumber, optional, target_type)?));
+        // Between number types, or IntegerToBigInt
+        if self.is_implicit_between_numbers(&from_type, target_type)? {
+            let kind = if target_type == &self.0.big_int_type() { ConversionKind::IntegerToBigInt } else { ConversionKind::BetweenNumber };
+            return Ok(Some(self.0.factory().create_conversion_value(value, kind, optional, target_type)?));
         }
 
         let from_type_esc = from_type.escape_of_nullable_or_non_nullable();
@@ -271,7 +299,10 @@ impl<'a> ConversionMethods<'a> {
     }
 
     pub fn explicit(&self, value: &Entity, target_type: &Entity, optional: bool) -> Result<Option<Entity>, DeferError> {
-        let from_type = value.static_type(self.0);
+        // Only values have a static type
+        let Ok(from_type) = value.try_static_type(self.0) else {
+            return Ok(None);
+        };
         if &from_type == target_type {
             return Ok(Some(value.clone()));
         }
@@ -364,6 +395,18 @@ impl<'a> ConversionMethods<'a> {
             return Ok(Some(self.0.factory().create_conversion_value(value, ConversionKind::ToInt, optional, target_type)?));
         }
 
+        if target_type == &self.0.long_type() {
+            return Ok(Some(self.0.factory().create_conversion_value(value, ConversionKind::ToLong, optional, target_type)?));
+        }
+
+        if target_type == &self.0.ulong_type() {
+            return Ok(Some(self.0.factory().create_conversion_value(value, ConversionKind::ToUlong, optional, target_type)?));
+        }
+
+        if target_type == &self.0.big_int_type() {
+            return Ok(Some(self.0.factory().create_conversion_value(value, ConversionKind::ToBigInt, optional, target_type)?));
+        }
+
         let boolean_type = self.0.boolean_type().defer()?;
 
         if target_type == &boolean_type {
@@ -393,4 +436,36 @@ impl<'a> ConversionMethods<'a> {
 
         Ok(None)
     }
+
+    /// Indicates whether a number type converts implicitly to another number type.
+    ///
+    /// `Number`, `float`, `int` and `uint` convert implicitly between each other.
+    /// `long` and `ulong` are implicitly converted to only by widening
+    /// (`int` to `long`, `uint` to `long` or `ulong`) and `BigInt` only from
+    /// an integer type; conversions out of these types are explicit.
+    fn is_implicit_between_numbers(&self, from_type: &Entity, target_type: &Entity) -> Result<bool, DeferError> {
+        let numeric_types = self.0.numeric_types()?;
+        if !(numeric_types.contains(from_type) && numeric_types.contains(target_type)) {
+            return Ok(false);
+        }
+        let big_int_type = self.0.big_int_type();
+        if from_type == &big_int_type {
+            return Ok(false);
+        }
+        if target_type == &big_int_type {
+            return Ok(self.0.integer_types()?.contains(from_type));
+        }
+        let long_type = self.0.long_type();
+        let ulong_type = self.0.ulong_type();
+        if from_type == &long_type || from_type == &ulong_type {
+            return Ok(false);
+        }
+        if target_type == &long_type {
+            return Ok(from_type == &self.0.int_type() || from_type == &self.0.uint_type());
+        }
+        if target_type == &ulong_type {
+            return Ok(from_type == &self.0.uint_type());
+        }
+        Ok(true)
+    }
 }
\ No newline at end of file
@@ -4,9 +4,15 @@ pub struct Database {
     pub(crate) arena: EntityArena,
     node_mapping: NodeAssignment<Entity>,
     node_invalidation_mapping: NodeAssignment<()>,
+    position_index: PositionIndex,
     project_path: Option<String>,
     config_constants: SharedMap<String, String>,
     config_constants_result: SharedMap<String, Entity>,
+    pub(crate) config_constants_in_evaluation: RefCell<Vec<String>>,
+    pub(crate) substitutions_in_verification: RefCell<Vec<(Entity, Vec<Entity>)>>,
+    pub(crate) references: RefCell<HashMap<Entity, Vec<Reference>>>,
+    pub(crate) reference_context: RefCell<Vec<Reference>>,
+    pub(crate) pending_references: RefCell<Option<Vec<(Entity, Reference)>>>,
     env_cache: RefCell<Option<Rc<HashMap<String, String>>>>,
 
     unused_things: Rc<RefCell<Vec<Entity>>>,
@@ -29,6 +35,9 @@ pub struct Database {
     int_type: RefCell<Option<Entity>>,
     uint_type: RefCell<Option<Entity>>,
     float_type: RefCell<Option<Entity>>,
+    long_type: RefCell<Option<Entity>>,
+    ulong_type: RefCell<Option<Entity>>,
+    big_int_type: RefCell<Option<Entity>>,
     string_type: RefCell<Option<Entity>>,
     array_type: RefCell<Option<Entity>>,
     namespace_type: RefCell<Option<Entity>>,
@@ -85,9 +94,15 @@ impl Database {
             arena,
             node_mapping: NodeAssignment::new(),
             node_invalidation_mapping: NodeAssignment::new(),
+            position_index: PositionIndex::new(),
             project_path: options.project_path.clone(),
             config_constants: SharedMap::new(),
             config_constants_result: SharedMap::new(),
+            config_constants_in_evaluation: RefCell::new(vec![]),
+            substitutions_in_verification: RefCell::new(vec![]),
+            references: RefCell::new(HashMap::new()),
+            reference_context: RefCell::new(vec![]),
+            pending_references: RefCell::new(None),
             env_cache: RefCell::new(None),
 
             explicit_namespaces,
@@ -113,6 +128,9 @@ impl Database {
             int_type: RefCell::new(None),
             uint_type: RefCell::new(None),
             float_type: RefCell::new(None),
+            long_type: RefCell::new(None),
+            ulong_type: RefCell::new(None),
+            big_int_type: RefCell::new(None),
             string_type: RefCell::new(None),
             array_type: RefCell::new(None),
             namespace_type: RefCell::new(None),
@@ -187,14 +205,24 @@ impl Database {
         &self.node_invalidation_mapping
     }
 
+    /// Index of entities by source position.
+    #[inline(always)]
+    pub fn position_index(&self) -> &PositionIndex {
+        &self.position_index
+    }
+
+    /// Maps a node to an entity if not already mapped, recording
+    /// the node location in the position index. Every node type
+    /// `NodeAssignment` maps implements `LocatedNode`.
     pub fn lazy_node_mapping<T>(&self, node: &Rc<T>, init: impl FnOnce() -> Entity) -> Entity
-        where NodeAssignment<Entity>: NodeAssignmentMethod<T, Entity>
+        where NodeAssignment<Entity>: NodeAssignmentMethod<T, Entity>, T: LocatedNode
     {
         if let Some(m) = self.node_mapping().get(node) {
             m
         } else {
             let entity = init();
             self.node_mapping().set(node, Some(entity.clone()));
+            self.position_index.add_node(node.node_location(), &entity);
             entity
         }
     }
@@ -295,6 +323,9 @@ impl Database {
     global_lookup!(int_type, "int");
     global_lookup!(uint_type, "uint");
     global_lookup!(float_type, "float");
+    global_lookup!(long_type, "long");
+    global_lookup!(ulong_type, "ulong");
+    global_lookup!(big_int_type, "BigInt");
     global_lookup!(string_type, "String");
     global_lookup!(array_type, "Array");
     global_lookup!(namespace_type, "Namespace");
@@ -308,17 +339,17 @@ impl Database {
 
     pub fn array_type_of_any(&self) -> Result<Entity, DeferError> {
         let origin = self.array_type().defer()?;
-        Ok(self.factory().create_type_after_substitution(&origin, &shared_array![self.any_type()]))
+        Ok(self.factory().intern_type_after_substitution(&origin, &shared_array![self.any_type()]))
     }
 
     pub fn promise_type_of_any(&self) -> Result<Entity, DeferError> {
         let origin = self.promise_type().defer()?;
-        Ok(self.factory().create_type_after_substitution(&origin, &shared_array![self.any_type()]))
+        Ok(self.factory().intern_type_after_substitution(&origin, &shared_array![self.any_type()]))
     }
 
     pub fn vector_type_of_any(&self) -> Result<Entity, DeferError> {
         let origin = self.vector_type().defer()?;
-        Ok(self.factory().create_type_after_substitution(&origin, &shared_array![self.any_type()]))
+        Ok(self.factory().intern_type_after_substitution(&origin, &shared_array![self.any_type()]))
     }
 
     /// Retrieves `__AS3__.vec.Vector`, a possibly unresolved thing.
@@ -387,12 +418,13 @@ impl Database {
         self.as3_ns.borrow().as_ref().unwrap().clone()
     }
 
-    /// Returns the set (`void`, `String`, `Boolean`, `Number`, `int`, `uint`, `float`).
+    /// Returns the set (`void`, `String`, `Boolean`, `Number`, `int`, `uint`, `float`,
+    /// `long`, `ulong`).
     pub fn primitive_types(&self) -> Result<Rc<Vec<Entity>>, DeferError> {
         if let Some(r) = self.primitive_types.borrow().as_ref() {
             return Ok(r.clone());
         }
-        let r = Rc::new(vec![
+        let mut r = vec![
             self.void_type(),
             self.string_type().defer()?,
             self.boolean_type().defer()?,
@@ -400,37 +432,45 @@ impl Database {
             self.int_type().defer()?,
             self.uint_type().defer()?,
             self.float_type().defer()?,
-        ]);
+        ];
+        r.extend(self.long_types()?);
+        let r = Rc::new(r);
         self.primitive_types.replace(Some(r.clone()));
         Ok(r)
     }
 
-    /// Returns the set (`Boolean`, `Number`, `int`, `uint`, `float`).
+    /// Returns the set (`Boolean`, `Number`, `int`, `uint`, `float`, `long`, `ulong`).
     pub fn non_null_primitive_types(&self) -> Result<Rc<Vec<Entity>>, DeferError> {
         if let Some(r) = self.non_null_primitive_types.borrow().as_ref() {
             return Ok(r.clone());
         }
-        let r = Rc::new(vec![
+        let mut r = vec![
             self.boolean_type().defer()?,
             self.number_type().defer()?,
             self.int_type().defer()?,
             self.uint_type().defer()?,
             self.float_type().defer()?,
-        ]);
+        ];
+        r.extend(self.long_types()?);
+        let r = Rc::new(r);
         self.non_null_primitive_types.replace(Some(r.clone()));
         Ok(r)
     }
 
+    /// Returns the set (`Number`, `int`, `uint`, `float`, `long`, `ulong`, `BigInt`).
     pub fn numeric_types(&self) -> Result<Rc<Vec<Entity>>, DeferError> {
         if let Some(r) = self.numeric_types.borrow().as_ref() {
             return Ok(r.clone());
         }
-        let r = Rc::new(vec![
+        let mut r = vec![
             self.number_type().defer()?,
             self.int_type().defer()?,
             self.uint_type().defer()?,
             self.float_type().defer()?,
-        ]);
+        ];
+        r.extend(self.long_types()?);
+        r.push(self.big_int_type().defer()?);
+        let r = Rc::new(r);
         self.numeric_types.replace(Some(r.clone()));
         Ok(r)
     }
@@ -447,18 +487,40 @@ impl Database {
         Ok(r)
     }
 
+    /// Returns the set (`int`, `uint`, `long`, `ulong`, `BigInt`).
     pub fn integer_types(&self) -> Result<Rc<Vec<Entity>>, DeferError> {
         if let Some(r) = self.integer_types.borrow().as_ref() {
             return Ok(r.clone());
         }
-        let r = Rc::new(vec![
+        let mut r = vec![
             self.int_type().defer()?,
             self.uint_type().defer()?,
-        ]);
+        ];
+        r.extend(self.long_types()?);
+        r.push(self.big_int_type().defer()?);
+        let r = Rc::new(r);
         self.integer_types.replace(Some(r.clone()));
         Ok(r)
     }
 
+    /// Returns the 64-bit integer types (`long`, `ulong`).
+    fn long_types(&self) -> Result<[Entity; 2], DeferError> {
+        Ok([self.long_type().defer()?, self.ulong_type().defer()?])
+    }
+
+    /// Looks up a package, package-level definition or member by its
+    /// fully qualified name, as in `mx.core.UIComponent.width`.
+    /// See `FullyQualifiedNameLookup` for the name syntax.
+    pub fn lookup(&self, name: &str) -> Option<Entity> {
+        FullyQualifiedNameLookup(self).lookup(name)
+    }
+
+    /// Produces the canonical fully qualified name of a definition,
+    /// which `Database::lookup()` resolves back to it.
+    pub fn canonical_name(&self, entity: &Entity) -> Option<String> {
+        FullyQualifiedNameLookup(self).canonical_name(entity)
+    }
+
     /// Preloads environment variables from the main project's `.env` file
     /// using the DotEnv file format.
     pub fn env(&self) -> Rc<HashMap<String, String>> {
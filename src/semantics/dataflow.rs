@@ -0,0 +1,483 @@
+use crate::ns::*;
+
+/// Direction in which facts flow in a dataflow analysis.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DataflowDirection {
+    Forward,
+    Backward,
+}
+
+/// A semilattice of dataflow facts.
+pub trait Lattice: Clone + PartialEq {
+    /// Combines the facts flowing into a block from several paths.
+    fn meet(&self, other: &Self) -> Self;
+}
+
+/// Set lattice for "must" analyses, whose meet is the intersection.
+/// `Universe` is the top element, used for blocks not yet reached.
+#[derive(Clone, Debug)]
+pub enum MustSet<T> {
+    Universe,
+    Set(Vec<T>),
+}
+
+impl<T: Clone + PartialEq> MustSet<T> {
+    pub fn contains(&self, value: &T) -> bool {
+        match self {
+            Self::Universe => true,
+            Self::Set(list) => list.contains(value),
+        }
+    }
+
+    /// Returns the set elements, or `None` for the universe.
+    pub fn elements(&self) -> Option<&Vec<T>> {
+        match self {
+            Self::Universe => None,
+            Self::Set(list) => Some(list),
+        }
+    }
+
+    pub fn insert(&mut self, value: T) {
+        if let Self::Set(list) = self {
+            if !list.contains(&value) {
+                list.push(value);
+            }
+        }
+    }
+
+    pub fn retain(&mut self, f: impl FnMut(&T) -> bool) {
+        if let Self::Set(list) = self {
+            list.retain(f);
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> PartialEq for MustSet<T> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Universe, Self::Universe) => true,
+            (Self::Set(a), Self::Set(b)) => a.len() == b.len() && a.iter().all(|v| b.contains(v)),
+            _ => false,
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> Lattice for MustSet<T> {
+    fn meet(&self, other: &Self) -> Self {
+        match (self, other) {
+            (Self::Universe, other) => other.clone(),
+            (this, Self::Universe) => this.clone(),
+            (Self::Set(a), Self::Set(b)) => Self::Set(a.iter().filter(|v| b.contains(v)).cloned().collect()),
+        }
+    }
+}
+
+/// Set lattice for "may" analyses, whose meet is the union.
+/// The empty set is the top element.
+#[derive(Clone, Debug)]
+pub struct MaySet<T>(pub Vec<T>);
+
+impl<T: Clone + PartialEq> PartialEq for MaySet<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len() && self.0.iter().all(|v| other.0.contains(v))
+    }
+}
+
+impl<T: Clone + PartialEq> Lattice for MaySet<T> {
+    fn meet(&self, other: &Self) -> Self {
+        let mut r = self.0.clone();
+        for v in other.0.iter() {
+            if !r.contains(v) {
+                r.push(v.clone());
+            }
+        }
+        Self(r)
+    }
+}
+
+/// A dataflow analysis over a control flow graph, solved by
+/// [`ControlFlowGraph::solve_dataflow`].
+pub trait DataflowAnalysis {
+    type Fact: Lattice;
+
+    fn direction(&self) -> DataflowDirection;
+
+    /// Fact at the entry block for a forward analysis, or at
+    /// the exit blocks for a backward analysis.
+    fn boundary_fact(&self) -> Self::Fact;
+
+    /// Initial fact of the remaining blocks, usually the top element.
+    fn initial_fact(&self) -> Self::Fact;
+
+    /// Computes the output fact of a block from its input fact.
+    fn transfer(&mut self, block: &ControlFlowBlock, input: &Self::Fact) -> Self::Fact;
+
+    /// Refines a fact flowing through an edge, such as with the outcome
+    /// of a branch condition. The default implementation returns the fact as is.
+    fn edge_transfer(&mut self, edge: &ControlFlowEdge, fact: &Self::Fact) -> Self::Fact {
+        let _ = edge;
+        fact.clone()
+    }
+}
+
+/// Facts computed by a dataflow analysis at the entry and exit of every block.
+pub struct DataflowResults<F> {
+    blocks: Vec<ControlFlowBlock>,
+    entry: Vec<F>,
+    exit: Vec<F>,
+}
+
+impl<F: Clone> DataflowResults<F> {
+    /// Fact at the entry of a block, in program order.
+    pub fn at_entry(&self, block: &ControlFlowBlock) -> Option<F> {
+        let i = self.blocks.iter().position(|b| b == block)?;
+        Some(self.entry[i].clone())
+    }
+
+    /// Fact at the exit of a block, in program order.
+    pub fn at_exit(&self, block: &ControlFlowBlock) -> Option<F> {
+        let i = self.blocks.iter().position(|b| b == block)?;
+        Some(self.exit[i].clone())
+    }
+}
+
+/// Dominator (or post-dominator) sets of the blocks of a control flow graph.
+pub struct Dominators {
+    blocks: Vec<ControlFlowBlock>,
+    sets: Vec<MustSet<usize>>,
+}
+
+impl Dominators {
+    /// Indicates whether block `a` dominates block `b`. Every block
+    /// dominates itself. Unreachable blocks are dominated by nothing.
+    pub fn dominates(&self, a: &ControlFlowBlock, b: &ControlFlowBlock) -> bool {
+        let (Some(i), Some(j)) = (self.index_of(a), self.index_of(b)) else {
+            return false;
+        };
+        match &self.sets[j] {
+            MustSet::Universe => false,
+            MustSet::Set(list) => list.contains(&i),
+        }
+    }
+
+    /// Returns the blocks that dominate a block, including itself.
+    pub fn dominators_of(&self, block: &ControlFlowBlock) -> Vec<ControlFlowBlock> {
+        let Some(j) = self.index_of(block) else {
+            return vec![];
+        };
+        match &self.sets[j] {
+            MustSet::Universe => vec![],
+            MustSet::Set(list) => list.iter().map(|i| self.blocks[*i].clone()).collect(),
+        }
+    }
+
+    /// Returns the immediate dominator of a block: the strict dominator
+    /// that is dominated by every other strict dominator.
+    pub fn immediate_dominator(&self, block: &ControlFlowBlock) -> Option<ControlFlowBlock> {
+        let strict: Vec<ControlFlowBlock> = self.dominators_of(block).into_iter().filter(|b| b != block).collect();
+        strict.iter().find(|d| strict.iter().all(|d2| self.dominates(d2, d))).cloned()
+    }
+
+    fn index_of(&self, block: &ControlFlowBlock) -> Option<usize> {
+        self.blocks.iter().position(|b| b == block)
+    }
+}
+
+/// A natural loop: a header block and the blocks that reach a back edge
+/// to the header without passing through it. Loops sharing a header
+/// are merged.
+#[derive(Clone)]
+pub struct NaturalLoop {
+    pub header: ControlFlowBlock,
+    pub back_edges: Vec<ControlFlowEdge>,
+    /// Blocks of the loop, including the header.
+    pub blocks: Vec<ControlFlowBlock>,
+}
+
+struct DominatorAnalysis {
+    blocks: Vec<ControlFlowBlock>,
+    direction: DataflowDirection,
+}
+
+impl DataflowAnalysis for DominatorAnalysis {
+    type Fact = MustSet<usize>;
+
+    fn direction(&self) -> DataflowDirection {
+        self.direction
+    }
+
+    fn boundary_fact(&self) -> Self::Fact {
+        MustSet::Set(vec![])
+    }
+
+    fn initial_fact(&self) -> Self::Fact {
+        MustSet::Universe
+    }
+
+    fn transfer(&mut self, block: &ControlFlowBlock, input: &Self::Fact) -> Self::Fact {
+        let mut r = input.clone();
+        r.insert(self.blocks.iter().position(|b| b == block).unwrap());
+        r
+    }
+}
+
+impl ControlFlowGraph {
+    /// The entry block, which is the first block of the graph.
+    pub fn entry_block(&self) -> Option<ControlFlowBlock> {
+        self.blocks().get(0)
+    }
+
+    /// Blocks with no successors.
+    pub fn exit_blocks(&self) -> Vec<ControlFlowBlock> {
+        self.blocks().iter().filter(|b| self.successors(b).is_empty()).collect()
+    }
+
+    pub fn successors(&self, block: &ControlFlowBlock) -> Vec<ControlFlowBlock> {
+        self.edges().iter().filter(|e| &e.from == block).map(|e| e.to.clone()).collect()
+    }
+
+    pub fn predecessors(&self, block: &ControlFlowBlock) -> Vec<ControlFlowBlock> {
+        self.edges().iter().filter(|e| &e.to == block).map(|e| e.from.clone()).collect()
+    }
+
+    /// Solves a dataflow analysis with a worklist algorithm.
+    pub fn solve_dataflow<A: DataflowAnalysis>(&self, analysis: &mut A) -> DataflowResults<A::Fact> {
+        let blocks: Vec<ControlFlowBlock> = self.blocks().iter().collect();
+        let edges: Vec<ControlFlowEdge> = self.edges().iter().collect();
+        let forward = analysis.direction() == DataflowDirection::Forward;
+        let index_of = |block: &ControlFlowBlock| blocks.iter().position(|b| b == block).unwrap();
+
+        let boundary: Vec<bool> = if forward {
+            blocks.iter().enumerate().map(|(i, _)| i == 0).collect()
+        } else {
+            blocks.iter().map(|b| !edges.iter().any(|e| &e.from == b)).collect()
+        };
+
+        // "Input" is the entry fact for a forward analysis
+        // and the exit fact for a backward analysis.
+        let mut input: Vec<A::Fact> = blocks.iter().map(|_| analysis.initial_fact()).collect();
+        let mut output: Vec<A::Fact> = blocks.iter().map(|_| analysis.initial_fact()).collect();
+        let mut worklist: Vec<usize> = (0..blocks.len()).rev().collect();
+
+        while let Some(i) = worklist.pop() {
+            let block = &blocks[i];
+            let mut fact: Option<A::Fact> = if boundary[i] { Some(analysis.boundary_fact()) } else { None };
+            for edge in edges.iter() {
+                let other = if forward && &edge.to == block {
+                    &edge.from
+                } else if !forward && &edge.from == block {
+                    &edge.to
+                } else {
+                    continue;
+                };
+                let incoming = analysis.edge_transfer(edge, &output[index_of(other)]);
+                fact = Some(match fact {
+                    None => incoming,
+                    Some(fact) => fact.meet(&incoming),
+                });
+            }
+            let fact = fact.unwrap_or_else(|| analysis.initial_fact());
+            let new_output = analysis.transfer(block, &fact);
+            input[i] = fact;
+
+            if new_output != output[i] {
+                output[i] = new_output;
+                for edge in edges.iter() {
+                    let dependent = if forward && &edge.from == block {
+                        &edge.to
+                    } else if !forward && &edge.to == block {
+                        &edge.from
+                    } else {
+                        continue;
+                    };
+                    let j = index_of(dependent);
+                    if !worklist.contains(&j) {
+                        worklist.push(j);
+                    }
+                }
+            }
+        }
+
+        let (entry, exit) = if forward { (input, output) } else { (output, input) };
+        DataflowResults { blocks, entry, exit }
+    }
+
+    /// Blocks reachable from the entry block.
+    pub fn reachable_blocks(&self) -> Vec<ControlFlowBlock> {
+        let mut r: Vec<ControlFlowBlock> = vec![];
+        let mut pending: Vec<ControlFlowBlock> = self.entry_block().into_iter().collect();
+        while let Some(block) = pending.pop() {
+            if r.contains(&block) {
+                continue;
+            }
+            pending.extend(self.successors(&block));
+            r.push(block);
+        }
+        r
+    }
+
+    /// Blocks not reachable from the entry block.
+    pub fn unreachable_blocks(&self) -> Vec<ControlFlowBlock> {
+        let reachable = self.reachable_blocks();
+        self.blocks().iter().filter(|b| !reachable.contains(b)).collect()
+    }
+
+    pub fn dominators(&self) -> Dominators {
+        self.dominators_1(DataflowDirection::Forward)
+    }
+
+    /// Post-dominators, computed relative to the exit blocks.
+    pub fn post_dominators(&self) -> Dominators {
+        self.dominators_1(DataflowDirection::Backward)
+    }
+
+    fn dominators_1(&self, direction: DataflowDirection) -> Dominators {
+        let blocks: Vec<ControlFlowBlock> = self.blocks().iter().collect();
+        let mut analysis = DominatorAnalysis { blocks: blocks.clone(), direction };
+        let results = self.solve_dataflow(&mut analysis);
+        let sets = if direction == DataflowDirection::Forward { results.exit } else { results.entry };
+        Dominators { blocks, sets }
+    }
+
+    /// Detects natural loops from back edges, that is, edges whose
+    /// target dominates their source.
+    pub fn natural_loops(&self) -> Vec<NaturalLoop> {
+        let dominators = self.dominators();
+        let mut r: Vec<NaturalLoop> = vec![];
+        for edge in self.edges().iter() {
+            if !dominators.dominates(&edge.to, &edge.from) {
+                continue;
+            }
+            let header = edge.to.clone();
+            let mut body: Vec<ControlFlowBlock> = vec![header.clone()];
+            let mut pending: Vec<ControlFlowBlock> = vec![edge.from.clone()];
+            while let Some(block) = pending.pop() {
+                if body.contains(&block) {
+                    continue;
+                }
+                pending.extend(self.predecessors(&block));
+                body.push(block);
+            }
+            if let Some(l) = r.iter_mut().find(|l| l.header == header) {
+                l.back_edges.push(edge.clone());
+                for block in body {
+                    if !l.blocks.contains(&block) {
+                        l.blocks.push(block);
+                    }
+                }
+            } else {
+                r.push(NaturalLoop { header, back_edges: vec![edge.clone()], blocks: body });
+            }
+        }
+        r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::semantics::test_fixtures::*;
+
+    /// Builds `entry -> header -> body -> header -> exit`, returning
+    /// the graph and its blocks in that order.
+    fn loop_graph() -> (ControlFlowGraph, [ControlFlowBlock; 4]) {
+        let cfg = ControlFlowGraph::new();
+        let entry = add_block(&cfg, vec![]);
+        let header = add_block(&cfg, vec![]);
+        let body = add_block(&cfg, vec![]);
+        let exit = add_block(&cfg, vec![]);
+        add_edge(&cfg, &entry, &header);
+        add_edge(&cfg, &header, &body);
+        add_edge(&cfg, &body, &header);
+        add_edge(&cfg, &header, &exit);
+        (cfg, [entry, header, body, exit])
+    }
+
+    /// Collects the indices of the blocks a path has gone through.
+    struct VisitedBlocks {
+        blocks: Vec<ControlFlowBlock>,
+        direction: DataflowDirection,
+    }
+
+    impl DataflowAnalysis for VisitedBlocks {
+        type Fact = MaySet<usize>;
+
+        fn direction(&self) -> DataflowDirection {
+            self.direction
+        }
+
+        fn boundary_fact(&self) -> Self::Fact {
+            MaySet(vec![])
+        }
+
+        fn initial_fact(&self) -> Self::Fact {
+            MaySet(vec![])
+        }
+
+        fn transfer(&mut self, block: &ControlFlowBlock, input: &Self::Fact) -> Self::Fact {
+            input.meet(&MaySet(vec![self.blocks.iter().position(|b| b == block).unwrap()]))
+        }
+    }
+
+    #[test]
+    fn solves_loop_to_fixpoint() {
+        let (cfg, [entry, header, body, exit]) = loop_graph();
+        let blocks: Vec<ControlFlowBlock> = cfg.blocks().iter().collect();
+
+        let results = cfg.solve_dataflow(&mut VisitedBlocks { blocks: blocks.clone(), direction: DataflowDirection::Forward });
+        assert_eq!(results.at_entry(&entry), Some(MaySet(vec![])));
+        // The back edge brings the body into the header
+        assert_eq!(results.at_entry(&header), Some(MaySet(vec![0, 1, 2])));
+        assert_eq!(results.at_exit(&exit), Some(MaySet(vec![0, 1, 2, 3])));
+
+        let results = cfg.solve_dataflow(&mut VisitedBlocks { blocks, direction: DataflowDirection::Backward });
+        assert_eq!(results.at_exit(&exit), Some(MaySet(vec![])));
+        assert_eq!(results.at_exit(&body), Some(MaySet(vec![1, 2, 3])));
+        assert_eq!(results.at_entry(&entry), Some(MaySet(vec![0, 1, 2, 3])));
+    }
+
+    #[test]
+    fn computes_dominators() {
+        let (cfg, [entry, header, body, exit]) = loop_graph();
+        let unreachable = add_block(&cfg, vec![]);
+        add_edge(&cfg, &unreachable, &exit);
+
+        let dominators = cfg.dominators();
+        assert!(dominators.dominates(&entry, &exit));
+        assert!(dominators.dominates(&header, &body));
+        assert!(dominators.dominates(&header, &header));
+        assert!(!dominators.dominates(&body, &exit));
+        assert!(!dominators.dominates(&entry, &unreachable));
+        assert!(dominators.dominators_of(&unreachable).is_empty());
+        assert!(dominators.immediate_dominator(&exit) == Some(header.clone()));
+        assert!(dominators.immediate_dominator(&body) == Some(header.clone()));
+        assert!(dominators.immediate_dominator(&entry).is_none());
+
+        let post_dominators = cfg.post_dominators();
+        assert!(post_dominators.dominates(&exit, &entry));
+        assert!(post_dominators.dominates(&header, &body));
+        assert!(!post_dominators.dominates(&body, &header));
+    }
+
+    #[test]
+    fn detects_natural_loops() {
+        let (cfg, [entry, header, body, exit]) = loop_graph();
+        // Nested loop: body -> inner -> body
+        let inner = add_block(&cfg, vec![]);
+        add_edge(&cfg, &body, &inner);
+        add_edge(&cfg, &inner, &body);
+
+        let loops = cfg.natural_loops();
+        assert_eq!(loops.len(), 2);
+        let outer_loop = loops.iter().find(|l| l.header == header).unwrap();
+        assert_eq!(outer_loop.back_edges.len(), 1);
+        assert!(outer_loop.back_edges[0].from == body);
+        assert_eq!(outer_loop.blocks.len(), 3);
+        assert!(outer_loop.blocks.contains(&inner));
+        assert!(!outer_loop.blocks.contains(&entry) && !outer_loop.blocks.contains(&exit));
+
+        let inner_loop = loops.iter().find(|l| l.header == body).unwrap();
+        assert_eq!(inner_loop.blocks.len(), 2);
+        assert!(inner_loop.blocks.contains(&inner));
+    }
+}
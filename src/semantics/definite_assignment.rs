@@ -0,0 +1,566 @@
+use crate::ns::*;
+
+/// Definite assignment analyses over the control flow graph of an activation.
+///
+/// * [`DefiniteAssignment::check_locals`] reports reads of local variables
+///   declared without an initializer before they are assigned on every path.
+/// * [`DefiniteAssignment::check_constructor`] reports `read_only` (`const`) instance
+///   variables that a constructor does not assign exactly once on all paths,
+///   and non-nullable instance variables that it does not assign on all paths.
+///
+/// The update expression of a `for` statement is evaluated on the edges
+/// into the block whose first line is the `for` statement from the blocks
+/// whose lines lie within its body.
+///
+/// Nested functions are not entered.
+pub struct DefiniteAssignment<'a>(pub &'a Database);
+
+/// Issue reported by [`DefiniteAssignment`].
+#[derive(Clone)]
+pub enum DefiniteAssignmentIssue {
+    /// A local variable is read before being definitely assigned.
+    ReadBeforeAssignment {
+        slot: Entity,
+        location: Location,
+    },
+    /// An instance variable is not assigned on all paths of the constructor.
+    FieldNotInitialized {
+        slot: Entity,
+    },
+    /// A `read_only` instance variable is possibly assigned more than once.
+    FieldAssignedMoreThanOnce {
+        slot: Entity,
+        location: Location,
+    },
+}
+
+/// Assignment state of tracked variables at a program point.
+#[derive(Clone, PartialEq)]
+struct AssignmentState {
+    definitely: MustSet<Entity>,
+    possibly: MaySet<Entity>,
+}
+
+impl Lattice for AssignmentState {
+    fn meet(&self, other: &Self) -> Self {
+        Self {
+            definitely: self.definitely.meet(&other.definitely),
+            possibly: self.possibly.meet(&other.possibly),
+        }
+    }
+}
+
+struct AssignmentAnalysis<'a> {
+    db: &'a Database,
+    tracked: Vec<Entity>,
+    /// Whether reads of tracked variables are checked (locals) or
+    /// whether assignments are counted (instance variables).
+    fields: bool,
+    issues: Option<Vec<DefiniteAssignmentIssue>>,
+}
+
+impl<'a> DataflowAnalysis for AssignmentAnalysis<'a> {
+    type Fact = AssignmentState;
+
+    fn direction(&self) -> DataflowDirection {
+        DataflowDirection::Forward
+    }
+
+    fn boundary_fact(&self) -> Self::Fact {
+        AssignmentState { definitely: MustSet::Set(vec![]), possibly: MaySet(vec![]) }
+    }
+
+    fn initial_fact(&self) -> Self::Fact {
+        AssignmentState { definitely: MustSet::Universe, possibly: MaySet(vec![]) }
+    }
+
+    fn transfer(&mut self, block: &ControlFlowBlock, input: &Self::Fact) -> Self::Fact {
+        let mut state = input.clone();
+        for line in block.lines().iter() {
+            self.directive(line, &mut state);
+        }
+        state
+    }
+
+    fn edge_transfer(&mut self, edge: &ControlFlowEdge, fact: &Self::Fact) -> Self::Fact {
+        let mut state = fact.clone();
+        if let Some(update) = loop_update(edge) {
+            self.expression(&update, &mut state);
+        }
+        state
+    }
+}
+
+/// Returns the update expression of a `for` statement evaluated
+/// through an edge from its body back to the statement.
+fn loop_update(edge: &ControlFlowEdge) -> Option<Rc<Expression>> {
+    let first_line = edge.to.lines().first().cloned()?;
+    let Directive::ForStatement(d) = first_line.as_ref() else {
+        return None;
+    };
+    let update = d.update.clone()?;
+    let body_location = d.body.location();
+    if edge.from.lines().iter().any(|line| location_contains(&body_location, &line.location())) {
+        Some(update)
+    } else {
+        None
+    }
+}
+
+impl<'a> DefiniteAssignment<'a> {
+    pub fn check_locals(&self, activation: &Entity) -> Vec<DefiniteAssignmentIssue> {
+        let cfg = activation.control_flow_graph();
+
+        // Track locals declared without an initializer
+        let mut tracked: Vec<Entity> = vec![];
+        for block in cfg.blocks().iter() {
+            for line in block.lines().iter() {
+                let bindings: Vec<Rc<VariableBinding>> = match line.as_ref() {
+                    Directive::VariableDefinition(defn) => defn.bindings.clone(),
+                    Directive::ForStatement(stmt) => match stmt.init.as_ref() {
+                        Some(ForInitializer::VariableDefinition(defn)) => defn.bindings.clone(),
+                        _ => vec![],
+                    },
+                    _ => vec![],
+                };
+                for binding in bindings.iter().filter(|b| b.initializer.is_none()) {
+                    let mut targets: Vec<Entity> = vec![];
+                    destructuring_targets(self.0, &binding.destructuring.destructuring, &mut targets);
+                    for slot in targets.iter().filter_map(slot_of_reference) {
+                        if !activation.property_has_capture(&slot) && !tracked.contains(&slot) {
+                            tracked.push(slot);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut analysis = AssignmentAnalysis { db: self.0, tracked, fields: false, issues: None };
+        self.run(&cfg, &mut analysis);
+        analysis.issues.unwrap_or_default()
+    }
+
+    /// Checks a constructor's activation. `initialized_fields` lists the instance
+    /// variables that are assigned by their declaration's initializer.
+    pub fn check_constructor(&self, activation: &Entity, initialized_fields: &[Entity]) -> Vec<DefiniteAssignmentIssue> {
+        let cfg = activation.control_flow_graph();
+        let Some(class) = activation.of_method().parent() else {
+            return vec![];
+        };
+
+        let mut tracked: Vec<Entity> = vec![];
+        for prop in class.prototype(self.0).borrow().values() {
+            if prop.is::<VariableSlot>() && !initialized_fields.contains(prop)
+            && (prop.read_only(self.0) || prop.static_type(self.0).is::<NonNullableType>()) {
+                tracked.push(prop.clone());
+            }
+        }
+
+        let mut analysis = AssignmentAnalysis { db: self.0, tracked: tracked.clone(), fields: true, issues: None };
+        let results = self.run(&cfg, &mut analysis);
+        let mut issues = analysis.issues.unwrap_or_default();
+
+        // Every tracked variable must be assigned at the exit blocks. Without
+        // a reachable exit block or a result for it, nothing is initialized.
+        let reachable_blocks = cfg.reachable_blocks();
+        let mut exit_blocks = cfg.exit_blocks();
+        exit_blocks.retain(|b| reachable_blocks.contains(b));
+        for slot in tracked.iter() {
+            let initialized = !exit_blocks.is_empty() && exit_blocks.iter().all(|b| {
+                results.at_exit(b).map(|s| s.definitely.contains(slot)).unwrap_or(false)
+            });
+            if !initialized {
+                issues.push(DefiniteAssignmentIssue::FieldNotInitialized { slot: slot.clone() });
+            }
+        }
+        issues
+    }
+
+    /// Solves the analysis, then replays every reached block and every
+    /// edge out of a reached block to collect issues.
+    fn run(&self, cfg: &ControlFlowGraph, analysis: &mut AssignmentAnalysis) -> DataflowResults<AssignmentState> {
+        let results = cfg.solve_dataflow(analysis);
+        analysis.issues = Some(vec![]);
+        let reachable_blocks = cfg.reachable_blocks();
+        for block in reachable_blocks.iter() {
+            if let Some(state) = results.at_entry(block) {
+                analysis.transfer(block, &state);
+            }
+        }
+        for edge in cfg.edges().iter().filter(|e| reachable_blocks.contains(&e.from)) {
+            if let Some(state) = results.at_exit(&edge.from) {
+                analysis.edge_transfer(&edge, &state);
+            }
+        }
+        results
+    }
+}
+
+impl<'a> AssignmentAnalysis<'a> {
+    fn tracked_slot(&self, exp: &Rc<Expression>) -> Option<Entity> {
+        let slot = slot_of_reference(&self.db.node_mapping().get(exp)?)?;
+        if self.tracked.contains(&slot) { Some(slot) } else { None }
+    }
+
+    fn read(&mut self, exp: &Rc<Expression>, state: &AssignmentState) {
+        let slot = self.tracked_slot(exp);
+        self.read_slot(slot, exp.location(), state);
+    }
+
+    /// Checks the read of a shorthand field of an object initializer.
+    fn read_field(&mut self, field: &Rc<InitializerField>, state: &AssignmentState) {
+        let slot = self.db.node_mapping().get(field).and_then(|r| slot_of_reference(&r)).filter(|slot| self.tracked.contains(slot));
+        self.read_slot(slot, field.location(), state);
+    }
+
+    fn read_slot(&mut self, slot: Option<Entity>, location: Location, state: &AssignmentState) {
+        if self.fields {
+            return;
+        }
+        if let Some(slot) = slot {
+            if !state.definitely.contains(&slot) {
+                if let Some(issues) = self.issues.as_mut() {
+                    issues.push(DefiniteAssignmentIssue::ReadBeforeAssignment { slot, location });
+                }
+            }
+        }
+    }
+
+    /// Records the assignment of the tracked variables a destructuring
+    /// pattern assigns to.
+    fn write_pattern(&mut self, pattern: &Rc<Expression>, state: &mut AssignmentState) {
+        let mut targets: Vec<Entity> = vec![];
+        destructuring_targets(self.db, pattern, &mut targets);
+        for slot in targets.iter().filter_map(slot_of_reference) {
+            if self.tracked.contains(&slot) {
+                self.write(slot, pattern.location(), state);
+            }
+        }
+    }
+
+    fn write(&mut self, slot: Entity, location: Location, state: &mut AssignmentState) {
+        if self.fields && slot.read_only(self.db) && state.possibly.0.contains(&slot) {
+            if let Some(issues) = self.issues.as_mut() {
+                issues.push(DefiniteAssignmentIssue::FieldAssignedMoreThanOnce { slot: slot.clone(), location });
+            }
+        }
+        state.definitely.insert(slot.clone());
+        if !state.possibly.0.contains(&slot) {
+            state.possibly.0.push(slot);
+        }
+    }
+
+    /// Visits a line. Sub-directives of compound statements are
+    /// lines of other blocks and are not visited.
+    fn directive(&mut self, directive: &Rc<Directive>, state: &mut AssignmentState) {
+        match directive.as_ref() {
+            Directive::ExpressionStatement(d) => self.expression(&d.expression, state),
+            Directive::IfStatement(d) => self.expression(&d.test, state),
+            Directive::WhileStatement(d) => self.expression(&d.test, state),
+            Directive::DoStatement(d) => self.expression(&d.test, state),
+            Directive::SwitchStatement(d) => self.expression(&d.discriminant, state),
+            Directive::WithStatement(d) => self.expression(&d.object, state),
+            Directive::ThrowStatement(d) => self.expression(&d.expression, state),
+            Directive::ReturnStatement(d) => {
+                if let Some(exp) = d.expression.as_ref() {
+                    self.expression(exp, state);
+                }
+            },
+            Directive::SuperStatement(d) => {
+                for arg in d.arguments.iter() {
+                    self.expression(arg, state);
+                }
+            },
+            Directive::VariableDefinition(d) => {
+                for binding in d.bindings.iter() {
+                    self.binding(binding, state);
+                }
+            },
+            Directive::ForStatement(d) => {
+                match d.init.as_ref() {
+                    Some(ForInitializer::Expression(exp)) => self.expression(exp, state),
+                    Some(ForInitializer::VariableDefinition(defn)) => {
+                        for binding in defn.bindings.iter() {
+                            self.binding(binding, state);
+                        }
+                    },
+                    None => {},
+                }
+                if let Some(exp) = d.test.as_ref() {
+                    self.expression(exp, state);
+                }
+            },
+            Directive::ForInStatement(d) => {
+                self.expression(&d.right, state);
+                match &d.left {
+                    ForInBinding::Expression(exp) => self.write_pattern(exp, state),
+                    ForInBinding::VariableDefinition(defn) => {
+                        for binding in defn.bindings.iter() {
+                            self.write_pattern(&binding.destructuring.destructuring, state);
+                        }
+                    },
+                }
+            },
+            Directive::DefaultXmlNamespaceStatement(d) => self.expression(&d.right, state),
+            _ => {},
+        }
+    }
+
+    fn binding(&mut self, binding: &Rc<VariableBinding>, state: &mut AssignmentState) {
+        let Some(init) = binding.initializer.as_ref() else {
+            return;
+        };
+        self.expression(init, state);
+        self.write_pattern(&binding.destructuring.destructuring, state);
+    }
+
+    /// Visits an expression in evaluation order.
+    fn expression(&mut self, exp: &Rc<Expression>, state: &mut AssignmentState) {
+        match exp.as_ref() {
+            Expression::QualifiedIdentifier(_) => {
+                // Computed qualifiers and names are evaluated first
+                for exp in subexpressions(exp) {
+                    self.expression(&exp, state);
+                }
+                self.read(exp, state);
+            },
+            Expression::Paren(e) => self.expression(&e.expression, state),
+            Expression::Assignment(e) if e.compound.is_none() && matches!(e.left.as_ref(), Expression::ArrayLiteral(_) | Expression::ObjectInitializer(_)) => {
+                // Destructuring assignment
+                self.expression(&e.right, state);
+                self.write_pattern(&e.left, state);
+            },
+            Expression::Assignment(e) => {
+                let slot = self.tracked_slot(&e.left);
+                if slot.is_none() {
+                    self.expression(&e.left, state);
+                } else if e.compound.is_some() {
+                    self.read(&e.left, state);
+                }
+                self.expression(&e.right, state);
+                if let Some(slot) = slot {
+                    self.write(slot, e.left.location(), state);
+                }
+            },
+            Expression::Unary(e) => {
+                self.expression(&e.expression, state);
+                if matches!(e.operator, Operator::PreIncrement | Operator::PreDecrement | Operator::PostIncrement | Operator::PostDecrement) {
+                    if let Some(slot) = self.tracked_slot(&e.expression) {
+                        self.write(slot, e.expression.location(), state);
+                    }
+                }
+            },
+            Expression::Binary(e) => {
+                self.expression(&e.left, state);
+                if matches!(e.operator, Operator::LogicalAnd | Operator::LogicalOr | Operator::LogicalXor | Operator::NullCoalescing) {
+                    // The right operand is conditionally evaluated
+                    let mut right_state = state.clone();
+                    self.expression(&e.right, &mut right_state);
+                    *state = state.meet(&right_state);
+                } else {
+                    self.expression(&e.right, state);
+                }
+            },
+            Expression::Conditional(e) => {
+                self.expression(&e.test, state);
+                let mut consequent_state = state.clone();
+                self.expression(&e.consequent, &mut consequent_state);
+                let mut alternative_state = state.clone();
+                self.expression(&e.alternative, &mut alternative_state);
+                *state = consequent_state.meet(&alternative_state);
+            },
+            Expression::OptionalChaining(e) => {
+                self.expression(&e.base, state);
+                // The chained operations are conditionally evaluated
+                let mut chain_state = state.clone();
+                self.expression(&e.expression, &mut chain_state);
+                *state = state.meet(&chain_state);
+            },
+            Expression::ObjectInitializer(e) => {
+                for field in e.fields.iter() {
+                    if let InitializerField::Field { value: None, .. } = field.as_ref() {
+                        // Shorthand field
+                        self.read_field(field, state);
+                    }
+                }
+                for exp in subexpressions(exp) {
+                    self.expression(&exp, state);
+                }
+            },
+            _ => {
+                for exp in subexpressions(exp) {
+                    self.expression(&exp, state);
+                }
+            },
+        }
+    }
+}
+
+/// Returns the variable slot a node mapping result refers to.
+fn slot_of_reference(r: &Entity) -> Option<Entity> {
+    if r.is::<VariableSlot>() {
+        Some(r.clone())
+    } else if r.is::<FixtureReferenceValue>() && r.property().is::<VariableSlot>() {
+        Some(r.property())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::semantics::test_fixtures::*;
+
+    /// Checks the locals of directives forming a single block,
+    /// with the identifier `x` mapped to a local.
+    fn check_straight_line(db: &Database, source: &str) -> Vec<DefiniteAssignmentIssue> {
+        let activation = define_activation(db);
+        let x = define_local(db, &activation, "x");
+        let directives = parse_directives(source);
+        map_identifiers(db, &directives, &|name| (name == "x").then(|| x.clone()));
+        add_block(&activation.control_flow_graph(), directives);
+        DefiniteAssignment(db).check_locals(&activation)
+    }
+
+    /// Checks the locals of `var x;` followed by a `for` statement, whose
+    /// body is a block, followed by the remaining directives.
+    fn check_for_loop(db: &Database, source: &str) -> Vec<DefiniteAssignmentIssue> {
+        let activation = define_activation(db);
+        let x = define_local(db, &activation, "x");
+        let directives = parse_directives(source);
+        map_identifiers(db, &directives, &|name| (name == "x").then(|| x.clone()));
+        let Directive::ForStatement(d) = directives[1].as_ref() else {
+            panic!("expected a for statement");
+        };
+        let cfg = activation.control_flow_graph();
+        let entry = add_block(&cfg, vec![directives[0].clone()]);
+        let header = add_block(&cfg, vec![directives[1].clone()]);
+        let body = add_block(&cfg, block_directives(&d.body));
+        let exit = add_block(&cfg, directives[2..].to_vec());
+        add_edge(&cfg, &entry, &header);
+        add_edge(&cfg, &header, &body);
+        add_edge(&cfg, &body, &header);
+        add_edge(&cfg, &header, &exit);
+        DefiniteAssignment(db).check_locals(&activation)
+    }
+
+    fn read_count(issues: &[DefiniteAssignmentIssue]) -> usize {
+        issues.iter().filter(|i| matches!(i, DefiniteAssignmentIssue::ReadBeforeAssignment { .. })).count()
+    }
+
+    #[test]
+    fn reports_reads_before_assignment() {
+        let db = Database::new(DatabaseOptions::default());
+        assert_eq!(read_count(&check_straight_line(&db, "var x; x;")), 1);
+        assert_eq!(read_count(&check_straight_line(&db, "var x; x = 1; x;")), 0);
+        assert_eq!(read_count(&check_straight_line(&db, "var x; x += 1;")), 1);
+        assert_eq!(read_count(&check_straight_line(&db, "var x; c && (x = 1); x;")), 1);
+    }
+
+    #[test]
+    fn reports_reads_in_every_subexpression() {
+        let db = Database::new(DatabaseOptions::default());
+        for source in [
+            "var x; o?.f(x);",
+            "var x; o.(x);",
+            "var x; o..[x];",
+            "var x; o.ns::[x];",
+            "var x; o.<T>[x];",
+            "var x; <a>{x}</a>;",
+            "var x; <a b={x}/>;",
+            "var x; ({ x });",
+            "var x; ({ [x]: 1 });",
+        ] {
+            assert_eq!(read_count(&check_straight_line(&db, source)), 1, "{source}");
+        }
+    }
+
+    #[test]
+    fn conditionally_assigns_in_optional_chaining() {
+        let db = Database::new(DatabaseOptions::default());
+        assert_eq!(read_count(&check_straight_line(&db, "var x; o?.f(x = 1); x;")), 1);
+    }
+
+    #[test]
+    fn assigns_through_destructuring() {
+        let db = Database::new(DatabaseOptions::default());
+        assert_eq!(read_count(&check_straight_line(&db, "var x; [x] = [1]; x;")), 0);
+        assert_eq!(read_count(&check_straight_line(&db, "var x; ({ x } = { x: 1 }); x;")), 0);
+        assert_eq!(read_count(&check_straight_line(&db, "var x; ({ k: [x] } = o); x;")), 0);
+    }
+
+    #[test]
+    fn evaluates_for_update_after_the_body() {
+        let db = Database::new(DatabaseOptions::default());
+        // The update is not evaluated before the first iteration
+        assert_eq!(read_count(&check_for_loop(&db, "var x; for (; c; x = 1) { x; }")), 1);
+        // The update reads what the body assigns
+        assert_eq!(read_count(&check_for_loop(&db, "var x; for (; c; x++) { x = 0; }")), 0);
+        assert_eq!(read_count(&check_for_loop(&db, "var x; for (; c; f(x)) { g(); }")), 1);
+    }
+
+    /// Checks a constructor of a class with a constant instance variable `a`
+    /// whose activation consists of the directives forming a single block.
+    fn check_constructor(db: &Database, source: &str) -> (Vec<DefiniteAssignmentIssue>, Entity) {
+        let activation = define_activation(db);
+        let ns = db.top_level_package().public_ns().unwrap();
+        let class = db.factory().create_class_type(db.factory().create_qname(&ns, "C".into()), &ns);
+        activation.of_method().set_parent(Some(class.clone()));
+        let name = db.factory().create_qname(&ns, "a".into());
+        let a = db.factory().create_variable_slot(&name, true, &db.any_type());
+        a.set_parent(Some(class.clone()));
+        class.prototype(db).set(name, a.clone());
+
+        let directives = parse_directives(source);
+        map_identifiers(db, &directives, &|name| (name == "a").then(|| a.clone()));
+        add_block(&activation.control_flow_graph(), directives);
+        (DefiniteAssignment(db).check_constructor(&activation, &[]), a)
+    }
+
+    #[test]
+    fn checks_constructor_assignments() {
+        let db = Database::new(DatabaseOptions::default());
+        let (issues, _) = check_constructor(&db, "a = 1;");
+        assert!(issues.is_empty());
+
+        let (issues, a) = check_constructor(&db, "g();");
+        assert!(matches!(&issues[..], [DefiniteAssignmentIssue::FieldNotInitialized { slot }] if slot == &a));
+
+        let (issues, a) = check_constructor(&db, "a = 1; a = 2;");
+        assert!(matches!(&issues[..], [DefiniteAssignmentIssue::FieldAssignedMoreThanOnce { slot, .. }] if slot == &a));
+
+        let (issues, _) = check_constructor(&db, "[a] = [1];");
+        assert!(issues.is_empty());
+
+        let (issues, a) = check_constructor(&db, "o?.f(a = 1);");
+        assert!(matches!(&issues[..], [DefiniteAssignmentIssue::FieldNotInitialized { slot }] if slot == &a));
+    }
+
+    #[test]
+    fn checks_constructor_branches() {
+        let db = Database::new(DatabaseOptions::default());
+        let activation = define_activation(&db);
+        let ns = db.top_level_package().public_ns().unwrap();
+        let class = db.factory().create_class_type(db.factory().create_qname(&ns, "C".into()), &ns);
+        activation.of_method().set_parent(Some(class.clone()));
+        let name = db.factory().create_qname(&ns, "a".into());
+        let a = db.factory().create_variable_slot(&name, true, &db.any_type());
+        class.prototype(&db).set(name, a.clone());
+
+        let directives = parse_directives("if (c) { a = 1; }");
+        map_identifiers(&db, &directives, &|name| (name == "a").then(|| a.clone()));
+        let Directive::IfStatement(d) = directives[0].as_ref() else {
+            panic!("expected an if statement");
+        };
+        let cfg = activation.control_flow_graph();
+        let test = add_block(&cfg, directives.clone());
+        let consequent = add_block(&cfg, block_directives(&d.consequent));
+        let exit = add_block(&cfg, vec![]);
+        add_edge(&cfg, &test, &consequent);
+        add_edge(&cfg, &test, &exit);
+        add_edge(&cfg, &consequent, &exit);
+        let issues = DefiniteAssignment(&db).check_constructor(&activation, &[]);
+        assert!(matches!(&issues[..], [DefiniteAssignmentIssue::FieldNotInitialized { slot }] if slot == &a));
+    }
+}
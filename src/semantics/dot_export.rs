@@ -0,0 +1,164 @@
+use crate::ns::*;
+
+/// Graphviz DOT exporters used for debugging control flow graph
+/// construction and class hierarchies.
+pub struct DotExport<'a>(pub &'a Database);
+
+impl<'a> DotExport<'a> {
+    /// Exports the control flow graph of an activation. Each block is labelled
+    /// with the source ranges of its directives, in the form
+    /// `line:column-line:column`, where lines are counted from one and
+    /// columns from zero.
+    pub fn control_flow_graph(&self, activation: &Entity) -> String {
+        let cfg = activation.control_flow_graph();
+        let blocks: Vec<ControlFlowBlock> = cfg.blocks().iter().collect();
+        let mut r = String::from("digraph cfg {\n    node [shape=box, fontname=\"monospace\"];\n");
+        for (i, block) in blocks.iter().enumerate() {
+            let mut label = format!("B{i}");
+            for line in block.lines().iter() {
+                let loc = line.location();
+                label.push_str(&format!("\\n{}:{}-{}:{}", loc.first_line_number(), loc.first_column(), loc.last_line_number(), loc.last_column()));
+            }
+            let shape = if i == 0 { ", peripheries=2" } else { "" };
+            r.push_str(&format!("    b{i} [label=\"{label}\"{shape}];\n"));
+        }
+        for edge in cfg.edges().iter() {
+            let Some(from) = blocks.iter().position(|b| b == &edge.from) else {
+                continue;
+            };
+            let Some(to) = blocks.iter().position(|b| b == &edge.to) else {
+                continue;
+            };
+            r.push_str(&format!("    b{from} -> b{to};\n"));
+        }
+        r.push_str("}\n");
+        r
+    }
+
+    /// Exports the hierarchy rooted at a class: all known subclasses,
+    /// with edges to the class they extend (`extends_class()`), and the interfaces
+    /// they implement (`implements()`), with dashed edges, including
+    /// the interfaces these extend.
+    pub fn class_hierarchy(&self, class: &Entity) -> String {
+        let mut nodes: Vec<Entity> = vec![];
+        let mut edges: Vec<(usize, usize, bool)> = vec![];
+        let root = if class.is::<TypeAfterSubstitution>() { class.origin() } else { class.clone() };
+        self.node_index(&root, &mut nodes);
+
+        let mut i = 0usize;
+        while i < nodes.len() {
+            let t = nodes[i].clone();
+            if t.is::<ClassType>() {
+                if t != root {
+                    if let Some(super_class) = t.extends_class(self.0).filter(|c| !c.is::<UnresolvedEntity>()) {
+                        let j = self.node_index(&super_class, &mut nodes);
+                        edges.push((i, j, false));
+                    }
+                }
+                for itrfc in t.implements(self.0).iter().filter(|t| !t.is::<UnresolvedEntity>()) {
+                    let j = self.node_index(&itrfc, &mut nodes);
+                    edges.push((i, j, true));
+                }
+                for subclass in t.known_subclasses().iter() {
+                    self.node_index(&subclass, &mut nodes);
+                }
+            } else if t.is::<InterfaceType>() {
+                for itrfc in t.extends_interfaces(self.0).iter().filter(|t| !t.is::<UnresolvedEntity>()) {
+                    let j = self.node_index(&itrfc, &mut nodes);
+                    edges.push((i, j, true));
+                }
+            }
+            i += 1;
+        }
+
+        let mut r = String::from("digraph hierarchy {\n    rankdir=BT;\n    node [shape=box];\n");
+        for (i, t) in nodes.iter().enumerate() {
+            let style = if t.is::<InterfaceType>() { ", style=rounded" } else { "" };
+            r.push_str(&format!("    t{i} [label=\"{}\"{style}];\n", escape_label(&t.to_string())));
+        }
+        for (from, to, dashed) in edges {
+            let style = if dashed { " [style=dashed]" } else { "" };
+            r.push_str(&format!("    t{from} -> t{to}{style};\n"));
+        }
+        r.push_str("}\n");
+        r
+    }
+
+    fn node_index(&self, t: &Entity, nodes: &mut Vec<Entity>) -> usize {
+        let t = if t.is::<TypeAfterSubstitution>() { t.origin() } else { t.clone() };
+        if let Some(i) = nodes.iter().position(|t1| t1 == &t) {
+            return i;
+        }
+        nodes.push(t);
+        nodes.len() - 1
+    }
+}
+
+fn escape_label(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::semantics::test_fixtures::*;
+
+    #[test]
+    fn exports_control_flow_graph() {
+        let db = Database::new(DatabaseOptions::default());
+        let activation = define_activation(&db);
+        let cfg = activation.control_flow_graph();
+        let directives = parse_directives("f();\nif (c)\n    g();");
+        let entry = add_block(&cfg, directives.clone());
+        let Directive::IfStatement(d) = directives[1].as_ref() else {
+            panic!("expected an if statement");
+        };
+        let consequent = add_block(&cfg, vec![d.consequent.clone()]);
+        let exit = add_block(&cfg, vec![]);
+        add_edge(&cfg, &entry, &consequent);
+        add_edge(&cfg, &entry, &exit);
+        add_edge(&cfg, &consequent, &exit);
+
+        assert_eq!(DotExport(&db).control_flow_graph(&activation), concat!(
+            "digraph cfg {\n",
+            "    node [shape=box, fontname=\"monospace\"];\n",
+            "    b0 [label=\"B0\\n1:0-1:4\\n2:0-3:8\", peripheries=2];\n",
+            "    b1 [label=\"B1\\n3:4-3:8\"];\n",
+            "    b2 [label=\"B2\"];\n",
+            "    b0 -> b1;\n",
+            "    b0 -> b2;\n",
+            "    b1 -> b2;\n",
+            "}\n",
+        ));
+    }
+
+    #[test]
+    fn exports_class_hierarchy() {
+        let db = Database::new(DatabaseOptions::default());
+        let ns = db.top_level_package().public_ns().unwrap();
+        let qname = |name: &str| db.factory().create_qname(&ns, name.into());
+        let a = db.factory().create_class_type(qname("A"), &ns);
+        let b = db.factory().create_class_type(qname("B"), &ns);
+        let i = db.factory().create_interface_type(qname("I"));
+        // Quotes in labels are escaped
+        let j = db.factory().create_interface_type(qname("J\"s"));
+        b.set_extends_class(Some(a.clone()));
+        a.known_subclasses().push(b.clone());
+        b.implements(&db).push(i.clone());
+        i.extends_interfaces(&db).push(j.clone());
+
+        assert_eq!(DotExport(&db).class_hierarchy(&a), concat!(
+            "digraph hierarchy {\n",
+            "    rankdir=BT;\n",
+            "    node [shape=box];\n",
+            "    t0 [label=\"A\"];\n",
+            "    t1 [label=\"B\"];\n",
+            "    t2 [label=\"I\", style=rounded];\n",
+            "    t3 [label=\"J\\\"s\", style=rounded];\n",
+            "    t1 -> t0;\n",
+            "    t1 -> t2 [style=dashed];\n",
+            "    t2 -> t3 [style=dashed];\n",
+            "}\n",
+        ));
+    }
+}
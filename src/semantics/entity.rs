@@ -158,6 +158,10 @@ smodel! {
             panic!();
         }
 
+        /// Marks every property as not captured. Does nothing for
+        /// entities other than activations.
+        pub fn clear_property_has_capture(&self) {}
+
         pub fn concat_open_ns_set_of_scope_chain(&self) -> SharedArray<Entity> {
             let mut open_ns_set = SharedArray::new();
             open_ns_set.extend(self.open_ns_set().iter());
@@ -577,6 +581,16 @@ smodel! {
             panic!();
         }
 
+        /// Names of the parameters of a method, in order, used for display.
+        /// Empty if unknown.
+        pub fn param_names(&self) -> SharedArray<String> {
+            SharedArray::new()
+        }
+
+        pub fn set_param_names(&self, names: SharedArray<String>) {
+            panic!();
+        }
+
         pub fn set_activation(&self, activation: Option<Entity>) {
             panic!();
         }
@@ -716,6 +730,15 @@ smodel! {
         pub fn set_type_params(&self, list: Option<SharedArray<Entity>>) {
         }
 
+        /// The constraint (upper bound) of a type parameter, as in `T extends C`.
+        pub fn constraint(&self) -> Option<Entity> {
+            None
+        }
+
+        pub fn set_constraint(&self, constraint: Option<Entity>) {
+            panic!();
+        }
+
         pub fn enum_member_number_mapping(&self) -> SharedMap<String, Number> {
             panic!();
         }
@@ -806,6 +829,23 @@ smodel! {
             if possibly_ascending_type.is::<AnyType>() {
                 return Ok(true);
             }
+            // Structural subtyping
+            if self.is::<FunctionType>() && possibly_ascending_type.is::<FunctionType>() && self != possibly_ascending_type {
+                return self.is_structural_function_subtype_of(possibly_ascending_type, host);
+            }
+            if self.is::<TupleType>() && possibly_ascending_type.is::<TupleType>() && self != possibly_ascending_type {
+                let element_types_1 = self.element_types();
+                let element_types_2 = possibly_ascending_type.element_types();
+                if element_types_1.length() != element_types_2.length() {
+                    return Ok(false);
+                }
+                for (t1, t2) in element_types_1.iter().zip(element_types_2.iter()) {
+                    if !t1.is_structural_component_subtype_of(&t2, host)? {
+                        return Ok(false);
+                    }
+                }
+                return Ok(true);
+            }
             for t in self.all_ascending_types(host) {
                 // Defer if unresolved
                 t.defer()?;
@@ -820,6 +860,91 @@ smodel! {
         pub fn is_equals_or_subtype_of(&self, other: &Entity, host: &Database) -> Result<bool, DeferError> {
             Ok(self == other || self.is_subtype_of(other, host)?)
         }
+
+        /// Structural subtyping of function types: parameters are contravariant
+        /// and the result type is covariant. Every argument list accepted by
+        /// the ascending function type must be accepted by the subtype.
+        fn is_structural_function_subtype_of(&self, possibly_ascending_type: &Entity, host: &Database) -> Result<bool, DeferError> {
+            if self.type_params().is_some() || possibly_ascending_type.type_params().is_some() {
+                return Ok(false);
+            }
+
+            // Covariant result type. Any result type is assignable to void.
+            let result_type_1 = self.result_type();
+            let result_type_2 = possibly_ascending_type.result_type();
+            if !(result_type_2.is::<VoidType>() || result_type_1.is_structural_component_subtype_of(&result_type_2, host)?) {
+                return Ok(false);
+            }
+
+            let params_1: Vec<Rc<SemanticFunctionTypeParameter>> = self.params().iter().collect();
+            let params_2: Vec<Rc<SemanticFunctionTypeParameter>> = possibly_ascending_type.params().iter().collect();
+            let rest_1 = params_1.last().filter(|p| p.kind == ParameterKind::Rest).cloned();
+            let rest_2 = params_2.last().filter(|p| p.kind == ParameterKind::Rest).cloned();
+            let positional_1: Vec<_> = params_1.iter().filter(|p| p.kind != ParameterKind::Rest).cloned().collect();
+            let positional_2: Vec<_> = params_2.iter().filter(|p| p.kind != ParameterKind::Rest).cloned().collect();
+
+            // Every required parameter of the subtype must be
+            // a required parameter of the ascending type.
+            for (i, p1) in positional_1.iter().enumerate() {
+                if p1.kind == ParameterKind::Required && positional_2.get(i).map(|p2| p2.kind != ParameterKind::Required).unwrap_or(true) {
+                    return Ok(false);
+                }
+            }
+
+            // Contravariant positional parameters
+            for (i, p2) in positional_2.iter().enumerate() {
+                let param_type_1 = if let Some(p1) = positional_1.get(i) {
+                    p1.static_type.clone()
+                } else if let Some(rest_1) = rest_1.as_ref() {
+                    rest_1.static_type.escape_of_nullable_or_non_nullable().array_element_type(host)?.unwrap_or(host.any_type())
+                } else {
+                    return Ok(false);
+                };
+                if !p2.static_type.is_structural_component_subtype_of(&param_type_1, host)? {
+                    return Ok(false);
+                }
+            }
+
+            // Arguments passed to the rest parameter of the ascending type
+            // must be accepted by the remaining parameters of the subtype.
+            if let Some(rest_2) = rest_2 {
+                let Some(rest_1) = rest_1 else {
+                    return Ok(false);
+                };
+                let element_type_2 = rest_2.static_type.escape_of_nullable_or_non_nullable().array_element_type(host)?.unwrap_or(host.any_type());
+                for p1 in positional_1.iter().skip(positional_2.len()) {
+                    if !element_type_2.is_structural_component_subtype_of(&p1.static_type, host)? {
+                        return Ok(false);
+                    }
+                }
+                let element_type_1 = rest_1.static_type.escape_of_nullable_or_non_nullable().array_element_type(host)?.unwrap_or(host.any_type());
+                if !element_type_2.is_structural_component_subtype_of(&element_type_1, host)? {
+                    return Ok(false);
+                }
+            }
+
+            Ok(true)
+        }
+
+        /// Subtyping of a component of a structural type. A non-nullable
+        /// type is a subtype of its nullable form, but not the reverse.
+        fn is_structural_component_subtype_of(&self, other: &Entity, host: &Database) -> Result<bool, DeferError> {
+            // Defer if unresolved
+            self.defer()?;
+            other.defer()?;
+
+            if self.is::<InvalidationEntity>() || other.is::<InvalidationEntity>() {
+                return Ok(true);
+            }
+            if other.is::<NonNullableType>() && !self.is::<NonNullableType>() {
+                if self.is::<NullableType>() || self.includes_null(host)? {
+                    return Ok(false);
+                }
+            }
+            let t1 = self.escape_of_nullable_or_non_nullable();
+            let t2 = other.escape_of_nullable_or_non_nullable();
+            t1.is_equals_or_subtype_of(&t2, host)
+        }
     
         /// Returns all ascending types of a type in ascending type order,
         /// each possibly unresolved.
@@ -864,6 +989,8 @@ smodel! {
                 return vec![host.function_type()];
             } else if self.is::<TupleType>() {
                 return vec![host.array_type_of_any().unwrap_or(host.unresolved_entity())];
+            } else if self.is::<TypeParameterType>() {
+                return self.constraint().into_iter().collect();
             } else if self.is::<InvalidationEntity>() {
                 return vec![];
             }
@@ -2047,7 +2174,12 @@ smodel! {
             let origin = self.origin();
             let mut r = Names::new();
             for (name, entity) in origin.prototype(host).borrow().iter() {
-                let entity = ApplyType(host).exec(&entity, &origin.type_params().unwrap(), &self.m_substitute_types());
+                // Entities such as namespaces are not affected by substitution
+                let entity = match ApplyType(host).try_exec(&entity, &origin.type_params().unwrap(), &self.m_substitute_types()) {
+                    Ok(r) => r,
+                    Err(ApplyTypeError::EntityAccess(_)) => entity.clone(),
+                    Err(_) => ApplyType(host).exec(&entity, &origin.type_params().unwrap(), &self.m_substitute_types()),
+                };
                 r.set(name.clone(), entity)
             }
             self.set_m_prototype(Some(r.clone()));
@@ -2061,7 +2193,12 @@ smodel! {
             let origin = self.origin();
             let mut r = Names::new();
             for (name, entity) in origin.properties(host).borrow().iter() {
-                let entity = ApplyType(host).exec(&entity, &origin.type_params().unwrap(), &self.m_substitute_types());
+                // Entities such as namespaces are not affected by substitution
+                let entity = match ApplyType(host).try_exec(&entity, &origin.type_params().unwrap(), &self.m_substitute_types()) {
+                    Ok(r) => r,
+                    Err(ApplyTypeError::EntityAccess(_)) => entity.clone(),
+                    Err(_) => ApplyType(host).exec(&entity, &origin.type_params().unwrap(), &self.m_substitute_types()),
+                };
                 r.set(name.clone(), entity)
             }
             self.set_m_properties(Some(r.clone()));
@@ -2227,14 +2364,21 @@ smodel! {
     /// Structural function type. This type is equivalent to `Function`
     /// with type safety.
     pub struct FunctionType: Type {
+        let ref m_type_params: Option<SharedArray<Entity>> = None;
         let ref m_params: SharedArray<Rc<SemanticFunctionTypeParameter>> = SharedArray::new();
         let ref m_result_type: Option<Entity> = None;
 
-        pub(crate) fn FunctionType(params: SharedArray<Rc<SemanticFunctionTypeParameter>>, result_type: Entity) {
+        pub(crate) fn FunctionType(type_params: Option<SharedArray<Entity>>, params: SharedArray<Rc<SemanticFunctionTypeParameter>>, result_type: Entity) {
             super();
+            self.set_m_type_params(type_params);
             self.set_m_params(params);
             self.set_m_result_type(Some(result_type));
         }
+
+        /// Type parameters of a generic function type.
+        pub override fn type_params(&self) -> Option<SharedArray<Entity>> {
+            self.m_type_params()
+        }
         
         pub override fn params(&self) -> SharedArray<Rc<SemanticFunctionTypeParameter>> {
             self.m_params()
@@ -2300,7 +2444,8 @@ smodel! {
                     },
                 }
             }
-            format!("function({}) : {}", p.join(", "), self.result_type().to_string())
+            let type_params = self.type_params().map(|list| format!(".<{}>", list.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(", "))).unwrap_or_default();
+            format!("function{}({}) : {}", type_params, p.join(", "), self.result_type().to_string())
         }
     }
 
@@ -2405,6 +2550,7 @@ smodel! {
     pub struct TypeParameterType: Type {
         let ref m_name: Option<QName> = None;
         let ref m_location: Option<Location> = None;
+        let ref m_constraint: Option<Entity> = None;
 
         pub(crate) fn TypeParameterType(name: QName) {
             super();
@@ -2423,6 +2569,15 @@ smodel! {
             self.set_m_location(loc);
         }
 
+        /// Possibly unresolved.
+        pub override fn constraint(&self) -> Option<Entity> {
+            self.m_constraint()
+        }
+
+        pub override fn set_constraint(&self, constraint: Option<Entity>) {
+            self.set_m_constraint(constraint);
+        }
+
         pub override fn includes_undefined(&self) -> Result<bool, DeferError> {
             Ok(false)
         }
@@ -2893,6 +3048,8 @@ smodel! {
         let ref m_of_virtual_slot: Option<Entity> = None;
         let ref m_overriden_by: SharedArray<Entity> = SharedArray::new();
         let ref m_overrides_method: Option<Entity> = None;
+        let ref m_type_params: Option<SharedArray<Entity>> = None;
+        let ref m_param_names: SharedArray<String> = SharedArray::new();
         let m_flags: MethodSlotFlags = MethodSlotFlags::empty();
 
         pub(crate) fn OriginalMethodSlot(name: &QName, signature: &Entity) {
@@ -3021,6 +3178,15 @@ smodel! {
             self.set_m_signature(Some(signature.clone()));
         }
 
+        /// Type parameters of a generic method.
+        pub override fn type_params(&self) -> Option<SharedArray<Entity>> {
+            self.m_type_params()
+        }
+
+        pub override fn set_type_params(&self, list: Option<SharedArray<Entity>>) {
+            self.set_m_type_params(list);
+        }
+
         pub override fn activation(&self) -> Option<Entity> {
             self.m_activation()
         }
@@ -3029,6 +3195,14 @@ smodel! {
             self.set_m_activation(activation);
         }
 
+        pub override fn param_names(&self) -> SharedArray<String> {
+            self.m_param_names()
+        }
+
+        pub override fn set_param_names(&self, names: SharedArray<String>) {
+            self.set_m_param_names(names);
+        }
+
         pub override fn of_virtual_slot(&self, host: &Database) -> Option<Entity> {
             self.m_of_virtual_slot()
         }
@@ -3093,6 +3267,18 @@ smodel! {
             self.m_indirect_substitute_types()
         }
 
+        /// Type parameters of a generic method, or `None` if the method's
+        /// own type parameters have been substituted.
+        pub override fn type_params(&self) -> Option<SharedArray<Entity>> {
+            let type_params = self.origin().type_params()?;
+            let indirect_type_params = self.m_indirect_type_params();
+            if type_params.iter().all(|t| indirect_type_params.includes(&t)) {
+                None
+            } else {
+                Some(type_params)
+            }
+        }
+
         pub override fn name(&self) -> QName {
             self.origin().name()
         }
@@ -3133,6 +3319,10 @@ smodel! {
             self.origin().is_constructor()
         }
 
+        pub override fn param_names(&self) -> SharedArray<String> {
+            self.origin().param_names()
+        }
+
         pub override fn location(&self) -> Option<Location> {
             None
         }
@@ -3321,6 +3511,11 @@ smodel! {
             }
         }
 
+        /// Marks every property of the activation as not captured.
+        pub override fn clear_property_has_capture(&self) {
+            self.set_m_property_has_capture(None);
+        }
+
         pub override fn control_flow_graph(&self) -> ControlFlowGraph {
             self.m_cfg()
         }
@@ -4412,7 +4607,7 @@ impl<'a> Iterator for DescendingClassHierarchy<'a> {
                 self.0 = None;
             } else {
                 self.0 = r.extends_class(self.1);
-                if self.0.as_ref().unwrap() == &self.2 {
+                if self.0.as_ref() == Some(&self.2) {
                     self.0 = None;
                 }
             }
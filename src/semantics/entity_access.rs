@@ -0,0 +1,1179 @@
+use crate::ns::*;
+
+/// Fallible counterparts of `Entity` methods that panic when
+/// the entity kind does not support them.
+///
+/// Each counterpart is named after the method with a `try_` prefix and
+/// returns an `EntityAccessError` naming the entity kind and the method
+/// instead of panicking. The entity kinds listed for each method are the
+/// topmost kinds overriding it, whose subkinds inherit the override.
+impl Entity {
+    /// Returns the name of the entity kind, such as `"ClassType"`.
+    pub fn kind_name(&self) -> &'static str {
+        if self.is::<StaticReferenceValue>() {
+            "StaticReferenceValue"
+        } else if self.is::<InstanceReferenceValue>() {
+            "InstanceReferenceValue"
+        } else if self.is::<ScopeReferenceValue>() {
+            "ScopeReferenceValue"
+        } else if self.is::<PackageReferenceValue>() {
+            "PackageReferenceValue"
+        } else if self.is::<ClassScope>() {
+            "ClassScope"
+        } else if self.is::<EnumScope>() {
+            "EnumScope"
+        } else if self.is::<InterfaceScope>() {
+            "InterfaceScope"
+        } else if self.is::<PackageScope>() {
+            "PackageScope"
+        } else if self.is::<UndefinedConstant>() {
+            "UndefinedConstant"
+        } else if self.is::<NullConstant>() {
+            "NullConstant"
+        } else if self.is::<NamespaceConstant>() {
+            "NamespaceConstant"
+        } else if self.is::<TypeConstant>() {
+            "TypeConstant"
+        } else if self.is::<NumberConstant>() {
+            "NumberConstant"
+        } else if self.is::<StringConstant>() {
+            "StringConstant"
+        } else if self.is::<BooleanConstant>() {
+            "BooleanConstant"
+        } else if self.is::<XmlReferenceValue>() {
+            "XmlReferenceValue"
+        } else if self.is::<DynamicReferenceValue>() {
+            "DynamicReferenceValue"
+        } else if self.is::<FixtureReferenceValue>() {
+            "FixtureReferenceValue"
+        } else if self.is::<StaticDynamicReferenceValue>() {
+            "StaticDynamicReferenceValue"
+        } else if self.is::<TupleReferenceValue>() {
+            "TupleReferenceValue"
+        } else if self.is::<DynamicScopeReferenceValue>() {
+            "DynamicScopeReferenceValue"
+        } else if self.is::<ArrayElementReferenceValue>() {
+            "ArrayElementReferenceValue"
+        } else if self.is::<VectorElementReferenceValue>() {
+            "VectorElementReferenceValue"
+        } else if self.is::<ByteArrayElementReferenceValue>() {
+            "ByteArrayElementReferenceValue"
+        } else if self.is::<SystemNamespace>() {
+            "SystemNamespace"
+        } else if self.is::<UserNamespace>() {
+            "UserNamespace"
+        } else if self.is::<ExplicitNamespace>() {
+            "ExplicitNamespace"
+        } else if self.is::<AnyType>() {
+            "AnyType"
+        } else if self.is::<VoidType>() {
+            "VoidType"
+        } else if self.is::<ClassType>() {
+            "ClassType"
+        } else if self.is::<EnumType>() {
+            "EnumType"
+        } else if self.is::<InterfaceType>() {
+            "InterfaceType"
+        } else if self.is::<TypeAfterSubstitution>() {
+            "TypeAfterSubstitution"
+        } else if self.is::<TupleType>() {
+            "TupleType"
+        } else if self.is::<FunctionType>() {
+            "FunctionType"
+        } else if self.is::<NullableType>() {
+            "NullableType"
+        } else if self.is::<NonNullableType>() {
+            "NonNullableType"
+        } else if self.is::<TypeParameterType>() {
+            "TypeParameterType"
+        } else if self.is::<OriginalVariableSlot>() {
+            "OriginalVariableSlot"
+        } else if self.is::<VariableSlotAfterSubstitution>() {
+            "VariableSlotAfterSubstitution"
+        } else if self.is::<OriginalVirtualSlot>() {
+            "OriginalVirtualSlot"
+        } else if self.is::<VirtualSlotAfterSubstitution>() {
+            "VirtualSlotAfterSubstitution"
+        } else if self.is::<OriginalMethodSlot>() {
+            "OriginalMethodSlot"
+        } else if self.is::<MethodSlotAfterSubstitution>() {
+            "MethodSlotAfterSubstitution"
+        } else if self.is::<WithScope>() {
+            "WithScope"
+        } else if self.is::<FilterScope>() {
+            "FilterScope"
+        } else if self.is::<Activation>() {
+            "Activation"
+        } else if self.is::<FixtureScope>() {
+            "FixtureScope"
+        } else if self.is::<PackagePropertyImport>() {
+            "PackagePropertyImport"
+        } else if self.is::<PackageWildcardImport>() {
+            "PackageWildcardImport"
+        } else if self.is::<PackageRecursiveImport>() {
+            "PackageRecursiveImport"
+        } else if self.is::<Constant>() {
+            "Constant"
+        } else if self.is::<ThisObject>() {
+            "ThisObject"
+        } else if self.is::<MetaProperty>() {
+            "MetaProperty"
+        } else if self.is::<MetaEnvProperty>() {
+            "MetaEnvProperty"
+        } else if self.is::<ReferenceValue>() {
+            "ReferenceValue"
+        } else if self.is::<ConversionValue>() {
+            "ConversionValue"
+        } else if self.is::<NonNullValue>() {
+            "NonNullValue"
+        } else if self.is::<LambdaObject>() {
+            "LambdaObject"
+        } else if self.is::<FilterValue>() {
+            "FilterValue"
+        } else if self.is::<UnresolvedEntity>() {
+            "UnresolvedEntity"
+        } else if self.is::<InvalidationEntity>() {
+            "InvalidationEntity"
+        } else if self.is::<Namespace>() {
+            "Namespace"
+        } else if self.is::<Package>() {
+            "Package"
+        } else if self.is::<Alias>() {
+            "Alias"
+        } else if self.is::<Type>() {
+            "Type"
+        } else if self.is::<VariableSlot>() {
+            "VariableSlot"
+        } else if self.is::<VirtualSlot>() {
+            "VirtualSlot"
+        } else if self.is::<MethodSlot>() {
+            "MethodSlot"
+        } else if self.is::<Scope>() {
+            "Scope"
+        } else if self.is::<Value>() {
+            "Value"
+        } else if self.is::<FieldResolution>() {
+            "FieldResolution"
+        } else if self.is::<DeclarativeFieldDestructuringResolution>() {
+            "DeclarativeFieldDestructuringResolution"
+        } else if self.is::<AssignmentFieldDestructuringResolution>() {
+            "AssignmentFieldDestructuringResolution"
+        } else {
+            "Entity"
+        }
+    }
+
+    /// Fallible counterpart of `Entity::location()`.
+    pub fn try_location(&self) -> Result<Option<Location>, EntityAccessError> {
+        if self.is::<Alias>() || self.is::<ClassType>() || self.is::<EnumType>() || self.is::<InterfaceType>() || self.is::<TypeAfterSubstitution>() || self.is::<TypeParameterType>() || self.is::<OriginalVariableSlot>() || self.is::<VariableSlotAfterSubstitution>() || self.is::<OriginalVirtualSlot>() || self.is::<VirtualSlotAfterSubstitution>() || self.is::<OriginalMethodSlot>() || self.is::<MethodSlotAfterSubstitution>() || self.is::<PackagePropertyImport>() || self.is::<PackageWildcardImport>() || self.is::<PackageRecursiveImport>() {
+            Ok(self.location())
+        } else {
+            Err(self.access_error("location"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_location()`.
+    pub fn try_set_location(&self, loc: Option<Location>) -> Result<(), EntityAccessError> {
+        if self.is::<Alias>() || self.is::<ClassType>() || self.is::<EnumType>() || self.is::<InterfaceType>() || self.is::<TypeParameterType>() || self.is::<OriginalVariableSlot>() || self.is::<OriginalVirtualSlot>() || self.is::<OriginalMethodSlot>() || self.is::<PackagePropertyImport>() || self.is::<PackageWildcardImport>() || self.is::<PackageRecursiveImport>() {
+            self.set_location(loc);
+            Ok(())
+        } else {
+            Err(self.access_error("set_location"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::qualifier()`.
+    pub fn try_qualifier(&self) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<XmlReferenceValue>() || self.is::<DynamicReferenceValue>() || self.is::<StaticDynamicReferenceValue>() || self.is::<DynamicScopeReferenceValue>() {
+            Ok(self.qualifier())
+        } else {
+            Err(self.access_error("qualifier"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::key()`.
+    pub fn try_key(&self) -> Result<Entity, EntityAccessError> {
+        if self.is::<XmlReferenceValue>() || self.is::<DynamicReferenceValue>() || self.is::<StaticDynamicReferenceValue>() || self.is::<DynamicScopeReferenceValue>() || self.is::<ArrayElementReferenceValue>() || self.is::<VectorElementReferenceValue>() || self.is::<ByteArrayElementReferenceValue>() {
+            Ok(self.key())
+        } else {
+            Err(self.access_error("key"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::property_static_type()`.
+    pub fn try_property_static_type(&self, host: &Database) -> Result<Entity, EntityAccessError> {
+        if self.is::<InvalidationEntity>() || self.is::<Namespace>() || self.is::<Type>() || self.is::<VariableSlot>() || self.is::<VirtualSlot>() || self.is::<MethodSlot>() || self.is::<Value>() {
+            Ok(self.property_static_type(host))
+        } else {
+            Err(self.access_error("property_static_type"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::metadata()`.
+    pub fn try_metadata(&self) -> Result<SharedArray<Rc<Metadata>>, EntityAccessError> {
+        if self.is::<ClassType>() || self.is::<EnumType>() || self.is::<InterfaceType>() || self.is::<TypeAfterSubstitution>() || self.is::<OriginalVariableSlot>() || self.is::<VariableSlotAfterSubstitution>() || self.is::<OriginalMethodSlot>() || self.is::<MethodSlotAfterSubstitution>() {
+            Ok(self.metadata())
+        } else {
+            Err(self.access_error("metadata"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_is_external()`.
+    pub fn try_set_is_external(&self, value: bool) -> Result<(), EntityAccessError> {
+        if self.is::<ClassType>() || self.is::<InterfaceType>() || self.is::<OriginalVariableSlot>() || self.is::<OriginalVirtualSlot>() || self.is::<OriginalMethodSlot>() {
+            self.set_is_external(value);
+            Ok(())
+        } else {
+            Err(self.access_error("set_is_external"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::object()`.
+    pub fn try_object(&self) -> Result<Entity, EntityAccessError> {
+        if self.is::<WithScope>() {
+            Ok(self.object())
+        } else {
+            Err(self.access_error("object"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::open_ns_set()`.
+    pub fn try_open_ns_set(&self) -> Result<SharedArray<Entity>, EntityAccessError> {
+        if self.is::<Scope>() {
+            Ok(self.open_ns_set())
+        } else {
+            Err(self.access_error("open_ns_set"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::import_list()`.
+    pub fn try_import_list(&self) -> Result<SharedArray<Entity>, EntityAccessError> {
+        if self.is::<Scope>() {
+            Ok(self.import_list())
+        } else {
+            Err(self.access_error("import_list"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::class()`.
+    pub fn try_class(&self) -> Result<Entity, EntityAccessError> {
+        if self.is::<ClassScope>() || self.is::<EnumScope>() {
+            Ok(self.class())
+        } else {
+            Err(self.access_error("class"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::interface()`.
+    pub fn try_interface(&self) -> Result<Entity, EntityAccessError> {
+        if self.is::<InterfaceScope>() {
+            Ok(self.interface())
+        } else {
+            Err(self.access_error("interface"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::package()`.
+    pub fn try_package(&self) -> Result<Entity, EntityAccessError> {
+        if self.is::<PackageScope>() || self.is::<PackageWildcardImport>() || self.is::<PackageRecursiveImport>() {
+            Ok(self.package())
+        } else {
+            Err(self.access_error("package"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::of_method()`.
+    pub fn try_of_method(&self) -> Result<Entity, EntityAccessError> {
+        if self.is::<Activation>() {
+            Ok(self.of_method())
+        } else {
+            Err(self.access_error("of_method"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::this()`.
+    pub fn try_this(&self) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<Activation>() {
+            Ok(self.this())
+        } else {
+            Err(self.access_error("this"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_this()`.
+    pub fn try_set_this(&self, this: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<Activation>() {
+            self.set_this(this);
+            Ok(())
+        } else {
+            Err(self.access_error("set_this"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::property_has_capture()`.
+    pub fn try_property_has_capture(&self, property: &Entity) -> Result<bool, EntityAccessError> {
+        if self.is::<Activation>() {
+            Ok(self.property_has_capture(property))
+        } else {
+            Err(self.access_error("property_has_capture"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_property_has_capture()`.
+    pub fn try_set_property_has_capture(&self, property: &Entity, value: bool) -> Result<(), EntityAccessError> {
+        if self.is::<Activation>() {
+            self.set_property_has_capture(property, value);
+            Ok(())
+        } else {
+            Err(self.access_error("set_property_has_capture"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::referenced_type()`.
+    pub fn try_referenced_type(&self) -> Result<Entity, EntityAccessError> {
+        if self.is::<TypeConstant>() {
+            Ok(self.referenced_type())
+        } else {
+            Err(self.access_error("referenced_type"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::referenced_ns()`.
+    pub fn try_referenced_ns(&self) -> Result<Entity, EntityAccessError> {
+        if self.is::<NamespaceConstant>() {
+            Ok(self.referenced_ns())
+        } else {
+            Err(self.access_error("referenced_ns"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::shorthand_resolution()`.
+    pub fn try_shorthand_resolution(&self) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<FieldResolution>() {
+            Ok(self.shorthand_resolution())
+        } else {
+            Err(self.access_error("shorthand_resolution"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_shorthand_resolution()`.
+    pub fn try_set_shorthand_resolution(&self, value: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<FieldResolution>() {
+            self.set_shorthand_resolution(value);
+            Ok(())
+        } else {
+            Err(self.access_error("set_shorthand_resolution"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::field_slot()`.
+    pub fn try_field_slot(&self) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<FieldResolution>() {
+            Ok(self.field_slot())
+        } else {
+            Err(self.access_error("field_slot"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_field_slot()`.
+    pub fn try_set_field_slot(&self, value: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<FieldResolution>() {
+            self.set_field_slot(value);
+            Ok(())
+        } else {
+            Err(self.access_error("set_field_slot"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::parent()`.
+    pub fn try_parent(&self) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<SystemNamespace>() || self.is::<Package>() || self.is::<Alias>() || self.is::<ClassType>() || self.is::<EnumType>() || self.is::<InterfaceType>() || self.is::<TypeAfterSubstitution>() || self.is::<OriginalVariableSlot>() || self.is::<VariableSlotAfterSubstitution>() || self.is::<OriginalVirtualSlot>() || self.is::<VirtualSlotAfterSubstitution>() || self.is::<OriginalMethodSlot>() || self.is::<MethodSlotAfterSubstitution>() || self.is::<Scope>() {
+            Ok(self.parent())
+        } else {
+            Err(self.access_error("parent"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_parent()`.
+    pub fn try_set_parent(&self, p: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<Package>() || self.is::<Alias>() || self.is::<ClassType>() || self.is::<EnumType>() || self.is::<InterfaceType>() || self.is::<OriginalVariableSlot>() || self.is::<OriginalVirtualSlot>() || self.is::<OriginalMethodSlot>() || self.is::<Scope>() {
+            self.set_parent(p);
+            Ok(())
+        } else {
+            Err(self.access_error("set_parent"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::package_concats()`.
+    pub fn try_package_concats(&self) -> Result<SharedArray<Entity>, EntityAccessError> {
+        if self.is::<Package>() {
+            Ok(self.package_concats())
+        } else {
+            Err(self.access_error("package_concats"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::public_ns()`.
+    pub fn try_public_ns(&self) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<Package>() || self.is::<Activation>() {
+            Ok(self.public_ns())
+        } else {
+            Err(self.access_error("public_ns"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_public_ns()`.
+    pub fn try_set_public_ns(&self, ns: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<Package>() || self.is::<Activation>() {
+            self.set_public_ns(ns);
+            Ok(())
+        } else {
+            Err(self.access_error("set_public_ns"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::private_ns()`.
+    pub fn try_private_ns(&self) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<ClassType>() || self.is::<EnumType>() {
+            Ok(self.private_ns())
+        } else {
+            Err(self.access_error("private_ns"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_private_ns()`.
+    pub fn try_set_private_ns(&self, ns: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<ClassType>() || self.is::<EnumType>() {
+            self.set_private_ns(ns);
+            Ok(())
+        } else {
+            Err(self.access_error("set_private_ns"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::protected_ns()`.
+    pub fn try_protected_ns(&self) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<ClassType>() {
+            Ok(self.protected_ns())
+        } else {
+            Err(self.access_error("protected_ns"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_protected_ns()`.
+    pub fn try_set_protected_ns(&self, ns: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<ClassType>() {
+            self.set_protected_ns(ns);
+            Ok(())
+        } else {
+            Err(self.access_error("set_protected_ns"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::static_protected_ns()`.
+    pub fn try_static_protected_ns(&self) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<ClassType>() {
+            Ok(self.static_protected_ns())
+        } else {
+            Err(self.access_error("static_protected_ns"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_static_protected_ns()`.
+    pub fn try_set_static_protected_ns(&self, ns: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<ClassType>() {
+            self.set_static_protected_ns(ns);
+            Ok(())
+        } else {
+            Err(self.access_error("set_static_protected_ns"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::internal_ns()`.
+    pub fn try_internal_ns(&self) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<Package>() || self.is::<Activation>() {
+            Ok(self.internal_ns())
+        } else {
+            Err(self.access_error("internal_ns"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_internal_ns()`.
+    pub fn try_set_internal_ns(&self, ns: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<Package>() || self.is::<Activation>() {
+            self.set_internal_ns(ns);
+            Ok(())
+        } else {
+            Err(self.access_error("set_internal_ns"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::is_global_initialization()`.
+    pub fn try_is_global_initialization(&self) -> Result<bool, EntityAccessError> {
+        if self.is::<Activation>() {
+            Ok(self.is_global_initialization())
+        } else {
+            Err(self.access_error("is_global_initialization"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_is_global_initialization()`.
+    pub fn try_set_is_global_initialization(&self, value: bool) -> Result<(), EntityAccessError> {
+        if self.is::<Activation>() {
+            self.set_is_global_initialization(value);
+            Ok(())
+        } else {
+            Err(self.access_error("set_is_global_initialization"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::is_package_initialization()`.
+    pub fn try_is_package_initialization(&self) -> Result<bool, EntityAccessError> {
+        if self.is::<Activation>() {
+            Ok(self.is_package_initialization())
+        } else {
+            Err(self.access_error("is_package_initialization"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_is_package_initialization()`.
+    pub fn try_set_is_package_initialization(&self, value: bool) -> Result<(), EntityAccessError> {
+        if self.is::<Activation>() {
+            self.set_is_package_initialization(value);
+            Ok(())
+        } else {
+            Err(self.access_error("set_is_package_initialization"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::number_value()`.
+    pub fn try_number_value(&self) -> Result<Number, EntityAccessError> {
+        if self.is::<NumberConstant>() {
+            Ok(self.number_value())
+        } else {
+            Err(self.access_error("number_value"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::string_value()`.
+    pub fn try_string_value(&self) -> Result<String, EntityAccessError> {
+        if self.is::<StringConstant>() {
+            Ok(self.string_value())
+        } else {
+            Err(self.access_error("string_value"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::boolean_value()`.
+    pub fn try_boolean_value(&self) -> Result<bool, EntityAccessError> {
+        if self.is::<BooleanConstant>() {
+            Ok(self.boolean_value())
+        } else {
+            Err(self.access_error("boolean_value"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::type_default_value()`.
+    pub fn try_type_default_value(&self, host: &Database) -> Result<Result<Option<Entity>, DeferError>, EntityAccessError> {
+        if self.is::<Type>() {
+            Ok(self.type_default_value(host))
+        } else {
+            Err(self.access_error("type_default_value"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::conversion_kind()`.
+    pub fn try_conversion_kind(&self) -> Result<ConversionKind, EntityAccessError> {
+        if self.is::<ConversionValue>() {
+            Ok(self.conversion_kind())
+        } else {
+            Err(self.access_error("conversion_kind"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::conversion_is_opt()`.
+    pub fn try_conversion_is_opt(&self) -> Result<bool, EntityAccessError> {
+        if self.is::<ConversionValue>() {
+            Ok(self.conversion_is_opt())
+        } else {
+            Err(self.access_error("conversion_is_opt"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::conversion_target()`.
+    pub fn try_conversion_target(&self) -> Result<Entity, EntityAccessError> {
+        if self.is::<ConversionValue>() {
+            Ok(self.conversion_target())
+        } else {
+            Err(self.access_error("conversion_target"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::events()`.
+    pub fn try_events(&self) -> Result<SharedMap<String, Event>, EntityAccessError> {
+        if self.is::<ClassType>() || self.is::<InterfaceType>() || self.is::<TypeAfterSubstitution>() {
+            Ok(self.events())
+        } else {
+            Err(self.access_error("events"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::bindable_event()`.
+    pub fn try_bindable_event(&self) -> Result<Option<String>, EntityAccessError> {
+        if self.is::<OriginalVariableSlot>() || self.is::<VariableSlotAfterSubstitution>() || self.is::<OriginalVirtualSlot>() || self.is::<VirtualSlotAfterSubstitution>() {
+            Ok(self.bindable_event())
+        } else {
+            Err(self.access_error("bindable_event"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_bindable_event()`.
+    pub fn try_set_bindable_event(&self, name: Option<String>) -> Result<(), EntityAccessError> {
+        if self.is::<OriginalVariableSlot>() || self.is::<OriginalVirtualSlot>() {
+            self.set_bindable_event(name);
+            Ok(())
+        } else {
+            Err(self.access_error("set_bindable_event"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::scope()`.
+    pub fn try_scope(&self) -> Result<Entity, EntityAccessError> {
+        if self.is::<FilterValue>() {
+            Ok(self.scope())
+        } else {
+            Err(self.access_error("scope"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::getter()`.
+    pub fn try_getter(&self, host: &Database) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<OriginalVirtualSlot>() || self.is::<VirtualSlotAfterSubstitution>() {
+            Ok(self.getter(host))
+        } else {
+            Err(self.access_error("getter"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_getter()`.
+    pub fn try_set_getter(&self, m: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<OriginalVirtualSlot>() {
+            self.set_getter(m);
+            Ok(())
+        } else {
+            Err(self.access_error("set_getter"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::setter()`.
+    pub fn try_setter(&self, host: &Database) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<OriginalVirtualSlot>() || self.is::<VirtualSlotAfterSubstitution>() {
+            Ok(self.setter(host))
+        } else {
+            Err(self.access_error("setter"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_setter()`.
+    pub fn try_set_setter(&self, m: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<OriginalVirtualSlot>() {
+            self.set_setter(m);
+            Ok(())
+        } else {
+            Err(self.access_error("set_setter"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::static_type()`.
+    pub fn try_static_type(&self, host: &Database) -> Result<Entity, EntityAccessError> {
+        if self.is::<InvalidationEntity>() || self.is::<OriginalVariableSlot>() || self.is::<VariableSlotAfterSubstitution>() || self.is::<OriginalVirtualSlot>() || self.is::<VirtualSlotAfterSubstitution>() || self.is::<Value>() {
+            Ok(self.static_type(host))
+        } else {
+            Err(self.access_error("static_type"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_static_type()`.
+    pub fn try_set_static_type(&self, value: Entity) -> Result<(), EntityAccessError> {
+        if self.is::<OriginalVariableSlot>() || self.is::<Value>() {
+            self.set_static_type(value);
+            Ok(())
+        } else {
+            Err(self.access_error("set_static_type"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::clone_constant()`.
+    pub fn try_clone_constant(&self, host: &Database) -> Result<Entity, EntityAccessError> {
+        if self.is::<UndefinedConstant>() || self.is::<NullConstant>() || self.is::<NamespaceConstant>() || self.is::<TypeConstant>() || self.is::<NumberConstant>() || self.is::<StringConstant>() || self.is::<BooleanConstant>() {
+            Ok(self.clone_constant(host))
+        } else {
+            Err(self.access_error("clone_constant"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::signature()`.
+    pub fn try_signature(&self, host: &Database) -> Result<Entity, EntityAccessError> {
+        if self.is::<OriginalMethodSlot>() || self.is::<MethodSlotAfterSubstitution>() {
+            Ok(self.signature(host))
+        } else {
+            Err(self.access_error("signature"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_signature()`.
+    pub fn try_set_signature(&self, signature: &Entity) -> Result<(), EntityAccessError> {
+        if self.is::<OriginalMethodSlot>() {
+            self.set_signature(signature);
+            Ok(())
+        } else {
+            Err(self.access_error("set_signature"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::activation()`.
+    pub fn try_activation(&self) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<OriginalMethodSlot>() || self.is::<LambdaObject>() {
+            Ok(self.activation())
+        } else {
+            Err(self.access_error("activation"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_param_names()`.
+    pub fn try_set_param_names(&self, names: SharedArray<String>) -> Result<(), EntityAccessError> {
+        if self.is::<OriginalMethodSlot>() {
+            self.set_param_names(names);
+            Ok(())
+        } else {
+            Err(self.access_error("set_param_names"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_activation()`.
+    pub fn try_set_activation(&self, activation: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<OriginalMethodSlot>() {
+            self.set_activation(activation);
+            Ok(())
+        } else {
+            Err(self.access_error("set_activation"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::of_virtual_slot()`.
+    pub fn try_of_virtual_slot(&self, host: &Database) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<OriginalMethodSlot>() || self.is::<MethodSlotAfterSubstitution>() {
+            Ok(self.of_virtual_slot(host))
+        } else {
+            Err(self.access_error("of_virtual_slot"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_of_virtual_slot()`.
+    pub fn try_set_of_virtual_slot(&self, virtual_slot: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<OriginalMethodSlot>() {
+            self.set_of_virtual_slot(virtual_slot);
+            Ok(())
+        } else {
+            Err(self.access_error("set_of_virtual_slot"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::overriden_by()`.
+    pub fn try_overriden_by(&self, host: &Database) -> Result<SharedArray<Entity>, EntityAccessError> {
+        if self.is::<OriginalMethodSlot>() || self.is::<MethodSlotAfterSubstitution>() {
+            Ok(self.overriden_by(host))
+        } else {
+            Err(self.access_error("overriden_by"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::overrides_method()`.
+    pub fn try_overrides_method(&self, host: &Database) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<OriginalMethodSlot>() || self.is::<MethodSlotAfterSubstitution>() {
+            Ok(self.overrides_method(host))
+        } else {
+            Err(self.access_error("overrides_method"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_overrides_method()`.
+    pub fn try_set_overrides_method(&self, method: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<OriginalMethodSlot>() || self.is::<MethodSlotAfterSubstitution>() {
+            self.set_overrides_method(method);
+            Ok(())
+        } else {
+            Err(self.access_error("set_overrides_method"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::constructor_method()`.
+    pub fn try_constructor_method(&self, host: &Database) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<ClassType>() || self.is::<TypeAfterSubstitution>() {
+            Ok(self.constructor_method(host))
+        } else {
+            Err(self.access_error("constructor_method"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::known_subclasses()`.
+    pub fn try_known_subclasses(&self) -> Result<SharedArray<Entity>, EntityAccessError> {
+        if self.is::<ClassType>() {
+            Ok(self.known_subclasses())
+        } else {
+            Err(self.access_error("known_subclasses"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::implements()`.
+    pub fn try_implements(&self, host: &Database) -> Result<SharedArray<Entity>, EntityAccessError> {
+        if self.is::<ClassType>() || self.is::<TypeAfterSubstitution>() {
+            Ok(self.implements(host))
+        } else {
+            Err(self.access_error("implements"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_extends_class()`.
+    pub fn try_set_extends_class(&self, entity: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<ClassType>() {
+            self.set_extends_class(entity);
+            Ok(())
+        } else {
+            Err(self.access_error("set_extends_class"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::subpackages()`.
+    pub fn try_subpackages(&self) -> Result<SharedMap<String, Entity>, EntityAccessError> {
+        if self.is::<Package>() {
+            Ok(self.subpackages())
+        } else {
+            Err(self.access_error("subpackages"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::alias_of()`.
+    pub fn try_alias_of(&self) -> Result<Entity, EntityAccessError> {
+        if self.is::<Alias>() {
+            Ok(self.alias_of())
+        } else {
+            Err(self.access_error("alias_of"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_alias_of()`.
+    pub fn try_set_alias_of(&self, value: &Entity) -> Result<(), EntityAccessError> {
+        if self.is::<Alias>() {
+            self.set_alias_of(value);
+            Ok(())
+        } else {
+            Err(self.access_error("set_alias_of"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::property()`.
+    pub fn try_property(&self) -> Result<Entity, EntityAccessError> {
+        if self.is::<PackagePropertyImport>() || self.is::<FixtureReferenceValue>() {
+            Ok(self.property())
+        } else {
+            Err(self.access_error("property"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_property()`.
+    pub fn try_set_property(&self, value: &Entity) -> Result<(), EntityAccessError> {
+        if self.is::<PackagePropertyImport>() {
+            self.set_property(value);
+            Ok(())
+        } else {
+            Err(self.access_error("set_property"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::includes_undefined()`.
+    pub fn try_includes_undefined(&self, host: &Database) -> Result<Result<bool, DeferError>, EntityAccessError> {
+        if self.is::<InvalidationEntity>() || self.is::<Type>() {
+            Ok(self.includes_undefined(host))
+        } else {
+            Err(self.access_error("includes_undefined"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::includes_null()`.
+    pub fn try_includes_null(&self, host: &Database) -> Result<Result<bool, DeferError>, EntityAccessError> {
+        if self.is::<InvalidationEntity>() || self.is::<Type>() {
+            Ok(self.includes_null(host))
+        } else {
+            Err(self.access_error("includes_null"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::name()`.
+    pub fn try_name(&self) -> Result<QName, EntityAccessError> {
+        if self.is::<Alias>() || self.is::<ClassType>() || self.is::<EnumType>() || self.is::<InterfaceType>() || self.is::<TypeAfterSubstitution>() || self.is::<TypeParameterType>() || self.is::<OriginalVariableSlot>() || self.is::<VariableSlotAfterSubstitution>() || self.is::<OriginalVirtualSlot>() || self.is::<VirtualSlotAfterSubstitution>() || self.is::<OriginalMethodSlot>() || self.is::<MethodSlotAfterSubstitution>() {
+            Ok(self.name())
+        } else {
+            Err(self.access_error("name"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_constraint()`.
+    pub fn try_set_constraint(&self, constraint: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<TypeParameterType>() {
+            self.set_constraint(constraint);
+            Ok(())
+        } else {
+            Err(self.access_error("set_constraint"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::enum_member_number_mapping()`.
+    pub fn try_enum_member_number_mapping(&self) -> Result<SharedMap<String, Number>, EntityAccessError> {
+        if self.is::<EnumType>() {
+            Ok(self.enum_member_number_mapping())
+        } else {
+            Err(self.access_error("enum_member_number_mapping"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::enum_member_slot_mapping()`.
+    pub fn try_enum_member_slot_mapping(&self) -> Result<SharedMap<String, Entity>, EntityAccessError> {
+        if self.is::<EnumType>() {
+            Ok(self.enum_member_slot_mapping())
+        } else {
+            Err(self.access_error("enum_member_slot_mapping"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::known_implementors()`.
+    pub fn try_known_implementors(&self) -> Result<SharedArray<Entity>, EntityAccessError> {
+        if self.is::<InterfaceType>() {
+            Ok(self.known_implementors())
+        } else {
+            Err(self.access_error("known_implementors"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::extends_interfaces()`.
+    pub fn try_extends_interfaces(&self, host: &Database) -> Result<SharedArray<Entity>, EntityAccessError> {
+        if self.is::<InterfaceType>() || self.is::<TypeAfterSubstitution>() {
+            Ok(self.extends_interfaces(host))
+        } else {
+            Err(self.access_error("extends_interfaces"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::origin()`.
+    pub fn try_origin(&self) -> Result<Entity, EntityAccessError> {
+        if self.is::<TypeAfterSubstitution>() || self.is::<VariableSlotAfterSubstitution>() || self.is::<VirtualSlotAfterSubstitution>() || self.is::<MethodSlotAfterSubstitution>() {
+            Ok(self.origin())
+        } else {
+            Err(self.access_error("origin"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::substitute_types()`.
+    pub fn try_substitute_types(&self) -> Result<SharedArray<Entity>, EntityAccessError> {
+        if self.is::<TypeAfterSubstitution>() {
+            Ok(self.substitute_types())
+        } else {
+            Err(self.access_error("substitute_types"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::indirect_type_params()`.
+    pub fn try_indirect_type_params(&self) -> Result<SharedArray<Entity>, EntityAccessError> {
+        if self.is::<VariableSlotAfterSubstitution>() || self.is::<VirtualSlotAfterSubstitution>() || self.is::<MethodSlotAfterSubstitution>() {
+            Ok(self.indirect_type_params())
+        } else {
+            Err(self.access_error("indirect_type_params"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::indirect_substitute_types()`.
+    pub fn try_indirect_substitute_types(&self) -> Result<SharedArray<Entity>, EntityAccessError> {
+        if self.is::<VariableSlotAfterSubstitution>() || self.is::<VirtualSlotAfterSubstitution>() || self.is::<MethodSlotAfterSubstitution>() {
+            Ok(self.indirect_substitute_types())
+        } else {
+            Err(self.access_error("indirect_substitute_types"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::element_types()`.
+    pub fn try_element_types(&self) -> Result<SharedArray<Entity>, EntityAccessError> {
+        if self.is::<TupleType>() {
+            Ok(self.element_types())
+        } else {
+            Err(self.access_error("element_types"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::params()`.
+    pub fn try_params(&self) -> Result<SharedArray<Rc<SemanticFunctionTypeParameter>>, EntityAccessError> {
+        if self.is::<FunctionType>() {
+            Ok(self.params())
+        } else {
+            Err(self.access_error("params"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::result_type()`.
+    pub fn try_result_type(&self) -> Result<Entity, EntityAccessError> {
+        if self.is::<FunctionType>() {
+            Ok(self.result_type())
+        } else {
+            Err(self.access_error("result_type"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::base()`.
+    pub fn try_base(&self) -> Result<Entity, EntityAccessError> {
+        if self.is::<NullableType>() || self.is::<NonNullableType>() || self.is::<FilterScope>() || self.is::<XmlReferenceValue>() || self.is::<DynamicReferenceValue>() || self.is::<FixtureReferenceValue>() || self.is::<StaticDynamicReferenceValue>() || self.is::<TupleReferenceValue>() || self.is::<DynamicScopeReferenceValue>() || self.is::<ArrayElementReferenceValue>() || self.is::<VectorElementReferenceValue>() || self.is::<ByteArrayElementReferenceValue>() || self.is::<ConversionValue>() || self.is::<NonNullValue>() {
+            Ok(self.base())
+        } else {
+            Err(self.access_error("base"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_read_only()`.
+    pub fn try_set_read_only(&self, value: bool) -> Result<(), EntityAccessError> {
+        if self.is::<OriginalVariableSlot>() {
+            self.set_read_only(value);
+            Ok(())
+        } else {
+            Err(self.access_error("set_read_only"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::var_constant()`.
+    pub fn try_var_constant(&self) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<OriginalVariableSlot>() || self.is::<VariableSlotAfterSubstitution>() {
+            Ok(self.var_constant())
+        } else {
+            Err(self.access_error("var_constant"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_var_constant()`.
+    pub fn try_set_var_constant(&self, k: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<OriginalVariableSlot>() {
+            self.set_var_constant(k);
+            Ok(())
+        } else {
+            Err(self.access_error("set_var_constant"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::control_flow_graph()`.
+    pub fn try_control_flow_graph(&self) -> Result<ControlFlowGraph, EntityAccessError> {
+        if self.is::<Activation>() {
+            Ok(self.control_flow_graph())
+        } else {
+            Err(self.access_error("control_flow_graph"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::field_reference()`.
+    pub fn try_field_reference(&self) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<DeclarativeFieldDestructuringResolution>() || self.is::<AssignmentFieldDestructuringResolution>() {
+            Ok(self.field_reference())
+        } else {
+            Err(self.access_error("field_reference"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_field_reference()`.
+    pub fn try_set_field_reference(&self, value: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<DeclarativeFieldDestructuringResolution>() || self.is::<AssignmentFieldDestructuringResolution>() {
+            self.set_field_reference(value);
+            Ok(())
+        } else {
+            Err(self.access_error("set_field_reference"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::var_slot()`.
+    pub fn try_var_slot(&self) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<DeclarativeFieldDestructuringResolution>() {
+            Ok(self.var_slot())
+        } else {
+            Err(self.access_error("var_slot"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_var_slot()`.
+    pub fn try_set_var_slot(&self, value: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<DeclarativeFieldDestructuringResolution>() {
+            self.set_var_slot(value);
+            Ok(())
+        } else {
+            Err(self.access_error("set_var_slot"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::target_reference()`.
+    pub fn try_target_reference(&self) -> Result<Option<Entity>, EntityAccessError> {
+        if self.is::<AssignmentFieldDestructuringResolution>() {
+            Ok(self.target_reference())
+        } else {
+            Err(self.access_error("target_reference"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::set_target_reference()`.
+    pub fn try_set_target_reference(&self, value: Option<Entity>) -> Result<(), EntityAccessError> {
+        if self.is::<AssignmentFieldDestructuringResolution>() {
+            self.set_target_reference(value);
+            Ok(())
+        } else {
+            Err(self.access_error("set_target_reference"))
+        }
+    }
+
+    /// Fallible counterpart of `Entity::apply_type()`.
+    pub fn try_apply_type(&self, host: &Database, type_params: &SharedArray<Entity>, substitute_types: &SharedArray<Entity>) -> Result<Entity, ApplyTypeError> {
+        ApplyType(host).try_exec(self, type_params, substitute_types)
+    }
+
+    fn access_error(&self, method: &'static str) -> EntityAccessError {
+        EntityAccessError {
+            entity_kind: self.kind_name(),
+            method,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+    use crate::semantics::test_fixtures::*;
+
+    #[test]
+    fn names_entity_kinds() {
+        let db = Database::new(DatabaseOptions::default());
+        let activation = define_activation(&db);
+        let slot = define_local(&db, &activation, "x");
+        assert_eq!(activation.kind_name(), "Activation");
+        assert_eq!(slot.kind_name(), "OriginalVariableSlot");
+        assert_eq!(db.top_level_package().kind_name(), "Package");
+        assert_eq!(db.any_type().kind_name(), "AnyType");
+        assert_eq!(db.factory().create_scope().kind_name(), "Scope");
+    }
+
+    #[test]
+    fn reports_unsupported_access() {
+        let db = Database::new(DatabaseOptions::default());
+        let activation = define_activation(&db);
+        let slot = define_local(&db, &activation, "x");
+
+        assert_eq!(db.top_level_package().try_static_type(&db), Err(EntityAccessError { entity_kind: "Package", method: "static_type" }));
+        assert_eq!(slot.try_property_static_type(&db), Ok(db.any_type()));
+        assert_eq!(db.any_type().try_set_location(None), Err(EntityAccessError { entity_kind: "AnyType", method: "set_location" }));
+
+        // Setters
+        assert_eq!(activation.try_set_this(Some(slot.clone())), Ok(()));
+        assert_eq!(activation.this(), Some(slot.clone()));
+        assert_eq!(slot.try_set_this(None), Err(EntityAccessError { entity_kind: "OriginalVariableSlot", method: "set_this" }));
+    }
+}
@@ -100,7 +100,9 @@ impl<'a> Factory<'a> {
     }
 
     pub fn create_alias(&self, name: QName, alias_of: Entity) -> Entity {
-        Alias::new(&self.0.arena, name, alias_of).into()
+        let r: Entity = Alias::new(&self.0.arena, name, alias_of).into();
+        self.0.position_index().add_declaration(&r);
+        r
     }
 
     /// # Parameters
@@ -118,7 +120,9 @@ impl<'a> Factory<'a> {
         let prototype_slot = self.create_variable_slot(&prototype_name, true, &self.0.any_type());
         r.properties(self.0).set(prototype_name.clone(), prototype_slot);
 
-        r.into()
+        let r: Entity = r.into();
+        self.0.position_index().add_declaration(&r);
+        r
     }
 
     pub fn create_enum_type(&self, name: QName, ns_for_prototype: &Entity) -> Entity {
@@ -130,21 +134,56 @@ impl<'a> Factory<'a> {
         let prototype_slot = self.create_variable_slot(&prototype_name, true, &self.0.any_type());
         r.properties(self.0).set(prototype_name.clone(), prototype_slot);
 
-        r.into()
+        let r: Entity = r.into();
+        self.0.position_index().add_declaration(&r);
+        r
     }
 
     pub fn create_interface_type(&self, name: QName) -> Entity {
-        let r = InterfaceType::new(&self.0.arena, name);
-        r.into()
+        let r: Entity = InterfaceType::new(&self.0.arena, name).into();
+        self.0.position_index().add_declaration(&r);
+        r
     }
 
     /// Interns type after substitution.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of substitute types does not match the number of
+    /// type parameters. Constraints are not verified; use
+    /// `try_create_type_after_substitution()` to verify them.
     pub fn create_type_after_substitution(&self, origin: &Entity, substitute_types: &SharedArray<Entity>) -> Entity {
         // Verify parameter count
         let params = origin.type_params().unwrap();
-        let param_count = params.length();
-        assert_eq!(substitute_types.length(), param_count);
+        assert_eq!(substitute_types.length(), params.length());
+
+        self.intern_type_after_substitution(origin, substitute_types)
+    }
+
+    /// Interns type after substitution, verifying the number of substitute
+    /// types and the constraints of the type parameters.
+    pub fn try_create_type_after_substitution(&self, origin: &Entity, substitute_types: &SharedArray<Entity>) -> Result<Entity, TypeSubstitutionError> {
+        let Some(params) = origin.type_params() else {
+            return Err(TypeSubstitutionError::WrongParameterCount { expected: 0, actual: substitute_types.length() });
+        };
+
+        // A constraint may refer to the type being verified, as in
+        // `T extends Comparable.<T>`; such a type is assumed to be valid
+        // while it is verified.
+        let key = (origin.clone(), substitute_types.iter().collect::<Vec<_>>());
+        if !self.0.substitutions_in_verification.borrow().contains(&key) {
+            self.0.substitutions_in_verification.borrow_mut().push(key.clone());
+            let r = ApplyType(self.0).verify_substitute_types(&params, substitute_types);
+            self.0.substitutions_in_verification.borrow_mut().retain(|k| k != &key);
+            r?;
+        }
 
+        Ok(self.intern_type_after_substitution(origin, substitute_types))
+    }
+
+    /// Interns a type after substitution without verifying
+    /// the substitute types.
+    pub(crate) fn intern_type_after_substitution(&self, origin: &Entity, substitute_types: &SharedArray<Entity>) -> Entity {
         let mut tas_list = self.0.types_after_sub.borrow_mut();
 
         let mut list = tas_list.get(&origin);
@@ -205,6 +244,16 @@ impl<'a> Factory<'a> {
 
     /// Interns a function type.
     pub fn create_function_type(&self, params: Vec<Rc<SemanticFunctionTypeParameter>>, result_type: Entity) -> Entity {
+        self.create_function_type_1(None, params, result_type)
+    }
+
+    /// Interns a generic function type, such as the signature of
+    /// a method with its own type parameters.
+    pub fn create_parameterized_function_type(&self, type_params: &SharedArray<Entity>, params: Vec<Rc<SemanticFunctionTypeParameter>>, result_type: Entity) -> Entity {
+        self.create_function_type_1(Some(type_params.clone()), params, result_type)
+    }
+
+    fn create_function_type_1(&self, type_params: Option<SharedArray<Entity>>, params: Vec<Rc<SemanticFunctionTypeParameter>>, result_type: Entity) -> Entity {
         let param_count = params.len();
         let mut function_types = self.0.function_types.borrow_mut();
         let mut collection = function_types.get_mut(&param_count);
@@ -217,6 +266,15 @@ impl<'a> Factory<'a> {
             if result_type != ft.result_type() {
                 continue 'ft;
             }
+            let type_params_2 = ft.type_params();
+            let same_type_params = match (&type_params, &type_params_2) {
+                (None, None) => true,
+                (Some(list_1), Some(list_2)) => list_1.length() == list_2.length() && list_1.iter().zip(list_2.iter()).all(|(t1, t2)| t1 == t2),
+                _ => false,
+            };
+            if !same_type_params {
+                continue 'ft;
+            }
             let mut params_1 = params.iter();
             let params_2 = ft.params();
             let mut params_2 = params_2.iter();
@@ -228,7 +286,7 @@ impl<'a> Factory<'a> {
             }
             return ft.clone();
         }
-        let ft = FunctionType::new(&self.0.arena, SharedArray::from(params), result_type);
+        let ft = FunctionType::new(&self.0.arena, type_params, SharedArray::from(params), result_type);
 
         let collection = function_types.get_mut(&param_count);
         collection.unwrap().push(ft.clone().into());
@@ -270,11 +328,15 @@ impl<'a> Factory<'a> {
     }
 
     pub fn create_type_parameter_type(&self, name: &QName) -> Entity {
-        TypeParameterType::new(&self.0.arena, name.clone()).into()
+        let r: Entity = TypeParameterType::new(&self.0.arena, name.clone()).into();
+        self.0.position_index().add_declaration(&r);
+        r
     }
 
     pub fn create_variable_slot(&self, name: &QName, read_only: bool, static_type: &Entity) -> Entity {
-        OriginalVariableSlot::new(&self.0.arena, name, read_only, static_type).into()
+        let r: Entity = OriginalVariableSlot::new(&self.0.arena, name, read_only, static_type).into();
+        self.0.position_index().add_declaration(&r);
+        r
     }
 
     /// Interns a variable slot after indirect substitution.
@@ -324,7 +386,9 @@ impl<'a> Factory<'a> {
     }
 
     pub fn create_virtual_slot(&self, name: &QName) -> Entity {
-        OriginalVirtualSlot::new(&self.0.arena, name).into()
+        let r: Entity = OriginalVirtualSlot::new(&self.0.arena, name).into();
+        self.0.position_index().add_declaration(&r);
+        r
     }
 
     /// Interns a virtual slot after indirect substitution.
@@ -374,7 +438,21 @@ impl<'a> Factory<'a> {
     }
 
     pub fn create_method_slot(&self, name: &QName, signature: &Entity) -> Entity {
-        OriginalMethodSlot::new(&self.0.arena, name, signature).into()
+        let r: Entity = OriginalMethodSlot::new(&self.0.arena, name, signature).into();
+        self.0.position_index().add_declaration(&r);
+        r
+    }
+
+    /// Creates a method slot whose signature was resolved from a function
+    /// signature node, recording the names of its parameters. A parameter
+    /// given by a destructuring pattern has an empty name.
+    pub fn create_method_slot_from_signature(&self, name: &QName, signature: &Entity, node: &FunctionSignature) -> Entity {
+        let r = self.create_method_slot(name, signature);
+        let param_names: SharedArray<String> = node.parameters.iter()
+            .map(|param| param.destructuring.destructuring.to_identifier_name().map(|(name, _)| name).unwrap_or_default())
+            .collect();
+        r.set_param_names(param_names);
+        r
     }
 
     /// Interns a method slot after indirect substitution.
@@ -423,6 +501,40 @@ impl<'a> Factory<'a> {
         mssub.into()
     }
 
+    /// Interns a generic method after substitution of its own type parameters,
+    /// verifying the substitute types.
+    ///
+    /// `method` is either an original method slot or a method slot
+    /// after substitution of the enclosing type's parameters.
+    pub fn create_method_slot_after_type_arguments(&self, method: &Entity, substitute_types: &SharedArray<Entity>) -> Result<Entity, TypeSubstitutionError> {
+        let Some(type_params) = method.type_params() else {
+            return Err(TypeSubstitutionError::WrongParameterCount { expected: 0, actual: substitute_types.length() });
+        };
+        if type_params.length() != substitute_types.length() {
+            return Err(TypeSubstitutionError::WrongParameterCount {
+                expected: type_params.length(),
+                actual: substitute_types.length(),
+            });
+        }
+
+        // Constraints may refer to the enclosing type's parameters.
+        let mut all_type_params: Vec<Entity> = type_params.iter().collect();
+        let mut all_substitute_types: Vec<Entity> = substitute_types.iter().collect();
+        if method.is::<MethodSlotAfterSubstitution>() {
+            all_type_params.extend(method.indirect_type_params().iter());
+            all_substitute_types.extend(method.indirect_substitute_types().iter());
+        }
+        let all_type_params = SharedArray::from(all_type_params);
+        let all_substitute_types = SharedArray::from(all_substitute_types);
+        ApplyType(self.0).verify_substitute_types(&all_type_params, &all_substitute_types)?;
+
+        // Compose with an existing substitution on its origin.
+        if method.is::<MethodSlotAfterSubstitution>() {
+            return Ok(self.create_method_slot_after_substitution(&method.origin(), &all_type_params, &all_substitute_types));
+        }
+        Ok(self.create_method_slot_after_substitution(method, &type_params, substitute_types))
+    }
+
     pub fn create_scope(&self) -> Entity {
         Scope::new(&self.0.arena).into()
     }
@@ -460,15 +572,30 @@ impl<'a> Factory<'a> {
     }
 
     pub fn create_package_property_import(&self, property: &Entity, location: Option<Location>) -> Entity {
-        PackagePropertyImport::new(&self.0.arena, property, location, &self.0.any_type()).into()
+        let r: Entity = PackagePropertyImport::new(&self.0.arena, property, location.clone(), &self.0.any_type()).into();
+        self.0.position_index().add_declaration(&r);
+        if let Some(location) = location {
+            References(self.0).add(property, &location, ReferenceKind::Import);
+        }
+        r
     }
 
     pub fn create_package_wildcard_import(&self, package: &Entity, location: Option<Location>) -> Entity {
-        PackageWildcardImport::new(&self.0.arena, package, location, &self.0.any_type()).into()
+        let r: Entity = PackageWildcardImport::new(&self.0.arena, package, location.clone(), &self.0.any_type()).into();
+        self.0.position_index().add_declaration(&r);
+        if let Some(location) = location {
+            References(self.0).add(package, &location, ReferenceKind::Import);
+        }
+        r
     }
 
     pub fn create_package_recursive_import(&self, package: &Entity, location: Option<Location>) -> Entity {
-        PackageRecursiveImport::new(&self.0.arena, package, location, &self.0.any_type()).into()
+        let r: Entity = PackageRecursiveImport::new(&self.0.arena, package, location.clone(), &self.0.any_type()).into();
+        self.0.position_index().add_declaration(&r);
+        if let Some(location) = location {
+            References(self.0).add(package, &location, ReferenceKind::Import);
+        }
+        r
     }
 
     pub fn create_undefined_constant(&self, static_type: &Entity) -> Entity {
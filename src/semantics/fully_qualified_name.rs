@@ -0,0 +1,299 @@
+use crate::ns::*;
+
+/// Lookup of definitions by fully qualified name and the inverse operation.
+///
+/// A fully qualified name consists of segments separated by dots, as in
+/// `mx.core.UIComponent.width`: a package name, followed by a package-level
+/// definition, followed by static or instance members. A segment may be
+/// qualified by a namespace, as in:
+///
+/// - `internal::Foo`, `private::x`, `protected::x`, `static protected::x` (system namespaces)
+/// - `mx_internal::x` (a namespace definition, resolved as in `TypeParser::parse_qname()`)
+/// - `"http://www.adobe.com/2006/flex/mx/internal"::x` (a namespace URI)
+///
+/// A `prototype` segment after a type selects its instance members,
+/// which are otherwise searched after its static members.
+/// A `/get` or `/set` suffix in the last segment selects the getter
+/// or setter of a virtual slot, as in `mx.core.UIComponent.width/get`.
+pub struct FullyQualifiedNameLookup<'a>(pub &'a Database);
+
+impl<'a> FullyQualifiedNameLookup<'a> {
+    /// Looks up a package, package-level definition or member
+    /// by its fully qualified name.
+    pub fn lookup(&self, name: &str) -> Option<Entity> {
+        let (name, accessor) = if let Some(name) = name.strip_suffix("/get") {
+            (name, Some(true))
+        } else if let Some(name) = name.strip_suffix("/set") {
+            (name, Some(false))
+        } else {
+            (name, None)
+        };
+        let segments = Self::split(name)?;
+
+        // Find the longest package prefix
+        let mut package = self.0.top_level_package();
+        let mut i = 0;
+        while i < segments.len() && !segments[i].contains("::") {
+            let Some(subpackage) = package.subpackages().get(&segments[i]) else {
+                break;
+            };
+            package = subpackage;
+            i += 1;
+        }
+        if i == segments.len() {
+            return if accessor.is_none() { Some(package) } else { None };
+        }
+
+        let mut r = self.lookup_in_names(&package.properties(self.0), &segments[i])?;
+        let mut prototype = false;
+        for segment in &segments[i + 1..] {
+            let base = r.resolve_alias();
+            if prototype {
+                r = self.lookup_in_names(&base.prototype(self.0), segment)?;
+                prototype = false;
+                continue;
+            }
+            if !(base.is::<ClassType>() || base.is::<EnumType>() || base.is::<InterfaceType>()) {
+                return None;
+            }
+            if segment == "prototype" {
+                prototype = true;
+                continue;
+            }
+            r = self.lookup_in_names(&base.properties(self.0), segment)
+                .or_else(|| self.lookup_in_names(&base.prototype(self.0), segment))?;
+        }
+        if prototype {
+            return None;
+        }
+
+        match accessor {
+            Some(true) if r.is::<VirtualSlot>() => r.getter(self.0),
+            Some(false) if r.is::<VirtualSlot>() => r.setter(self.0),
+            Some(_) => None,
+            None => Some(r),
+        }
+    }
+
+    /// Produces the canonical fully qualified name of a package,
+    /// package-level definition or member, such that `lookup()`
+    /// returns the same entity for it.
+    ///
+    /// Returns `None` for entities that are not addressable
+    /// by name, such as structural types, entities after substitution
+    /// and definitions local to a scope other than a package.
+    pub fn canonical_name(&self, entity: &Entity) -> Option<String> {
+        if entity.is::<Package>() {
+            return Some(entity.fully_qualified_name_list().join("."));
+        }
+        if entity.is::<MethodSlot>() && !entity.is::<MethodSlotAfterSubstitution>() {
+            if let Some(virtual_slot) = entity.of_virtual_slot(self.0) {
+                let name = self.canonical_name(&virtual_slot)?;
+                let suffix = if virtual_slot.getter(self.0).as_ref() == Some(entity) { "/get" } else { "/set" };
+                return Some(name + suffix);
+            }
+        }
+        let addressable = entity.is::<ClassType>() || entity.is::<EnumType>() || entity.is::<InterfaceType>()
+            || entity.is::<OriginalVariableSlot>() || entity.is::<OriginalVirtualSlot>() || entity.is::<OriginalMethodSlot>()
+            || entity.is::<Alias>();
+        if !addressable {
+            return None;
+        }
+        let name = entity.name();
+        let parent = entity.parent()?;
+        let segment = self.segment(&name)?;
+
+        if parent.is::<Package>() {
+            let package_name = self.canonical_name(&parent)?;
+            if package_name.is_empty() {
+                return Some(segment);
+            }
+            return Some(format!("{package_name}.{segment}"));
+        }
+
+        if parent.is::<ClassType>() || parent.is::<EnumType>() || parent.is::<InterfaceType>() {
+            let parent_name = self.canonical_name(&parent)?;
+            let r#static = parent.properties(self.0).get(&name).as_ref() == Some(entity);
+            if r#static {
+                return Some(format!("{parent_name}.{segment}"));
+            }
+            // Disambiguate from a static member with the same name.
+            if segment == "prototype" || self.lookup_in_names(&parent.properties(self.0), &segment).is_some() {
+                return Some(format!("{parent_name}.prototype.{segment}"));
+            }
+            return Some(format!("{parent_name}.{segment}"));
+        }
+
+        None
+    }
+
+    fn segment(&self, name: &QName) -> Option<String> {
+        let ns = name.namespace();
+        let local_name = name.local_name();
+        if let Some(kind) = ns.system_ns_kind() {
+            return Some(if kind == SystemNamespaceKind::Public {
+                local_name
+            } else {
+                format!("{}::{local_name}", kind.to_string())
+            });
+        }
+        if ns.is::<UserNamespace>() || ns.is::<ExplicitNamespace>() {
+            return Some(format!("\"{}\"::{local_name}", ns.uri()));
+        }
+        None
+    }
+
+    fn lookup_in_names(&self, names: &Names, segment: &str) -> Option<Entity> {
+        let Some((qual, local_name)) = segment.rsplit_once("::") else {
+            return names.get_in_any_public_ns(segment).ok().flatten();
+        };
+        let kind = match qual {
+            "public" => Some(SystemNamespaceKind::Public),
+            "private" => Some(SystemNamespaceKind::Private),
+            "protected" => Some(SystemNamespaceKind::Protected),
+            "internal" => Some(SystemNamespaceKind::Internal),
+            "static protected" => Some(SystemNamespaceKind::StaticProtected),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            return names.get_in_system_ns_kind(kind, local_name).ok().flatten();
+        }
+        if let Some(uri) = qual.strip_prefix('"').and_then(|q| q.strip_suffix('"')) {
+            return names.borrow().iter()
+                .find(|(name, _)| {
+                    let ns = name.namespace();
+                    name.local_name() == local_name && (ns.is::<UserNamespace>() || ns.is::<ExplicitNamespace>()) && ns.uri() == uri
+                })
+                .map(|(_, r)| r.clone());
+        }
+        let qname = TypeParser(self.0).parse_qname(segment).ok()?;
+        names.get(&qname)
+    }
+
+    /// Splits a fully qualified name into segments, ignoring
+    /// dots inside quoted namespace URIs.
+    fn split(name: &str) -> Option<Vec<String>> {
+        let mut segments = vec![];
+        let mut segment = String::new();
+        let mut quoted = false;
+        for ch in name.chars() {
+            match ch {
+                '"' => {
+                    quoted = !quoted;
+                    segment.push(ch);
+                },
+                '.' if !quoted => {
+                    if segment.is_empty() {
+                        return None;
+                    }
+                    segments.push(std::mem::take(&mut segment));
+                },
+                _ => segment.push(ch),
+            }
+        }
+        if quoted {
+            return None;
+        }
+        if !segment.is_empty() {
+            segments.push(segment);
+        } else if !segments.is_empty() {
+            return None;
+        }
+        Some(segments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    fn define(db: &Database, parent: &Entity, names: &Names, ns: &Entity, local_name: &str, definition: impl FnOnce(&QName) -> Entity) -> Entity {
+        let name = db.factory().create_qname(ns, local_name.into());
+        let r = definition(&name);
+        r.set_parent(Some(parent.clone()));
+        names.clone().set(name, r.clone());
+        r
+    }
+
+    fn define_variable(db: &Database, parent: &Entity, names: &Names, ns: &Entity, local_name: &str) -> Entity {
+        define(db, parent, names, ns, local_name, |name| db.factory().create_variable_slot(name, false, &db.any_type()))
+    }
+
+    /// Asserts that the canonical name of an entity is `expected`
+    /// and that looking it up results in the same entity.
+    fn assert_round_trip(db: &Database, entity: &Entity, expected: &str) {
+        let lookup = FullyQualifiedNameLookup(db);
+        assert_eq!(lookup.canonical_name(entity).as_deref(), Some(expected));
+        assert_eq!(lookup.lookup(expected).as_ref(), Some(entity), "{expected}");
+    }
+
+    #[test]
+    fn round_trips_packages_and_package_definitions() {
+        let db = Database::new(DatabaseOptions::default());
+        let com_a = db.factory().create_package(["com", "a"]);
+        let public_ns = com_a.public_ns().unwrap();
+        let internal_ns = com_a.internal_ns().unwrap();
+        let a = define(&db, &com_a, &com_a.properties(&db), &public_ns, "A", |name| db.factory().create_class_type(name.clone(), &public_ns));
+        let helper = define(&db, &com_a, &com_a.properties(&db), &internal_ns, "Helper", |name| db.factory().create_class_type(name.clone(), &internal_ns));
+        let top_level = define_variable(&db, &db.top_level_package(), &db.top_level_package().properties(&db), &db.top_level_package().public_ns().unwrap(), "t");
+
+        assert_round_trip(&db, &com_a, "com.a");
+        assert_round_trip(&db, &a, "com.a.A");
+        assert_round_trip(&db, &helper, "com.a.internal::Helper");
+        assert_round_trip(&db, &top_level, "t");
+        assert!(FullyQualifiedNameLookup(&db).lookup("com.a.Helper").is_none());
+    }
+
+    #[test]
+    fn round_trips_members() {
+        let db = Database::new(DatabaseOptions::default());
+        let com_a = db.factory().create_package(["com", "a"]);
+        let public_ns = com_a.public_ns().unwrap();
+        let a = define(&db, &com_a, &com_a.properties(&db), &public_ns, "A", |name| db.factory().create_class_type(name.clone(), &public_ns));
+        let (statics, prototype) = (a.properties(&db), a.prototype(&db));
+
+        let static_x = define_variable(&db, &a, &statics, &public_ns, "x");
+        let instance_x = define_variable(&db, &a, &prototype, &public_ns, "x");
+        let instance_y = define_variable(&db, &a, &prototype, &public_ns, "y");
+        let instance_prototype = define_variable(&db, &a, &prototype, &public_ns, "prototype");
+        assert_round_trip(&db, &static_x, "com.a.A.x");
+        assert_round_trip(&db, &instance_x, "com.a.A.prototype.x");
+        assert_round_trip(&db, &instance_y, "com.a.A.y");
+        assert_round_trip(&db, &instance_prototype, "com.a.A.prototype.prototype");
+
+        // Namespace-qualified members
+        let private_ns = db.factory().create_private_ns(Some(a.clone()));
+        let mx_internal = db.factory().create_user_ns("http://www.adobe.com/2006/flex/mx/internal".into());
+        let private_p = define_variable(&db, &a, &statics, &private_ns, "p");
+        let mx_internal_q = define_variable(&db, &a, &prototype, &mx_internal, "q");
+        assert_round_trip(&db, &private_p, "com.a.A.private::p");
+        assert_round_trip(&db, &mx_internal_q, "com.a.A.\"http://www.adobe.com/2006/flex/mx/internal\"::q");
+    }
+
+    #[test]
+    fn round_trips_accessors() {
+        let db = Database::new(DatabaseOptions::default());
+        let com_a = db.factory().create_package(["com", "a"]);
+        let public_ns = com_a.public_ns().unwrap();
+        let a = define(&db, &com_a, &com_a.properties(&db), &public_ns, "A", |name| db.factory().create_class_type(name.clone(), &public_ns));
+        let width = define(&db, &a, &a.prototype(&db), &public_ns, "width", |name| db.factory().create_virtual_slot(name));
+        let accessor = |signature: Entity| {
+            let method = db.factory().create_method_slot(&width.name(), &signature);
+            method.set_parent(Some(a.clone()));
+            method.set_of_virtual_slot(Some(width.clone()));
+            method
+        };
+        let getter = accessor(db.factory().create_function_type(vec![], db.any_type()));
+        let setter = accessor(db.factory().create_function_type(vec![Rc::new(SemanticFunctionTypeParameter {
+            kind: ParameterKind::Required,
+            static_type: db.any_type(),
+        })], db.void_type()));
+        width.set_getter(Some(getter.clone()));
+        width.set_setter(Some(setter.clone()));
+
+        assert_round_trip(&db, &width, "com.a.A.width");
+        assert_round_trip(&db, &getter, "com.a.A.width/get");
+        assert_round_trip(&db, &setter, "com.a.A.width/set");
+        assert!(FullyQualifiedNameLookup(&db).lookup("com.a.A/get").is_none());
+    }
+}
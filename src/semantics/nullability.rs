@@ -0,0 +1,416 @@
+use crate::ns::*;
+use std::collections::HashMap;
+
+/// Flow-sensitive nullability narrowing over the control flow graph
+/// of an activation.
+///
+/// The analysis tracks, per block, which local variable slots are known
+/// to be non-null after checks such as `if (x != null)` or `if (!x) return;`,
+/// so that a `T?` value may be used as `T!` without an explicit `NonNullValue`.
+///
+/// The first block of the control flow graph is taken as the entry block.
+/// A block whose last line is an `if` or `while` statement branches into
+/// the blocks whose first line lies within the consequent (or body) and
+/// into the remaining successors.
+///
+/// Locals captured by nested functions are never narrowed.
+pub struct NullabilityNarrowing<'a>(pub &'a Database);
+
+/// Result of [`NullabilityNarrowing`]: local variable slots known
+/// to be non-null before each block and directive.
+pub struct NonNullLocals {
+    block_entry: HashMap<ControlFlowBlock, Vec<Entity>>,
+    directive_entry: HashMap<ByAddress<Rc<Directive>>, Vec<Entity>>,
+}
+
+impl NonNullLocals {
+    /// Locals known to be non-null at the entry of a block.
+    pub fn at_block(&self, block: &ControlFlowBlock) -> Vec<Entity> {
+        self.block_entry.get(block).cloned().unwrap_or_default()
+    }
+
+    /// Locals known to be non-null before a directive executes.
+    /// Returns an empty list for directives that are not a line of
+    /// any block of the control flow graph.
+    pub fn at_directive(&self, directive: &Rc<Directive>) -> Vec<Entity> {
+        self.directive_entry.get(&ByAddress(directive.clone())).cloned().unwrap_or_default()
+    }
+
+    /// Indicates whether a local variable slot is known to be non-null
+    /// before a directive executes.
+    pub fn is_non_null_at(&self, directive: &Rc<Directive>, slot: &Entity) -> bool {
+        self.directive_entry.get(&ByAddress(directive.clone())).map(|list| list.contains(slot)).unwrap_or(false)
+    }
+}
+
+struct NullabilityAnalysis<'a, 'b> {
+    narrowing: &'b NullabilityNarrowing<'a>,
+    activation: Entity,
+}
+
+impl<'a, 'b> DataflowAnalysis for NullabilityAnalysis<'a, 'b> {
+    type Fact = MustSet<Entity>;
+
+    fn direction(&self) -> DataflowDirection {
+        DataflowDirection::Forward
+    }
+
+    fn boundary_fact(&self) -> Self::Fact {
+        MustSet::Set(vec![])
+    }
+
+    fn initial_fact(&self) -> Self::Fact {
+        MustSet::Universe
+    }
+
+    fn transfer(&mut self, block: &ControlFlowBlock, input: &Self::Fact) -> Self::Fact {
+        let mut state = input.clone();
+        for line in block.lines().iter() {
+            self.narrowing.transfer_line(&self.activation, line, &mut state);
+        }
+        state
+    }
+
+    fn edge_transfer(&mut self, edge: &ControlFlowEdge, fact: &Self::Fact) -> Self::Fact {
+        let mut r = fact.clone();
+        for slot in self.narrowing.edge_facts(&self.activation, &edge.from, &edge.to) {
+            r.insert(slot);
+        }
+        r
+    }
+}
+
+impl<'a> NullabilityNarrowing<'a> {
+    pub fn analyze(&self, activation: &Entity) -> NonNullLocals {
+        let cfg = activation.control_flow_graph();
+        let mut analysis = NullabilityAnalysis { narrowing: self, activation: activation.clone() };
+        let results = cfg.solve_dataflow(&mut analysis);
+
+        let mut r = NonNullLocals {
+            block_entry: HashMap::new(),
+            directive_entry: HashMap::new(),
+        };
+
+        // Record the state before each line of reached blocks
+        for block in cfg.blocks().iter() {
+            let Some(MustSet::Set(list)) = results.at_entry(&block) else {
+                continue;
+            };
+            r.block_entry.insert(block.clone(), list.clone());
+            let mut state = MustSet::Set(list);
+            for line in block.lines().iter() {
+                r.directive_entry.insert(ByAddress(line.clone()), state.elements().cloned().unwrap_or_default());
+                self.transfer_line(activation, line, &mut state);
+            }
+        }
+
+        r
+    }
+
+    fn transfer_line(&self, activation: &Entity, line: &Rc<Directive>, state: &mut MustSet<Entity>) {
+        let mut assigned: Vec<Entity> = vec![];
+        self.assigned_locals_of_directive(activation, line, &mut assigned);
+        state.retain(|slot| !assigned.contains(slot));
+    }
+
+    /// Facts that hold when control flows through an edge.
+    fn edge_facts(&self, activation: &Entity, from: &ControlFlowBlock, to: &ControlFlowBlock) -> Vec<Entity> {
+        let Some(last_line) = from.lines().last().cloned() else {
+            return vec![];
+        };
+        let Some(first_line) = to.lines().first().cloned() else {
+            return vec![];
+        };
+        let first_location = first_line.location();
+        let (test, consequent, alternative) = match last_line.as_ref() {
+            Directive::IfStatement(d) => (d.test.clone(), d.consequent.clone(), d.alternative.clone()),
+            Directive::WhileStatement(d) => (d.test.clone(), d.body.clone(), None),
+            _ => {
+                return vec![];
+            },
+        };
+        if location_contains(&consequent.location(), &first_location) {
+            return self.test_facts(activation, &test).0;
+        }
+        if alternative.is_none() || location_contains(&alternative.unwrap().location(), &first_location) {
+            return self.test_facts(activation, &test).1;
+        }
+        vec![]
+    }
+
+    /// Returns the locals known to be non-null when a test expression
+    /// is respectively truthy and falsy.
+    fn test_facts(&self, activation: &Entity, test: &Rc<Expression>) -> (Vec<Entity>, Vec<Entity>) {
+        match test.as_ref() {
+            Expression::Paren(e) => self.test_facts(activation, &e.expression),
+            Expression::QualifiedIdentifier(_) => {
+                (self.local_of(activation, test).into_iter().collect(), vec![])
+            },
+            Expression::Unary(e) if e.operator == Operator::LogicalNot => {
+                let (t, f) = self.test_facts(activation, &e.expression);
+                (f, t)
+            },
+            Expression::Binary(e) => {
+                match e.operator {
+                    Operator::LogicalAnd => {
+                        let (t1, f1) = self.test_facts(activation, &e.left);
+                        let (t2, f2) = self.test_facts(activation, &e.right);
+                        (union(t1, t2), intersection(f1, f2))
+                    },
+                    Operator::LogicalOr => {
+                        let (t1, f1) = self.test_facts(activation, &e.left);
+                        let (t2, f2) = self.test_facts(activation, &e.right);
+                        (intersection(t1, t2), union(f1, f2))
+                    },
+                    Operator::Equals | Operator::StrictEquals | Operator::NotEquals | Operator::StrictNotEquals => {
+                        let local = if is_null_literal(&e.right) {
+                            self.local_of(activation, &e.left)
+                        } else if is_null_literal(&e.left) {
+                            self.local_of(activation, &e.right)
+                        } else {
+                            None
+                        };
+                        let facts: Vec<Entity> = local.into_iter().collect();
+                        if matches!(e.operator, Operator::NotEquals | Operator::StrictNotEquals) {
+                            (facts, vec![])
+                        } else {
+                            (vec![], facts)
+                        }
+                    },
+                    Operator::Is | Operator::Instanceof => {
+                        (self.local_of(activation, &e.left).into_iter().collect(), vec![])
+                    },
+                    _ => (vec![], vec![]),
+                }
+            },
+            _ => (vec![], vec![]),
+        }
+    }
+
+    /// If an expression is a reference to a non-captured local
+    /// variable of the activation, returns its variable slot.
+    fn local_of(&self, activation: &Entity, exp: &Rc<Expression>) -> Option<Entity> {
+        let exp = unparen(exp);
+        if !matches!(exp.as_ref(), Expression::QualifiedIdentifier(_)) {
+            return None;
+        }
+        self.local_of_reference(activation, &self.0.node_mapping().get(&exp)?)
+    }
+
+    /// If a reference resolves to a non-captured local variable
+    /// of the activation, returns its variable slot.
+    fn local_of_reference(&self, activation: &Entity, r: &Entity) -> Option<Entity> {
+        let slot = if r.is::<ScopeReferenceValue>() {
+            let mut scope = Some(r.base());
+            while let Some(s) = scope.clone() {
+                if s.is::<Activation>() {
+                    break;
+                }
+                scope = s.parent();
+            }
+            if scope.as_ref() != Some(activation) {
+                return None;
+            }
+            r.property()
+        } else if r.is::<VariableSlot>() {
+            r.clone()
+        } else {
+            return None;
+        };
+        if !slot.is::<VariableSlot>() || activation.property_has_capture(&slot) {
+            return None;
+        }
+        Some(slot)
+    }
+
+    /// Collects locals assigned by a line. Sub-directives of compound
+    /// statements are lines of other blocks and are not visited.
+    fn assigned_locals_of_directive(&self, activation: &Entity, directive: &Rc<Directive>, r: &mut Vec<Entity>) {
+        match directive.as_ref() {
+            Directive::ExpressionStatement(d) => self.assigned_locals(activation, &d.expression, r),
+            Directive::IfStatement(d) => self.assigned_locals(activation, &d.test, r),
+            Directive::WhileStatement(d) => self.assigned_locals(activation, &d.test, r),
+            Directive::DoStatement(d) => self.assigned_locals(activation, &d.test, r),
+            Directive::SwitchStatement(d) => self.assigned_locals(activation, &d.discriminant, r),
+            Directive::WithStatement(d) => self.assigned_locals(activation, &d.object, r),
+            Directive::ReturnStatement(d) => {
+                if let Some(exp) = d.expression.as_ref() {
+                    self.assigned_locals(activation, exp, r);
+                }
+            },
+            Directive::ThrowStatement(d) => self.assigned_locals(activation, &d.expression, r),
+            Directive::ForStatement(d) => {
+                match d.init.as_ref() {
+                    Some(ForInitializer::Expression(exp)) => self.assigned_locals(activation, exp, r),
+                    Some(ForInitializer::VariableDefinition(defn)) => {
+                        for binding in defn.bindings.iter() {
+                            self.assigned_locals_of_binding(activation, binding, r);
+                        }
+                    },
+                    None => {},
+                }
+                if let Some(exp) = d.test.as_ref() {
+                    self.assigned_locals(activation, exp, r);
+                }
+                if let Some(exp) = d.update.as_ref() {
+                    self.assigned_locals(activation, exp, r);
+                }
+            },
+            Directive::ForInStatement(d) => {
+                match &d.left {
+                    ForInBinding::Expression(exp) => self.assigned_locals_of_pattern(activation, exp, r),
+                    ForInBinding::VariableDefinition(defn) => {
+                        for binding in defn.bindings.iter() {
+                            self.assigned_locals_of_binding(activation, binding, r);
+                        }
+                    },
+                }
+                self.assigned_locals(activation, &d.right, r);
+            },
+            Directive::VariableDefinition(d) => {
+                for binding in d.bindings.iter() {
+                    self.assigned_locals_of_binding(activation, binding, r);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fn assigned_locals_of_binding(&self, activation: &Entity, binding: &Rc<VariableBinding>, r: &mut Vec<Entity>) {
+        self.assigned_locals_of_pattern(activation, &binding.destructuring.destructuring, r);
+        if let Some(init) = binding.initializer.as_ref() {
+            self.assigned_locals(activation, init, r);
+        }
+    }
+
+    /// Collects locals assigned by an expression, including assignments
+    /// nested in any of its subexpressions and destructuring assignments.
+    fn assigned_locals(&self, activation: &Entity, exp: &Rc<Expression>, r: &mut Vec<Entity>) {
+        if let Expression::Assignment(e) = exp.as_ref() {
+            self.assigned_locals_of_pattern(activation, &e.left, r);
+        }
+        for exp in subexpressions(exp) {
+            self.assigned_locals(activation, &exp, r);
+        }
+    }
+
+    fn assigned_locals_of_pattern(&self, activation: &Entity, pattern: &Rc<Expression>, r: &mut Vec<Entity>) {
+        let mut targets: Vec<Entity> = vec![];
+        destructuring_targets(self.0, pattern, &mut targets);
+        r.extend(targets.iter().filter_map(|target| self.local_of_reference(activation, target)));
+    }
+}
+
+fn unparen(exp: &Rc<Expression>) -> Rc<Expression> {
+    if let Expression::Paren(e) = exp.as_ref() {
+        unparen(&e.expression)
+    } else {
+        exp.clone()
+    }
+}
+
+fn is_null_literal(exp: &Rc<Expression>) -> bool {
+    matches!(unparen(exp).as_ref(), Expression::NullLiteral(_))
+}
+
+pub(crate) fn location_contains(outer: &Location, inner: &Location) -> bool {
+    Rc::ptr_eq(&outer.compilation_unit(), &inner.compilation_unit())
+        && outer.first_offset() <= inner.first_offset()
+        && inner.last_offset() <= outer.last_offset()
+}
+
+fn union(mut a: Vec<Entity>, b: Vec<Entity>) -> Vec<Entity> {
+    for e in b {
+        if !a.contains(&e) {
+            a.push(e);
+        }
+    }
+    a
+}
+
+fn intersection(a: Vec<Entity>, b: Vec<Entity>) -> Vec<Entity> {
+    a.into_iter().filter(|e| b.contains(e)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::semantics::test_fixtures::*;
+
+    /// Analyzes a single `if` statement whose consequent is a block, with
+    /// the local `x` mapped, returning the directives of the consequent
+    /// and the result.
+    fn analyze_if(db: &Database, source: &str) -> (Vec<Rc<Directive>>, NonNullLocals, Entity) {
+        let activation = define_activation(db);
+        let x = define_local(db, &activation, "x");
+        let directives = parse_directives(source);
+        map_identifiers(db, &directives, &|name| (name == "x").then(|| x.clone()));
+        let Directive::IfStatement(d) = directives[0].as_ref() else {
+            panic!("expected an if statement");
+        };
+        let consequent = block_directives(&d.consequent);
+        let cfg = activation.control_flow_graph();
+        let test_block = add_block(&cfg, vec![directives[0].clone()]);
+        let consequent_block = add_block(&cfg, consequent.clone());
+        let exit_block = add_block(&cfg, vec![]);
+        add_edge(&cfg, &test_block, &consequent_block);
+        add_edge(&cfg, &test_block, &exit_block);
+        add_edge(&cfg, &consequent_block, &exit_block);
+        let result = NullabilityNarrowing(db).analyze(&activation);
+        (consequent, result, x)
+    }
+
+    #[test]
+    fn narrows_after_null_check() {
+        let db = Database::new(DatabaseOptions::default());
+        let (consequent, result, x) = analyze_if(&db, "if (x != null) { x.y; }");
+        assert!(result.is_non_null_at(&consequent[0], &x));
+
+        let (consequent, result, x) = analyze_if(&db, "if (!(x == null) && true) { x.y; }");
+        assert!(result.is_non_null_at(&consequent[0], &x));
+
+        let (consequent, result, x) = analyze_if(&db, "if (x == null) { x.y; }");
+        assert!(!result.is_non_null_at(&consequent[0], &x));
+    }
+
+    #[test]
+    fn kills_on_plain_assignment() {
+        let db = Database::new(DatabaseOptions::default());
+        let (consequent, result, x) = analyze_if(&db, "if (x != null) { x = null; x.y; }");
+        assert!(result.is_non_null_at(&consequent[0], &x));
+        assert!(!result.is_non_null_at(&consequent[1], &x));
+    }
+
+    #[test]
+    fn kills_on_nested_assignment() {
+        let db = Database::new(DatabaseOptions::default());
+        for source in [
+            "if (x != null) { f([x = null]); x.y; }",
+            "if (x != null) { f({ k: x = null }); x.y; }",
+            "if (x != null) { o?.f(x = null); x.y; }",
+            "if (x != null) { o.(x = null); x.y; }",
+            "if (x != null) { o..[x = null]; x.y; }",
+            "if (x != null) { o.ns::[x = null]; x.y; }",
+            "if (x != null) { <a>{x = null}</a>; x.y; }",
+        ] {
+            let (consequent, result, x) = analyze_if(&db, source);
+            assert_eq!(consequent.len(), 2, "{source}");
+            assert!(!result.is_non_null_at(&consequent[1], &x), "{source}");
+        }
+    }
+
+    #[test]
+    fn kills_on_destructuring_assignment() {
+        let db = Database::new(DatabaseOptions::default());
+        for source in [
+            "if (x != null) { [x] = [null]; x.y; }",
+            "if (x != null) { [y, ...x] = [null]; x.y; }",
+            "if (x != null) { ({ k: x } = { k: null }); x.y; }",
+            "if (x != null) { ({ x } = { x: null }); x.y; }",
+        ] {
+            let (consequent, result, x) = analyze_if(&db, source);
+            assert_eq!(consequent.len(), 2, "{source}");
+            assert!(!result.is_non_null_at(&consequent[1], &x), "{source}");
+        }
+    }
+}
@@ -1,18 +1,20 @@
 use crate::ns::*;
 
-use num_traits::FromPrimitive;
-// use num_traits::{One, Zero};
-// use num_bigint::BigInt;
+use num_traits::{FromPrimitive, One, Signed, ToPrimitive, Zero};
+use num_bigint::BigInt;
 use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Rem, Shl, Shr, Sub};
 
 /// Represents a numeric value represented as one of the data types
-/// `Number`, `float`, `uint`, or `int`.
+/// `Number`, `float`, `uint`, `int`, `long`, `ulong`, or `BigInt`.
 #[derive(Clone, PartialEq, PartialOrd)]
 pub enum Number {
     Number(f64),
     Float(f32),
     Int(i32),
     Uint(u32),
+    Long(i64),
+    Ulong(u64),
+    BigInt(BigInt),
 }
 
 impl Add for Number {
@@ -27,6 +29,7 @@ impl Add for Number {
                 let rhs = rhs.force_double();
                 Self::Number(v + rhs)
             },
+            Self::BigInt(v) => Self::BigInt(v + rhs.force_big_int()),
             Self::Int(v) => {
                 let rhs = rhs.force_int();
                 Self::Int(v.checked_add(rhs).unwrap_or(0))
@@ -35,6 +38,14 @@ impl Add for Number {
                 let rhs = rhs.force_uint();
                 Self::Uint(v.checked_add(rhs).unwrap_or(0))
             },
+            Self::Long(v) => {
+                let rhs = rhs.force_long();
+                Self::Long(v.wrapping_add(rhs))
+            },
+            Self::Ulong(v) => {
+                let rhs = rhs.force_ulong();
+                Self::Ulong(v.wrapping_add(rhs))
+            },
         }
     }
 }
@@ -51,6 +62,7 @@ impl Sub for Number {
                 let rhs = rhs.force_double();
                 Self::Number(v - rhs)
             },
+            Self::BigInt(v) => Self::BigInt(v - rhs.force_big_int()),
             Self::Int(v) => {
                 let rhs = rhs.force_int();
                 Self::Int(v.checked_sub(rhs).unwrap_or(0))
@@ -59,6 +71,14 @@ impl Sub for Number {
                 let rhs = rhs.force_uint();
                 Self::Uint(v.checked_sub(rhs).unwrap_or(0))
             },
+            Self::Long(v) => {
+                let rhs = rhs.force_long();
+                Self::Long(v.wrapping_sub(rhs))
+            },
+            Self::Ulong(v) => {
+                let rhs = rhs.force_ulong();
+                Self::Ulong(v.wrapping_sub(rhs))
+            },
         }
     }
 }
@@ -75,6 +95,7 @@ impl Mul for Number {
                 let rhs = rhs.force_double();
                 Self::Number(v * rhs)
             },
+            Self::BigInt(v) => Self::BigInt(v * rhs.force_big_int()),
             Self::Int(v) => {
                 let rhs = rhs.force_int();
                 Self::Int(v.checked_mul(rhs).unwrap_or(0))
@@ -83,6 +104,14 @@ impl Mul for Number {
                 let rhs = rhs.force_uint();
                 Self::Uint(v.checked_mul(rhs).unwrap_or(0))
             },
+            Self::Long(v) => {
+                let rhs = rhs.force_long();
+                Self::Long(v.wrapping_mul(rhs))
+            },
+            Self::Ulong(v) => {
+                let rhs = rhs.force_ulong();
+                Self::Ulong(v.wrapping_mul(rhs))
+            },
         }
     }
 }
@@ -99,6 +128,10 @@ impl Div for Number {
                 let rhs = rhs.force_double();
                 Self::Number(v / rhs)
             },
+            Self::BigInt(v) => {
+                let rhs = rhs.force_big_int();
+                Self::BigInt(if rhs.is_zero() { BigInt::zero() } else { v / rhs })
+            },
             Self::Int(v) => {
                 let rhs = rhs.force_int();
                 Self::Int(v.checked_div(rhs).unwrap_or(0))
@@ -107,6 +140,14 @@ impl Div for Number {
                 let rhs = rhs.force_uint();
                 Self::Uint(v.checked_div(rhs).unwrap_or(0))
             },
+            Self::Long(v) => {
+                let rhs = rhs.force_long();
+                Self::Long(v.checked_div(rhs).unwrap_or(0))
+            },
+            Self::Ulong(v) => {
+                let rhs = rhs.force_ulong();
+                Self::Ulong(v.checked_div(rhs).unwrap_or(0))
+            },
         }
     }
 }
@@ -123,6 +164,10 @@ impl Rem for Number {
                 let rhs = rhs.force_double();
                 Self::Number(v % rhs)
             },
+            Self::BigInt(v) => {
+                let rhs = rhs.force_big_int();
+                Self::BigInt(if rhs.is_zero() { BigInt::zero() } else { v % rhs })
+            },
             Self::Int(v) => {
                 let rhs = rhs.force_int();
                 Self::Int(v.checked_rem(rhs).unwrap_or(0))
@@ -131,6 +176,14 @@ impl Rem for Number {
                 let rhs = rhs.force_uint();
                 Self::Uint(v.checked_rem(rhs).unwrap_or(0))
             },
+            Self::Long(v) => {
+                let rhs = rhs.force_long();
+                Self::Long(v.checked_rem(rhs).unwrap_or(0))
+            },
+            Self::Ulong(v) => {
+                let rhs = rhs.force_ulong();
+                Self::Ulong(v.checked_rem(rhs).unwrap_or(0))
+            },
         }
     }
 }
@@ -141,8 +194,11 @@ impl std::ops::Neg for Number {
         match self {
             Self::Float(v) => Self::Float(-v),
             Self::Number(v) => Self::Number(-v),
+            Self::BigInt(v) => Self::BigInt(-v),
             Self::Int(v) => Self::Int(-v),
             Self::Uint(v) => Self::Uint(v),
+            Self::Long(v) => Self::Long(v.wrapping_neg()),
+            Self::Ulong(v) => Self::Ulong(v),
         }
     }
 }
@@ -153,12 +209,13 @@ impl BitAnd for Number {
         match self {
             Self::Float(v) => {
                 let rhs = rhs.force_float();
-                Self::Float(f32::from_u32(unsafe { v.to_int_unchecked::<u32>() } & unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0.0))
+                Self::Float((to_int32(v as f64) & to_int32(rhs as f64)) as f32)
             },
             Self::Number(v) => {
                 let rhs = rhs.force_double();
-                Self::Number(f64::from_u32(unsafe { v.to_int_unchecked::<u32>() } & unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0.0))
+                Self::Number((to_int32(v) & to_int32(rhs)) as f64)
             },
+            Self::BigInt(v) => Self::BigInt(v & rhs.force_big_int()),
             Self::Int(v) => {
                 let rhs = rhs.force_int();
                 Self::Int(v & rhs)
@@ -167,6 +224,14 @@ impl BitAnd for Number {
                 let rhs = rhs.force_uint();
                 Self::Uint(v & rhs)
             },
+            Self::Long(v) => {
+                let rhs = rhs.force_long();
+                Self::Long(v & rhs)
+            },
+            Self::Ulong(v) => {
+                let rhs = rhs.force_ulong();
+                Self::Ulong(v & rhs)
+            },
         }
     }
 }
@@ -177,12 +242,13 @@ impl BitXor for Number {
         match self {
             Self::Float(v) => {
                 let rhs = rhs.force_float();
-                Self::Float(f32::from_u32(unsafe { v.to_int_unchecked::<u32>() } ^ unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0.0))
+                Self::Float((to_int32(v as f64) ^ to_int32(rhs as f64)) as f32)
             },
             Self::Number(v) => {
                 let rhs = rhs.force_double();
-                Self::Number(f64::from_u32(unsafe { v.to_int_unchecked::<u32>() } ^ unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0.0))
+                Self::Number((to_int32(v) ^ to_int32(rhs)) as f64)
             },
+            Self::BigInt(v) => Self::BigInt(v ^ rhs.force_big_int()),
             Self::Int(v) => {
                 let rhs = rhs.force_int();
                 Self::Int(v ^ rhs)
@@ -191,6 +257,14 @@ impl BitXor for Number {
                 let rhs = rhs.force_uint();
                 Self::Uint(v ^ rhs)
             },
+            Self::Long(v) => {
+                let rhs = rhs.force_long();
+                Self::Long(v ^ rhs)
+            },
+            Self::Ulong(v) => {
+                let rhs = rhs.force_ulong();
+                Self::Ulong(v ^ rhs)
+            },
         }
     }
 }
@@ -201,12 +275,13 @@ impl BitOr for Number {
         match self {
             Self::Float(v) => {
                 let rhs = rhs.force_float();
-                Self::Float(f32::from_u32(unsafe { v.to_int_unchecked::<u32>() } | unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0.0))
+                Self::Float((to_int32(v as f64) | to_int32(rhs as f64)) as f32)
             },
             Self::Number(v) => {
                 let rhs = rhs.force_double();
-                Self::Number(f64::from_u32(unsafe { v.to_int_unchecked::<u32>() } | unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0.0))
+                Self::Number((to_int32(v) | to_int32(rhs)) as f64)
             },
+            Self::BigInt(v) => Self::BigInt(v | rhs.force_big_int()),
             Self::Int(v) => {
                 let rhs = rhs.force_int();
                 Self::Int(v | rhs)
@@ -215,6 +290,14 @@ impl BitOr for Number {
                 let rhs = rhs.force_uint();
                 Self::Uint(v | rhs)
             },
+            Self::Long(v) => {
+                let rhs = rhs.force_long();
+                Self::Long(v | rhs)
+            },
+            Self::Ulong(v) => {
+                let rhs = rhs.force_ulong();
+                Self::Ulong(v | rhs)
+            },
         }
     }
 }
@@ -225,19 +308,28 @@ impl Shl for Number {
         match self {
             Self::Float(v) => {
                 let rhs = rhs.force_float();
-                Self::Float(f32::from_u32(unsafe { v.to_int_unchecked::<u32>() }.checked_shl(unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0)).unwrap_or(0.0))
+                Self::Float(to_int32(v as f64).wrapping_shl(to_uint32(rhs as f64)) as f32)
             },
             Self::Number(v) => {
                 let rhs = rhs.force_double();
-                Self::Number(f64::from_u32(unsafe { v.to_int_unchecked::<u32>() }.checked_shl(unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0)).unwrap_or(0.0))
+                Self::Number(to_int32(v).wrapping_shl(to_uint32(rhs)) as f64)
             },
+            Self::BigInt(v) => Self::BigInt(big_int_shift_left(&v, &rhs.force_big_int())),
             Self::Int(v) => {
                 let rhs = rhs.force_int();
-                Self::Int(v.checked_shl(rhs.try_into().unwrap_or(0)).unwrap_or(0))
+                Self::Int(v.wrapping_shl(rhs as u32))
             },
             Self::Uint(v) => {
                 let rhs = rhs.force_uint();
-                Self::Uint(v.checked_shl(rhs).unwrap_or(0))
+                Self::Uint(v.wrapping_shl(rhs))
+            },
+            Self::Long(v) => {
+                let rhs = rhs.force_long();
+                Self::Long(v.wrapping_shl(rhs as u32))
+            },
+            Self::Ulong(v) => {
+                let rhs = rhs.force_ulong();
+                Self::Ulong(v.wrapping_shl(rhs as u32))
             },
         }
     }
@@ -249,19 +341,28 @@ impl Shr for Number {
         match self {
             Self::Float(v) => {
                 let rhs = rhs.force_float();
-                Self::Float(f32::from_u32(unsafe { v.to_int_unchecked::<u32>() }.checked_shr(unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0)).unwrap_or(0.0))
+                Self::Float(to_int32(v as f64).wrapping_shr(to_uint32(rhs as f64)) as f32)
             },
             Self::Number(v) => {
                 let rhs = rhs.force_double();
-                Self::Number(f64::from_u32(unsafe { v.to_int_unchecked::<u32>() }.checked_shr(unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0)).unwrap_or(0.0))
+                Self::Number(to_int32(v).wrapping_shr(to_uint32(rhs)) as f64)
             },
+            Self::BigInt(v) => Self::BigInt(big_int_shift_left(&v, &-rhs.force_big_int())),
             Self::Int(v) => {
                 let rhs = rhs.force_int();
-                Self::Int(v.checked_shr(rhs.try_into().unwrap_or(0)).unwrap_or(0))
+                Self::Int(v.wrapping_shr(rhs as u32))
             },
             Self::Uint(v) => {
                 let rhs = rhs.force_uint();
-                Self::Uint(v.checked_shr(rhs).unwrap_or(0))
+                Self::Uint(v.wrapping_shr(rhs))
+            },
+            Self::Long(v) => {
+                let rhs = rhs.force_long();
+                Self::Long(v.wrapping_shr(rhs as u32))
+            },
+            Self::Ulong(v) => {
+                let rhs = rhs.force_ulong();
+                Self::Ulong(v.wrapping_shr(rhs as u32))
             },
         }
     }
@@ -269,84 +370,108 @@ impl Shr for Number {
 
 impl Number {
     pub fn zero(type_thing: &Entity, host: &Database) -> Self {
+        Self::try_zero(type_thing, host).expect("Type is not numeric.")
+    }
+
+    pub fn try_zero(type_thing: &Entity, host: &Database) -> Option<Self> {
         if type_thing == &host.number_type() {
-            Self::Number(0.0)
+            Some(Self::Number(0.0))
         } else if type_thing == &host.int_type() {
-            Self::Int(0)
+            Some(Self::Int(0))
         } else if type_thing == &host.uint_type() {
-            Self::Uint(0)
-        /*
+            Some(Self::Uint(0))
+        } else if type_thing == &host.long_type() {
+            Some(Self::Long(0))
+        } else if type_thing == &host.ulong_type() {
+            Some(Self::Ulong(0))
         } else if type_thing == &host.big_int_type() {
-            Self::BigInt(BigInt::zero())
-        */
+            Some(Self::BigInt(BigInt::zero()))
         } else if type_thing == &host.float_type() {
-            Self::Float(0.0)
+            Some(Self::Float(0.0))
         } else {
-            panic!()
+            None
         }
     }
 
     pub fn nan(type_thing: &Entity, host: &Database) -> Self {
+        Self::try_nan(type_thing, host).expect("Type does not support NaN.")
+    }
+
+    pub fn try_nan(type_thing: &Entity, host: &Database) -> Option<Self> {
         if type_thing == &host.number_type() {
-            Self::Number(f64::NAN)
+            Some(Self::Number(f64::NAN))
         } else if type_thing == &host.float_type() {
-            Self::Float(f32::NAN)
+            Some(Self::Float(f32::NAN))
         } else {
-            panic!("Type does not support NaN.");
+            None
         }
     }
 
     pub fn one(type_thing: &Entity, host: &Database) -> Self {
+        Self::try_one(type_thing, host).expect("Type is not numeric.")
+    }
+
+    pub fn try_one(type_thing: &Entity, host: &Database) -> Option<Self> {
         if type_thing == &host.number_type() {
-            Self::Number(1.0)
+            Some(Self::Number(1.0))
         } else if type_thing == &host.int_type() {
-            Self::Int(1)
+            Some(Self::Int(1))
         } else if type_thing == &host.uint_type() {
-            Self::Uint(1)
-        /*
+            Some(Self::Uint(1))
+        } else if type_thing == &host.long_type() {
+            Some(Self::Long(1))
+        } else if type_thing == &host.ulong_type() {
+            Some(Self::Ulong(1))
         } else if type_thing == &host.big_int_type() {
-            Self::BigInt(BigInt::one())
-        */
+            Some(Self::BigInt(BigInt::one()))
         } else if type_thing == &host.float_type() {
-            Self::Float(1.0)
+            Some(Self::Float(1.0))
         } else {
-            panic!()
+            None
         }
     }
 
     pub fn minimum_value(type_thing: &Entity, host: &Database) -> Self {
+        Self::try_minimum_value(type_thing, host).expect("Type has no minimum value.")
+    }
+
+    pub fn try_minimum_value(type_thing: &Entity, host: &Database) -> Option<Self> {
         if type_thing == &host.number_type() {
-            Self::Number(f64::NEG_INFINITY)
+            Some(Self::Number(f64::NEG_INFINITY))
         } else if type_thing == &host.int_type() {
-            Self::Int(i32::MIN)
+            Some(Self::Int(i32::MIN))
         } else if type_thing == &host.uint_type() {
-            Self::Uint(0)
-        /*
-        } else if type_thing == &host.big_int_type() {
-            panic!("BigInt has no minimum value")
-        */
+            Some(Self::Uint(0))
+        } else if type_thing == &host.long_type() {
+            Some(Self::Long(i64::MIN))
+        } else if type_thing == &host.ulong_type() {
+            Some(Self::Ulong(0))
         } else if type_thing == &host.float_type() {
-            Self::Float(f32::NEG_INFINITY)
+            Some(Self::Float(f32::NEG_INFINITY))
         } else {
-            panic!()
+            None
         }
     }
 
     pub fn maximum_value(type_thing: &Entity, host: &Database) -> Self {
+        Self::try_maximum_value(type_thing, host).expect("Type has no maximum value.")
+    }
+
+    pub fn try_maximum_value(type_thing: &Entity, host: &Database) -> Option<Self> {
         if type_thing == &host.number_type() {
-            Self::Number(f64::INFINITY)
+            Some(Self::Number(f64::INFINITY))
         } else if type_thing == &host.int_type() {
-            Self::Int(i32::MAX)
+            Some(Self::Int(i32::MAX))
         } else if type_thing == &host.uint_type() {
-            Self::Uint(u32::MAX)
-        /*
-        } else if type_thing == &host.big_int_type() {
-            panic!("BigInt has no maximum value")
-        */
+            Some(Self::Uint(u32::MAX))
+        } else if type_thing == &host.long_type() {
+            Some(Self::Long(i64::MAX))
+        } else if type_thing == &host.ulong_type() {
+            Some(Self::Ulong(u64::MAX))
         } else if type_thing == &host.float_type() {
-            Self::Float(f32::INFINITY)
+            Some(Self::Float(f32::INFINITY))
         } else {
-            panic!()
+            None
         }
     }
 
@@ -354,9 +479,11 @@ impl Number {
         match self {
             Self::Float(v) => v == &0.0,
             Self::Number(v) => v == &0.0,
-            // Self::BigInt(v) => v.is_zero(),
+            Self::BigInt(v) => v.is_zero(),
             Self::Int(v) => v == &0,
             Self::Uint(v) => v == &0,
+            Self::Long(v) => v == &0,
+            Self::Ulong(v) => v == &0,
         }
     }
 
@@ -364,9 +491,11 @@ impl Number {
         match self {
             Self::Float(v) => v == &1.0,
             Self::Number(v) => v == &1.0,
-            // Self::BigInt(v) => v.is_one(),
+            Self::BigInt(v) => v.is_one(),
             Self::Int(v) => v == &1,
             Self::Uint(v) => v == &1,
+            Self::Long(v) => v == &1,
+            Self::Ulong(v) => v == &1,
         }
     }
 
@@ -374,9 +503,11 @@ impl Number {
         match self {
             Self::Float(v) => Self::Float(v * 2.0),
             Self::Number(v) => Self::Number(v * 2.0),
-            // Self::BigInt(v) => Self::BigInt(v * 2),
+            Self::BigInt(v) => Self::BigInt(v * 2),
             Self::Int(v) => Self::Int(v * 2),
             Self::Uint(v) => Self::Uint(v * 2),
+            Self::Long(v) => Self::Long(v * 2),
+            Self::Ulong(v) => Self::Ulong(v * 2),
         }
     }
 
@@ -384,9 +515,11 @@ impl Number {
         match self {
             Self::Float(v) => Self::Float(v + 1.0),
             Self::Number(v) => Self::Number(v + 1.0),
-            // Self::BigInt(v) => Self::BigInt(v + 1),
+            Self::BigInt(v) => Self::BigInt(v + 1),
             Self::Int(v) => Self::Int(v + 1),
             Self::Uint(v) => Self::Uint(v + 1),
+            Self::Long(v) => Self::Long(v + 1),
+            Self::Ulong(v) => Self::Ulong(v + 1),
         }
     }
 
@@ -402,10 +535,13 @@ impl Number {
 
     pub fn bitwise_not(&self) -> Self {
         match self {
-            Self::Float(v) => Self::Float(f32::from_u32(unsafe { !v.to_int_unchecked::<u32>() }).unwrap_or(0.0)),
-            Self::Number(v) => Self::Number(f64::from_u32(unsafe { !v.to_int_unchecked::<u32>() }).unwrap_or(0.0)),
+            Self::Float(v) => Self::Float(!to_int32(*v as f64) as f32),
+            Self::Number(v) => Self::Number(!to_int32(*v) as f64),
+            Self::BigInt(v) => Self::BigInt(!v),
             Self::Int(v) => Self::Int(!v),
             Self::Uint(v) => Self::Uint(!v),
+            Self::Long(v) => Self::Long(!v),
+            Self::Ulong(v) => Self::Ulong(!v),
         }
     }
     
@@ -413,23 +549,29 @@ impl Number {
         match self {
             Self::Float(v) => {
                 let rhs = rhs.force_float();
-                Self::Float(f32::from_u32(unsafe { v.to_int_unchecked::<u32>() }.checked_shr(unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0)).unwrap_or(0.0))
+                Self::Float(to_uint32(*v as f64).wrapping_shr(to_uint32(rhs as f64)) as f32)
             },
             Self::Number(v) => {
                 let rhs = rhs.force_double();
-                Self::Number(f64::from_u32(unsafe { v.to_int_unchecked::<u32>() }.checked_shr(unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0)).unwrap_or(0.0))
+                Self::Number(to_uint32(*v).wrapping_shr(to_uint32(rhs)) as f64)
             },
+            // BigInt has no unsigned right shift; shift arithmetically.
+            Self::BigInt(v) => Self::BigInt(big_int_shift_left(v, &-rhs.force_big_int())),
             Self::Int(v) => {
-                let rhs = rhs.force_int();
-                let uint1: u32 = (*v).try_into().unwrap_or(0);
-                let uint2: u32 = rhs.try_into().unwrap_or(0);
-                let v = uint1.checked_shr(uint2).unwrap_or(0);
-                let v: i32 = v.try_into().unwrap_or(0);
-                Self::Int(v)
+                let rhs = rhs.force_uint();
+                Self::Int((*v as u32).wrapping_shr(rhs) as i32)
             },
             Self::Uint(v) => {
                 let rhs = rhs.force_uint();
-                Self::Uint(v.checked_shr(rhs).unwrap_or(0))
+                Self::Uint(v.wrapping_shr(rhs))
+            },
+            Self::Long(v) => {
+                let rhs = rhs.force_uint();
+                Self::Long((*v as u64).wrapping_shr(rhs) as i64)
+            },
+            Self::Ulong(v) => {
+                let rhs = rhs.force_uint();
+                Self::Ulong(v.wrapping_shr(rhs))
             },
         }
     }
@@ -438,18 +580,16 @@ impl Number {
         match self {
             Self::Float(v) => {
                 let Self::Float(rhs) = rhs else { panic!(); };
-                (unsafe { v.to_int_unchecked::<u32>() } & unsafe { rhs.to_int_unchecked::<u32>() } != 0)
+                to_uint32(*v as f64) & to_uint32(*rhs as f64) != 0
             },
             Self::Number(v) => {
                 let Self::Number(rhs) = rhs else { panic!(); };
-                (unsafe { v.to_int_unchecked::<u32>() } & unsafe { rhs.to_int_unchecked::<u32>() } != 0)
+                to_uint32(*v) & to_uint32(*rhs) != 0
             },
-            /*
             Self::BigInt(v) => {
                 let Self::BigInt(ref rhs) = rhs else { panic!(); };
-                !(v.clone() & rhs.clone()).is_zero()
+                !(v & rhs).is_zero()
             },
-            */
             Self::Int(v) => {
                 let Self::Int(rhs) = rhs else { panic!(); };
                 v & rhs != 0
@@ -458,6 +598,14 @@ impl Number {
                 let Self::Uint(rhs) = rhs else { panic!(); };
                 v & rhs != 0
             },
+            Self::Long(v) => {
+                let Self::Long(rhs) = rhs else { panic!(); };
+                v & rhs != 0
+            },
+            Self::Ulong(v) => {
+                let Self::Ulong(rhs) = rhs else { panic!(); };
+                v & rhs != 0
+            },
         }
     }
 
@@ -465,30 +613,36 @@ impl Number {
         // Based on https://stackoverflow.com/a/600306
         match self {
             Self::Float(v) => {
-                let v = unsafe { v.to_int_unchecked::<u32>() };
+                let v = to_uint32(*v as f64);
                 (v != 0) && ((v & (v - 1)) == 0)
             },
             Self::Number(v) => {
-                let v = unsafe { v.to_int_unchecked::<u32>() };
+                let v = to_uint32(*v);
                 (v != 0) && ((v & (v - 1)) == 0)
             },
-            /*
             Self::BigInt(v) => {
-                !v.is_zero() && ((v & (v - BigInt::one())).is_zero())
+                v.is_positive() && ((v & (v - BigInt::one())).is_zero())
             },
-            */
             Self::Int(v) => (v != &0) && ((v & (v - 1)) == 0),
             Self::Uint(v) => (v != &0) && ((v & (v - 1)) == 0),
+            Self::Long(v) => (v > &0) && ((v & (v - 1)) == 0),
+            Self::Ulong(v) => (v != &0) && ((v & (v - 1)) == 0),
         }
     }
 
     pub fn convert_type(&self, target_type: &Entity, host: &Database) -> Result<Self, DeferError> {
+        Ok(self.try_convert_type(target_type, host)?.expect("Type is not numeric."))
+    }
+
+    /// Converts to a numeric type, returning `None` if the
+    /// target type is not numeric.
+    pub fn try_convert_type(&self, target_type: &Entity, host: &Database) -> Result<Option<Self>, DeferError> {
         let number_type = host.number_type().defer()?;
         let float_type = host.float_type().defer()?;
         let int_type = host.int_type().defer()?;
-        let uint_type = host.int_type().defer()?;
+        let uint_type = host.uint_type().defer()?;
 
-        Ok(if target_type == &number_type {
+        Ok(Some(if target_type == &number_type {
             Self::Number(self.force_double())
         } else if target_type == &float_type {
             Self::Float(self.force_float())
@@ -496,9 +650,15 @@ impl Number {
             Self::Int(self.force_int())
         } else if target_type == &uint_type {
             Self::Uint(self.force_uint())
+        } else if target_type == &host.long_type() {
+            Self::Long(self.force_long())
+        } else if target_type == &host.ulong_type() {
+            Self::Ulong(self.force_ulong())
+        } else if target_type == &host.big_int_type() {
+            Self::BigInt(self.force_big_int())
         } else {
-            panic!()
-        })
+            return Ok(None);
+        }))
     }
 
     pub fn is_nan(&self) -> bool {
@@ -541,18 +701,27 @@ impl Number {
         if let Number::Uint(v) = self { Some(*v) } else { None }
     }
 
+    pub fn as_long(&self) -> Option<i64> {
+        if let Number::Long(v) = self { Some(*v) } else { None }
+    }
+
+    pub fn as_ulong(&self) -> Option<u64> {
+        if let Number::Ulong(v) = self { Some(*v) } else { None }
+    }
+
+    pub fn as_big_int(&self) -> Option<BigInt> {
+        if let Number::BigInt(v) = self { Some(v.clone()) } else { None }
+    }
+
     pub fn force_double(&self) -> f64 {
         match self {
             Self::Number(v) => *v,
             Self::Float(v) => *v as f64,
-            Self::Int(v) => {
-                let v: Result<i32, _> = (*v).try_into();
-                v.map(|v| v as f64).unwrap_or(f64::NAN)
-            },
-            Self::Uint(v) => {
-                let v: Result<u32, _> = (*v).try_into();
-                v.map(|v| v as f64).unwrap_or(f64::NAN)
-            },
+            Self::Int(v) => *v as f64,
+            Self::Uint(v) => *v as f64,
+            Self::Long(v) => *v as f64,
+            Self::Ulong(v) => *v as f64,
+            Self::BigInt(v) => v.to_f64().unwrap_or(f64::NAN),
         }
     }
 
@@ -560,45 +729,339 @@ impl Number {
         match self {
             Self::Float(v) => *v,
             Self::Number(v) => *v as f32,
-            Self::Int(v) => {
-                let v: Result<i32, _> = (*v).try_into();
-                v.map(|v| v as f32).unwrap_or(f32::NAN)
-            },
-            Self::Uint(v) => {
-                let v: Result<u32, _> = (*v).try_into();
-                v.map(|v| v as f32).unwrap_or(f32::NAN)
-            },
+            Self::Int(v) => *v as f32,
+            Self::Uint(v) => *v as f32,
+            Self::Long(v) => *v as f32,
+            Self::Ulong(v) => *v as f32,
+            Self::BigInt(v) => v.to_f32().unwrap_or(f32::NAN),
         }
     }
 
+    /// Converts to `int` using the ECMA-262 `ToInt32` semantics:
+    /// `NaN` and infinities convert to zero and other values are
+    /// truncated and wrapped modulo 2<sup>32</sup>.
     pub fn force_int(&self) -> i32 {
         match self {
-            Self::Float(v) =>
-                if v.is_infinite() {
-                    if v.is_sign_negative() { i32::MIN } else { i32::MAX }
-                } else if v.is_nan() { 0 } else { unsafe { v.to_int_unchecked() } },
-            Self::Number(v) =>
-                if v.is_infinite() {
-                    if v.is_sign_negative() { i32::MIN } else { i32::MAX }
-                } else if v.is_nan() { 0 } else { unsafe { v.to_int_unchecked() } },
-            Self::Int(v) => (*v).try_into().unwrap_or(0),
-            Self::Uint(v) => (*v).try_into().unwrap_or(0),
+            Self::Float(v) => to_int32(*v as f64),
+            Self::Number(v) => to_int32(*v),
+            Self::BigInt(v) => big_int_to_uint32(v) as i32,
+            Self::Int(v) => *v,
+            Self::Uint(v) => *v as i32,
+            Self::Long(v) => *v as i32,
+            Self::Ulong(v) => *v as i32,
         }
     }
 
+    /// Converts to `uint` using the ECMA-262 `ToUint32` semantics:
+    /// `NaN` and infinities convert to zero and other values are
+    /// truncated and wrapped modulo 2<sup>32</sup>.
     pub fn force_uint(&self) -> u32 {
         match self {
-            Self::Float(v) =>
-                if v.is_infinite() {
-                    if v.is_sign_negative() { u32::MIN } else { u32::MAX }
-                } else if v.is_nan() { 0 } else { unsafe { v.to_int_unchecked() } },
-            Self::Number(v) =>
-                if v.is_infinite() {
-                    if v.is_sign_negative() { u32::MIN } else { u32::MAX }
-                } else if v.is_nan() { 0 } else { unsafe { v.to_int_unchecked() } },
-            // Self::BigInt(v) => Self::Int(v.try_into().unwrap_or(0)),
-            Self::Int(v) => (*v).try_into().unwrap_or(0),
-            Self::Uint(v) => (*v).try_into().unwrap_or(0),
-        }
-    }
-}
\ No newline at end of file
+            Self::Float(v) => to_uint32(*v as f64),
+            Self::Number(v) => to_uint32(*v),
+            Self::BigInt(v) => big_int_to_uint32(v),
+            Self::Int(v) => *v as u32,
+            Self::Uint(v) => *v,
+            Self::Long(v) => *v as u32,
+            Self::Ulong(v) => *v as u32,
+        }
+    }
+
+    /// Converts to `BigInt`, truncating the fractional part of
+    /// floating point values. `NaN` and infinities convert to zero.
+    pub fn force_big_int(&self) -> BigInt {
+        match self {
+            Self::BigInt(v) => v.clone(),
+            Self::Int(v) => BigInt::from(*v),
+            Self::Uint(v) => BigInt::from(*v),
+            Self::Long(v) => BigInt::from(*v),
+            Self::Ulong(v) => BigInt::from(*v),
+            Self::Number(v) => BigInt::from_f64(*v).unwrap_or_default(),
+            Self::Float(v) => BigInt::from_f32(*v).unwrap_or_default(),
+        }
+    }
+
+    /// Converts to `long`, truncating the fractional part of floating point
+    /// values and wrapping modulo 2<sup>64</sup>. `NaN` and infinities
+    /// convert to zero.
+    pub fn force_long(&self) -> i64 {
+        self.force_ulong() as i64
+    }
+
+    /// Converts to `ulong`, truncating the fractional part of floating point
+    /// values and wrapping modulo 2<sup>64</sup>. `NaN` and infinities
+    /// convert to zero.
+    pub fn force_ulong(&self) -> u64 {
+        match self {
+            Self::Float(v) => to_uint64(*v as f64),
+            Self::Number(v) => to_uint64(*v),
+            Self::BigInt(v) => (v & BigInt::from(u64::MAX)).to_u64().unwrap_or(0),
+            Self::Int(v) => *v as u64,
+            Self::Uint(v) => *v as u64,
+            Self::Long(v) => *v as u64,
+            Self::Ulong(v) => *v,
+        }
+    }
+
+    /// Converts a string to `Number` as ECMA-262 `ToNumber` does.
+    /// Strings that are not numeric literals convert to `NaN`.
+    pub fn from_string(s: &str) -> Self {
+        Self::Number(string_to_number(s))
+    }
+}
+
+/// Shifts a `BigInt` left, or right if the shift count is negative.
+fn big_int_shift_left(v: &BigInt, count: &BigInt) -> BigInt {
+    let Some(count) = count.to_i64() else {
+        return if count.is_negative() && v.is_negative() { -BigInt::one() } else { BigInt::zero() };
+    };
+    if count >= 0 {
+        v << (count as u64)
+    } else {
+        v >> count.unsigned_abs()
+    }
+}
+
+/// The low 32 bits of a `BigInt` in two's complement.
+fn big_int_to_uint32(v: &BigInt) -> u32 {
+    (v & BigInt::from(u32::MAX)).to_u32().unwrap_or(0)
+}
+
+/// ECMA-262 `ToUint32`.
+fn to_uint32(v: f64) -> u32 {
+    if !v.is_finite() {
+        return 0;
+    }
+    v.trunc().rem_euclid(4_294_967_296.0) as u32
+}
+
+/// Truncates and wraps modulo 2<sup>64</sup>, as `ToUint32` does
+/// modulo 2<sup>32</sup>.
+fn to_uint64(v: f64) -> u64 {
+    if !v.is_finite() {
+        return 0;
+    }
+    // The remainder of the magnitude is exact, whereas adding 2^64
+    // to a negative remainder would round.
+    let m = (v.abs().trunc() % 18_446_744_073_709_551_616.0) as u64;
+    if v < 0.0 { m.wrapping_neg() } else { m }
+}
+
+/// ECMA-262 `ToInt32`.
+fn to_int32(v: f64) -> i32 {
+    to_uint32(v) as i32
+}
+
+/// ECMA-262 `ToNumber` applied to a string: a `StringNumericLiteral`
+/// surrounded by white space and line terminators.
+fn string_to_number(s: &str) -> f64 {
+    let s = s.trim_matches(|ch: char| (ch.is_whitespace() && ch != '\u{85}') || ch == '\u{FEFF}');
+    if s.is_empty() {
+        return 0.0;
+    }
+    if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        if hex.is_empty() || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            return f64::NAN;
+        }
+        return BigInt::parse_bytes(hex.as_bytes(), 16).and_then(|v| v.to_f64()).unwrap_or(f64::NAN);
+    }
+    let (negative, unsigned) = match s.as_bytes()[0] {
+        b'+' => (false, &s[1..]),
+        b'-' => (true, &s[1..]),
+        _ => (false, s),
+    };
+    let v = if unsigned == "Infinity" {
+        f64::INFINITY
+    } else if is_unsigned_decimal_literal(unsigned) {
+        unsigned.parse::<f64>().unwrap_or(f64::NAN)
+    } else {
+        return f64::NAN;
+    };
+    if negative { -v } else { v }
+}
+
+/// Whether a string is a `StrUnsignedDecimalLiteral` other than `Infinity`.
+fn is_unsigned_decimal_literal(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    let digits = |i: usize| bytes[i..].iter().take_while(|b| b.is_ascii_digit()).count();
+    let mut i = digits(0);
+    let mut mantissa_digits = i;
+    if bytes.get(i) == Some(&b'.') {
+        let fraction = digits(i + 1);
+        mantissa_digits += fraction;
+        i += 1 + fraction;
+    }
+    if mantissa_digits == 0 {
+        return false;
+    }
+    if matches!(bytes.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(bytes.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        let exponent = digits(i);
+        if exponent == 0 {
+            return false;
+        }
+        i += exponent;
+    }
+    i == bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWO_31: f64 = 2_147_483_648.0;
+    const TWO_32: f64 = 4_294_967_296.0;
+
+    /// Reference values as computed by ECMAScript engines:
+    /// (input, ToInt32(input), ToUint32(input)).
+    const REFERENCE: &[(f64, i32, u32)] = &[
+        (0.0, 0, 0),
+        (-0.0, 0, 0),
+        (f64::NAN, 0, 0),
+        (f64::INFINITY, 0, 0),
+        (f64::NEG_INFINITY, 0, 0),
+        (1.0, 1, 1),
+        (-1.0, -1, 4_294_967_295),
+        (0.5, 0, 0),
+        (-0.5, 0, 0),
+        (1.9, 1, 1),
+        (-1.9, -1, 4_294_967_295),
+        (2_147_483_647.0, 2_147_483_647, 2_147_483_647),
+        (TWO_31, -2_147_483_648, 2_147_483_648),
+        (TWO_31 + 1.0, -2_147_483_647, 2_147_483_649),
+        (-TWO_31, -2_147_483_648, 2_147_483_648),
+        (-TWO_31 - 1.0, 2_147_483_647, 2_147_483_647),
+        (TWO_32 - 1.0, -1, 4_294_967_295),
+        (TWO_32, 0, 0),
+        (TWO_32 + 1.0, 1, 1),
+        (-TWO_32, 0, 0),
+        (-TWO_32 - 1.0, -1, 4_294_967_295),
+        (TWO_32 * 2.0 + 5.5, 5, 5),
+        (1e10, 1_410_065_408, 1_410_065_408),
+        (-1e10, -1_410_065_408, 2_884_901_888),
+        (1e20, 1_661_992_960, 1_661_992_960),
+        (9_007_199_254_740_991.0, -1, 4_294_967_295),
+        (-9_007_199_254_740_991.0, 1, 1),
+        (1.7976931348623157e308, 0, 0),
+        (5e-324, 0, 0),
+    ];
+
+    #[test]
+    fn number_to_int32_and_uint32() {
+        for &(input, int, uint) in REFERENCE {
+            assert_eq!(Number::Number(input).force_int(), int, "ToInt32({input})");
+            assert_eq!(Number::Number(input).force_uint(), uint, "ToUint32({input})");
+        }
+    }
+
+    #[test]
+    fn float_to_int32_and_uint32() {
+        for &(input, int, uint) in REFERENCE {
+            // Only inputs exactly representable as `float`
+            let f = input as f32;
+            if !(f as f64 == input || input.is_nan()) {
+                continue;
+            }
+            assert_eq!(Number::Float(f).force_int(), int, "ToInt32({input}f)");
+            assert_eq!(Number::Float(f).force_uint(), uint, "ToUint32({input}f)");
+        }
+    }
+
+    #[test]
+    fn integer_wrap_around() {
+        assert_eq!(Number::Int(-1).force_uint(), u32::MAX);
+        assert_eq!(Number::Int(i32::MIN).force_uint(), 2_147_483_648);
+        assert_eq!(Number::Uint(u32::MAX).force_int(), -1);
+        assert_eq!(Number::Uint(2_147_483_648).force_int(), i32::MIN);
+        assert_eq!(Number::Uint(7).force_int(), 7);
+        assert_eq!(Number::Int(7).force_uint(), 7);
+        assert_eq!(Number::Int(-1).force_double(), -1.0);
+        assert_eq!(Number::Uint(u32::MAX).force_double(), 4_294_967_295.0);
+    }
+
+    #[test]
+    fn bitwise_operators_use_int32() {
+        assert_eq!((Number::Number(-1.0) & Number::Number(-1.0)).force_double(), -1.0);
+        assert_eq!((Number::Number(TWO_32 + 1.0) | Number::Number(0.0)).force_double(), 1.0);
+        assert_eq!((Number::Number(f64::NAN) ^ Number::Number(3.0)).force_double(), 3.0);
+        assert_eq!(Number::Number(TWO_31).bitwise_not().force_double(), 2_147_483_647.0);
+        assert_eq!((Number::Number(1.0) << Number::Number(31.0)).force_double(), -TWO_31);
+        assert_eq!((Number::Number(1.0) << Number::Number(33.0)).force_double(), 2.0);
+        assert_eq!((Number::Number(-8.0) >> Number::Number(1.0)).force_double(), -4.0);
+        assert_eq!(Number::Number(-1.0).shift_right_unsigned(&Number::Number(28.0)).force_double(), 15.0);
+        assert_eq!(Number::Int(-1).shift_right_unsigned(&Number::Int(28)).force_int(), 15);
+        assert_eq!((Number::Int(1) << Number::Int(32)).force_int(), 1);
+        assert_eq!((Number::Int(-16) >> Number::Int(2)).force_int(), -4);
+        assert_eq!((Number::Uint(1) << Number::Uint(35)).force_uint(), 8);
+    }
+
+    #[test]
+    fn big_int_conversions_and_operators() {
+        let v = Number::BigInt(BigInt::from(4_294_967_297u64));
+        assert_eq!(v.force_int(), 1);
+        assert_eq!(v.force_uint(), 1);
+        assert_eq!(Number::BigInt(BigInt::from(-1)).force_uint(), u32::MAX);
+        assert_eq!(Number::BigInt(BigInt::from(-1)).force_int(), -1);
+        assert_eq!(Number::Number(-2.5).force_big_int(), BigInt::from(-2));
+        assert_eq!(Number::Number(f64::NAN).force_big_int(), BigInt::from(0));
+        assert_eq!((Number::BigInt(BigInt::from(1)) << Number::Int(40)).force_double(), 1_099_511_627_776.0);
+        assert_eq!((Number::BigInt(BigInt::from(-8)) >> Number::Int(1)).as_big_int(), Some(BigInt::from(-4)));
+        assert_eq!((Number::BigInt(BigInt::from(7)) / Number::BigInt(BigInt::from(0))).as_big_int(), Some(BigInt::from(0)));
+        assert_eq!(Number::BigInt(BigInt::from(-1)).bitwise_not().as_big_int(), Some(BigInt::from(0)));
+    }
+
+    #[test]
+    fn long_and_ulong_conversions_and_operators() {
+        assert_eq!(Number::Int(-1).force_long(), -1);
+        assert_eq!(Number::Int(-1).force_ulong(), u64::MAX);
+        assert_eq!(Number::Uint(u32::MAX).force_long(), 4_294_967_295);
+        assert_eq!(Number::Long(4_294_967_297).force_int(), 1);
+        assert_eq!(Number::Ulong(u64::MAX).force_uint(), u32::MAX);
+        assert_eq!(Number::Number(-2.5).force_long(), -2);
+        assert_eq!(Number::Number(-1.0).force_ulong(), u64::MAX);
+        assert_eq!(Number::Number(18_446_744_073_709_551_616.0).force_ulong(), 0);
+        assert_eq!(Number::Number(f64::NAN).force_long(), 0);
+        assert_eq!(Number::Number(f64::NEG_INFINITY).force_ulong(), 0);
+        assert_eq!(Number::BigInt(BigInt::from(-1)).force_ulong(), u64::MAX);
+        assert_eq!(Number::BigInt(BigInt::from(u64::MAX) + 2).force_long(), 1);
+        assert_eq!(Number::Long(-1).force_big_int(), BigInt::from(-1));
+
+        assert_eq!((Number::Long(1) << Number::Int(40)).as_long(), Some(1_099_511_627_776));
+        assert_eq!((Number::Long(1) << Number::Int(64)).as_long(), Some(1));
+        assert_eq!((Number::Long(-8) >> Number::Int(1)).as_long(), Some(-4));
+        assert_eq!(Number::Long(-1).shift_right_unsigned(&Number::Int(60)).as_long(), Some(15));
+        assert_eq!((Number::Ulong(u64::MAX) & Number::Int(0xFF)).as_ulong(), Some(0xFF));
+        assert_eq!((Number::Long(7) / Number::Long(2)).as_long(), Some(3));
+        assert_eq!((Number::Long(7) % Number::Long(0)).as_long(), Some(0));
+        assert_eq!(Number::Ulong(0).bitwise_not().as_ulong(), Some(u64::MAX));
+        assert_eq!((-Number::Long(i64::MIN)).as_long(), Some(i64::MIN));
+    }
+
+    #[test]
+    fn string_to_number() {
+        let cases: &[(&str, f64)] = &[
+            ("", 0.0),
+            (" \t\n\u{A0}\u{FEFF}\u{2028} ", 0.0),
+            ("42", 42.0),
+            (" -42 ", -42.0),
+            ("+1.5", 1.5),
+            ("1.", 1.0),
+            (".5", 0.5),
+            ("1.e2", 100.0),
+            ("2E-2", 0.02),
+            ("0x1F", 31.0),
+            ("0X10000000000000000", 18_446_744_073_709_551_616.0),
+            ("Infinity", f64::INFINITY),
+            ("-Infinity", f64::NEG_INFINITY),
+        ];
+        for &(input, expected) in cases {
+            assert_eq!(Number::from_string(input).force_double(), expected, "ToNumber({input:?})");
+        }
+        for input in [".", "e5", "1e", "1e+", "-0x10", "0x", "0xG", "1_000", "inf", "NaN", "infinity", "1 2", "\u{85}1"] {
+            assert!(Number::from_string(input).is_nan(), "ToNumber({input:?})");
+        }
+    }
+}
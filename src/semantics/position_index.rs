@@ -0,0 +1,225 @@
+use crate::ns::*;
+use by_address::ByAddress;
+
+type LocationsByUnit = HashMap<ByAddress<Rc<CompilationUnit>>, Vec<(Location, Entity)>>;
+
+/// Declarations grouped by compilation unit, along with the location of
+/// every declaration at the time they were grouped.
+type GroupedDeclarations = (Vec<Option<Location>>, Rc<LocationsByUnit>);
+
+/// Nodes that may be mapped through `Database::lazy_node_mapping()`.
+/// Implemented for every node type a `NodeAssignment` maps.
+pub trait LocatedNode {
+    fn node_location(&self) -> Location;
+}
+
+macro impl_located_node_with_loc_call {
+    ($($nodetype:ident),*$(,)?) => {
+        $(
+            impl LocatedNode for $nodetype {
+                fn node_location(&self) -> Location {
+                    self.location()
+                }
+            }
+        )*
+    },
+}
+
+macro impl_located_node_with_loc_field {
+    ($($nodetype:ident),*$(,)?) => {
+        $(
+            impl LocatedNode for $nodetype {
+                fn node_location(&self) -> Location {
+                    self.location.clone()
+                }
+            }
+        )*
+    },
+}
+
+impl_located_node_with_loc_call!(
+    Expression,
+    InitializerField,
+    Directive,
+    MxmlContent,
+    CssDirective,
+    CssMediaQueryCondition,
+    CssSelectorCondition,
+    CssPropertyValue,
+    CssSelector,
+);
+
+impl_located_node_with_loc_field!(
+    FunctionCommon,
+    Block,
+    Program,
+    PackageDefinition,
+    SimpleVariableDefinition,
+    Metadata,
+    MetadataEntry,
+    Mxml,
+    MxmlElement,
+    MxmlAttribute,
+    CssProperty,
+    CssRule,
+    CssDocument,
+    QualifiedIdentifier,
+);
+
+/// Index of entities by source position, used for hover and go-to-definition.
+///
+/// The index covers entities created by the factory that have a declaration
+/// location (type definitions, slots, aliases and imports), whose locations are
+/// read whenever the index is queried, and nodes mapped through
+/// `Database::lazy_node_mapping()`.
+pub struct PositionIndex {
+    declarations: RefCell<Vec<Entity>>,
+    declarations_by_unit: RefCell<Option<GroupedDeclarations>>,
+    nodes: RefCell<LocationsByUnit>,
+}
+
+impl PositionIndex {
+    pub(crate) fn new() -> Self {
+        Self {
+            declarations: RefCell::new(vec![]),
+            declarations_by_unit: RefCell::new(None),
+            nodes: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn add_declaration(&self, entity: &Entity) {
+        self.declarations.borrow_mut().push(entity.clone());
+    }
+
+    pub(crate) fn add_node(&self, location: Location, entity: &Entity) {
+        self.nodes.borrow_mut().entry(ByAddress(location.compilation_unit())).or_default().push((location, entity.clone()));
+    }
+
+    /// Returns the innermost entity at an offset of a compilation unit.
+    /// Declarations take precedence over mapped nodes with the same location.
+    pub fn entity_at(&self, compilation_unit: &Rc<CompilationUnit>, offset: usize) -> Option<Entity> {
+        self.entities_at(compilation_unit, offset).into_iter().next()
+    }
+
+    /// Returns the innermost entity at an offset of the compilation unit
+    /// with a given file path.
+    pub fn entity_at_file(&self, file_path: &str, offset: usize) -> Option<Entity> {
+        let compilation_unit = self.compilation_units().into_iter().find(|cu| cu.file_path().as_deref() == Some(file_path))?;
+        self.entity_at(&compilation_unit, offset)
+    }
+
+    /// Returns the entities whose location includes an offset of a compilation unit,
+    /// from innermost to outermost.
+    pub fn entities_at(&self, compilation_unit: &Rc<CompilationUnit>, offset: usize) -> Vec<Entity> {
+        let key = ByAddress(compilation_unit.clone());
+        let includes = |loc: &Location| loc.first_offset() <= offset && offset <= loc.last_offset();
+        let mut r: Vec<(usize, Entity)> = vec![];
+        if let Some(list) = self.grouped_declarations().get(&key) {
+            r.extend(list.iter().filter(|(loc, _)| includes(loc)).map(|(loc, e)| (loc.last_offset() - loc.first_offset(), e.clone())));
+        }
+        if let Some(list) = self.nodes.borrow().get(&key) {
+            r.extend(list.iter().filter(|(loc, _)| includes(loc)).map(|(loc, e)| (loc.last_offset() - loc.first_offset(), e.clone())));
+        }
+        // Stable sort keeps declarations before nodes of the same length.
+        r.sort_by_key(|(len, _)| *len);
+        let mut entities: Vec<Entity> = vec![];
+        for (_, e) in r {
+            if !entities.contains(&e) {
+                entities.push(e);
+            }
+        }
+        entities
+    }
+
+    /// Returns the declarations located in a compilation unit, in source order.
+    pub fn declarations_in(&self, compilation_unit: &Rc<CompilationUnit>) -> Vec<Entity> {
+        self.grouped_declarations().get(&ByAddress(compilation_unit.clone()))
+            .map(|list| list.iter().map(|(_, e)| e.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the location where an entity is declared. References, type
+    /// and namespace constants and entities after substitution resolve
+    /// to the entity they refer to.
+    pub fn declaration_location(&self, entity: &Entity) -> Option<Location> {
+        Self::declaration_of(entity).try_location().ok().flatten()
+    }
+
+    /// Resolves a referring entity to the declaration it refers to.
+    pub fn declaration_of(entity: &Entity) -> Entity {
+        if entity.is::<FixtureReferenceValue>() {
+            Self::declaration_of(&entity.property())
+        } else if entity.is::<TypeConstant>() {
+            Self::declaration_of(&entity.referenced_type())
+        } else if entity.is::<NamespaceConstant>() {
+            entity.referenced_ns()
+        } else if entity.is::<TypeAfterSubstitution>() || entity.is::<VariableSlotAfterSubstitution>()
+            || entity.is::<VirtualSlotAfterSubstitution>() || entity.is::<MethodSlotAfterSubstitution>() {
+            Self::declaration_of(&entity.origin())
+        } else {
+            entity.clone()
+        }
+    }
+
+    fn compilation_units(&self) -> Vec<Rc<CompilationUnit>> {
+        let mut r: Vec<Rc<CompilationUnit>> = self.grouped_declarations().keys().map(|cu| cu.0.clone()).collect();
+        for cu in self.nodes.borrow().keys() {
+            if !r.iter().any(|cu1| Rc::ptr_eq(cu1, &cu.0)) {
+                r.push(cu.0.clone());
+            }
+        }
+        r
+    }
+
+    // Compilation units are keyed by address.
+    #[allow(clippy::mutable_key_type)]
+    fn grouped_declarations(&self) -> Rc<LocationsByUnit> {
+        // Declarations are regrouped when one is added or relocated.
+        let locations: Vec<Option<Location>> = self.declarations.borrow().iter()
+            .map(|entity| entity.try_location().ok().flatten())
+            .collect();
+        if let Some((locations_1, r)) = self.declarations_by_unit.borrow().as_ref() {
+            if *locations_1 == locations {
+                return r.clone();
+            }
+        }
+        let mut r = LocationsByUnit::new();
+        for (entity, loc) in self.declarations.borrow().iter().zip(locations.iter()) {
+            if let Some(loc) = loc {
+                r.entry(ByAddress(loc.compilation_unit())).or_default().push((loc.clone(), entity.clone()));
+            }
+        }
+        for list in r.values_mut() {
+            list.sort_by_key(|(loc, _)| loc.first_offset());
+        }
+        let r = Rc::new(r);
+        self.declarations_by_unit.replace(Some((locations, r.clone())));
+        r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn regroups_relocated_declarations() {
+        let db = Database::new(DatabaseOptions::default());
+        let cu = CompilationUnit::new(None, "var x; var y;".into());
+        let name = db.factory().create_qname(&db.top_level_package().public_ns().unwrap(), "x".into());
+        let x = db.factory().create_variable_slot(&name, false, &db.any_type());
+        x.set_location(Some(Location::with_offsets(&cu, 4, 5)));
+        assert_eq!(db.position_index().entity_at(&cu, 4), Some(x.clone()));
+        assert_eq!(db.position_index().entity_at(&cu, 11), None);
+
+        x.set_location(Some(Location::with_offsets(&cu, 11, 12)));
+        assert_eq!(db.position_index().entity_at(&cu, 4), None);
+        assert_eq!(db.position_index().entity_at(&cu, 11), Some(x.clone()));
+
+        // Indexes of other databases are independent
+        let db_2 = Database::new(DatabaseOptions::default());
+        assert_eq!(db_2.position_index().entity_at(&cu, 11), None);
+        assert_eq!(db_2.position_index().declarations_in(&cu), vec![]);
+        assert_eq!(db.position_index().declarations_in(&cu), vec![x]);
+    }
+}
@@ -1,6 +1,9 @@
 use crate::ns::*;
 
 /// Methods for looking up a property in an object, package or scope.
+///
+/// Lookups record references to the definitions they resolve to only
+/// inside a reference context; see `References`.
 pub struct PropertyLookup<'a>(pub &'a Database);
 
 #[derive(Clone)]
@@ -60,8 +63,16 @@ fn map_defer_error<T>(result: Result<T, DeferError>) -> Result<T, PropertyLookup
     result.map_err(|_| PropertyLookupError::Defer)
 }
 
+fn map_access_error<T>(result: Result<T, EntityAccessError>) -> Result<T, PropertyLookupError> {
+    result.map_err(PropertyLookupError::EntityAccess)
+}
+
 impl<'a> PropertyLookup<'a> {
     pub fn lookup_in_object(&self, base: &Entity, open_ns_set: &SharedArray<Entity>, qual: Option<Entity>, key: &PropertyLookupKey, calling: bool) -> Result<Option<Entity>, PropertyLookupError> {
+        References(self.0).discard_on_error(|| self.lookup_in_object_1(base, open_ns_set, qual, key, calling))
+    }
+
+    fn lookup_in_object_1(&self, base: &Entity, open_ns_set: &SharedArray<Entity>, qual: Option<Entity>, key: &PropertyLookupKey, calling: bool) -> Result<Option<Entity>, PropertyLookupError> {
         if base.is::<InvalidationEntity>() {
             return Ok(Some(base.clone()));
         }
@@ -88,6 +99,7 @@ impl<'a> PropertyLookup<'a> {
                 let r = self.get_qname_in_ns_set_or_any_public_ns(&class.properties(self.0), open_ns_set, qual.clone(), &local_name)?;
                 if let Some(r) = r {
                     Unused(self.0).mark_used(&r);
+                    References(self.0).record(&r);
 
                     let r = r.resolve_alias();
 
@@ -120,7 +132,18 @@ impl<'a> PropertyLookup<'a> {
         // For a value
         if base.is::<Value>() {
             let base_type = defer(&base.static_type(self.0))?;
-            let base_esc_type = base_type.escape_of_non_nullable();
+            let mut base_esc_type = base_type.escape_of_non_nullable();
+
+            // If base data type is a type parameter, resolve
+            // members through its constraint.
+            let mut visited_type_params: Vec<Entity> = vec![];
+            while base_esc_type.is::<TypeParameterType>() && !visited_type_params.contains(&base_esc_type) {
+                let Some(constraint) = base_esc_type.constraint() else {
+                    break;
+                };
+                visited_type_params.push(base_esc_type.clone());
+                base_esc_type = defer(&constraint)?.escape_of_nullable_or_non_nullable();
+            }
 
             if base_esc_type.is::<InvalidationEntity>() {
                 return Ok(Some(base_esc_type.clone()));
@@ -230,11 +253,12 @@ impl<'a> PropertyLookup<'a> {
 
                     if let Some(prop) = prop {
                         Unused(self.0).mark_used(&prop);
+                        References(self.0).record(&prop);
 
                         let prop = prop.resolve_alias();
 
                         // Throw if unresolved
-                        defer(&prop.property_static_type(self.0))?;
+                        defer(&map_access_error(prop.try_property_static_type(self.0))?)?;
 
                         if prop.is_namespace_or_ns_constant() {
                             return Ok(Some(map_defer_error(self.0.factory().create_namespace_constant(&prop))?));
@@ -252,9 +276,10 @@ impl<'a> PropertyLookup<'a> {
 
                     if let Some(prop) = prop {
                         Unused(self.0).mark_used(&prop);
+                        References(self.0).record(&prop);
 
                         // Defer if unresolved
-                        defer(&prop.property_static_type(self.0))?;
+                        defer(&map_access_error(prop.try_property_static_type(self.0))?)?;
 
                         return Ok(Some(map_defer_error(self.0.factory().create_instance_reference_value(&base, &prop))?));
                     }
@@ -289,15 +314,18 @@ impl<'a> PropertyLookup<'a> {
 
             if let Some(prop) = prop {
                 Unused(self.0).mark_used(&prop);
+                References(self.0).record(&prop);
 
                 let prop = prop.resolve_alias();
 
-                defer(&prop.property_static_type(self.0))?;
+                defer(&map_access_error(prop.try_property_static_type(self.0))?)?;
 
                 r = Some(map_defer_error(prop.wrap_property_reference(self.0))?);
             // Detect Vector from __AS3__.vec.Vector
             } else if base == &self.0.top_level_package && local_name == "Vector" && qual.as_ref().map(|q| q.is_public_ns()).unwrap_or(true) {
-                r = Some(defer(&self.0.vector_type())?);
+                let vector_type = defer(&self.0.vector_type())?;
+                References(self.0).record(&vector_type);
+                r = Some(vector_type);
             }
 
             for concatp in base.package_concats().iter() {
@@ -317,6 +345,12 @@ impl<'a> PropertyLookup<'a> {
     }
 
     pub fn lookup_in_scope_chain(&self, scope: &Entity, qual: Option<Entity>, key: &PropertyLookupKey) -> Result<Option<Entity>, PropertyLookupError> {
+        References(self.0).with_lookup_scope(scope, || {
+            References(self.0).discard_on_error(|| self.lookup_in_scope_chain_1(scope, qual, key))
+        })
+    }
+
+    fn lookup_in_scope_chain_1(&self, scope: &Entity, qual: Option<Entity>, key: &PropertyLookupKey) -> Result<Option<Entity>, PropertyLookupError> {
         let open_ns_set = scope.concat_open_ns_set_of_scope_chain();
 
         // If the key is computed, always return dynamic
@@ -375,10 +409,11 @@ impl<'a> PropertyLookup<'a> {
 
         if let Some(r1) = r.as_ref() {
             Unused(self.0).mark_used(&r1);
+            References(self.0).record(r1);
 
             let r1 = r1.resolve_alias();
 
-            defer(&r1.property_static_type(self.0))?;
+            defer(&map_access_error(r1.try_property_static_type(self.0))?)?;
 
             r = Some(map_defer_error(r1.wrap_property_reference(self.0))?);
         }
@@ -438,21 +473,22 @@ impl<'a> PropertyLookup<'a> {
                             r = Some(amb);
                         }
                     } else {
-                        assert!(import.is::<PackagePropertyImport>());
-                        let prop = map_defer_error(import.property().defer())?;
+                        let prop = map_defer_error(map_access_error(import.try_property())?.defer())?;
                         if prop.is::<InvalidationEntity>() {
                             continue;
                         }
-                        if prop.name().matches_in_ns_set_or_any_public_ns(self.0, &open_ns_set, &local_name) {
+                        if map_access_error(prop.try_name())?.matches_in_ns_set_or_any_public_ns(self.0, &open_ns_set, &local_name) {
                             Unused(self.0).mark_used(&import);
 
                             if r.is_some() && !r.as_ref().unwrap().fixture_reference_value_equals(&prop) {
                                 return Err(PropertyLookupError::AmbiguousReference(local_name));
                             }
 
+                            References(self.0).record(&prop);
+
                             let prop = prop.resolve_alias();
 
-                            defer(&prop.property_static_type(self.0))?;
+                            defer(&map_access_error(prop.try_property_static_type(self.0))?)?;
 
                             r = Some(map_defer_error(prop.wrap_property_reference(self.0))?);
                         }
@@ -496,6 +532,10 @@ impl<'a> PropertyLookup<'a> {
     }
 
     pub fn lookup_in_package_recursive(&self, package: &Entity, open_ns_set: &SharedArray<Entity>, qual: Option<Entity>, local_name: &PropertyLookupKey) -> Result<Option<Entity>, PropertyLookupError> {
+        References(self.0).discard_on_error(|| self.lookup_in_package_recursive_1(package, open_ns_set, qual, local_name))
+    }
+
+    fn lookup_in_package_recursive_1(&self, package: &Entity, open_ns_set: &SharedArray<Entity>, qual: Option<Entity>, local_name: &PropertyLookupKey) -> Result<Option<Entity>, PropertyLookupError> {
         let mut r = self.lookup_in_object(&package, &open_ns_set, qual.clone(), local_name, false)?;
 
         for (_, subpackage) in package.subpackages().borrow().iter() {
@@ -0,0 +1,212 @@
+use crate::ns::*;
+
+/// The way a definition is referenced.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ReferenceKind {
+    Read,
+    Write,
+    Call,
+    TypeAnnotation,
+    Import,
+}
+
+/// A place where a definition is referenced.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Reference {
+    pub location: Location,
+    pub kind: ReferenceKind,
+    /// The scope from which the reference is resolved, if it is
+    /// resolved through the scope chain. References recorded by
+    /// `PropertyLookup::lookup_in_scope_chain()` always have a scope.
+    pub scope: Option<Entity>,
+}
+
+/// Index of references to definitions, used for find-all-references and rename.
+///
+/// `PropertyLookup` records a reference to the definition a lookup resolves to
+/// while a reference context is active (see `References::with_context()`
+/// and `References::with_scope_context()`). A lookup that fails, as with
+/// an ambiguous reference, records nothing.
+///
+/// The crate does not set reference contexts by itself: the verifier of the
+/// host wraps each lookup made for a name in the source, such as an identifier
+/// or a member expression, in a reference context at the location of that name.
+/// Lookups made outside a reference context, such as those made by the
+/// semantic queries of this crate, are not recorded.
+/// Imports created with a location record an `Import` reference to the imported
+/// property or package.
+///
+/// Entities after substitution are recorded as their `origin()`, so that
+/// references through `Vector.<T>` members, for example, are found by querying
+/// the original definition.
+pub struct References<'a>(pub &'a Database);
+
+impl<'a> References<'a> {
+    /// Runs a function, such as a property lookup, recording references
+    /// to the definitions resolved by it at a location and of a kind.
+    pub fn with_context<T>(&self, location: &Location, kind: ReferenceKind, f: impl FnOnce() -> T) -> T {
+        self.with_reference_context(Reference { location: location.clone(), kind, scope: None }, f)
+    }
+
+    /// Runs a function such as `PropertyLookup::lookup_in_scope_chain()`,
+    /// recording references to the definitions resolved by it at a location
+    /// and of a kind, from a scope.
+    pub fn with_scope_context<T>(&self, scope: &Entity, location: &Location, kind: ReferenceKind, f: impl FnOnce() -> T) -> T {
+        self.with_reference_context(Reference { location: location.clone(), kind, scope: Some(scope.clone()) }, f)
+    }
+
+    /// Runs a lookup through the scope chain from a scope, recording
+    /// the references of a reference context without a scope as
+    /// resolved from that scope.
+    pub(crate) fn with_lookup_scope<T>(&self, scope: &Entity, f: impl FnOnce() -> T) -> T {
+        let context = self.0.reference_context.borrow().last().cloned();
+        match context {
+            Some(context) if context.scope.is_none() => {
+                self.with_reference_context(Reference { scope: Some(scope.clone()), ..context }, f)
+            },
+            _ => f(),
+        }
+    }
+
+    fn with_reference_context<T>(&self, context: Reference, f: impl FnOnce() -> T) -> T {
+        self.0.reference_context.borrow_mut().push(context);
+        let r = f();
+        self.0.reference_context.borrow_mut().pop();
+        r
+    }
+
+    /// Records a reference to a definition.
+    pub fn add(&self, definition: &Entity, location: &Location, kind: ReferenceKind) {
+        self.add_reference(definition, Reference { location: location.clone(), kind, scope: None });
+    }
+
+    fn add_reference(&self, definition: &Entity, reference: Reference) {
+        let definition = Self::origin_of(definition);
+        let mut references = self.0.references.borrow_mut();
+        let list = references.entry(definition).or_default();
+        if !list.iter().any(|r| r.location == reference.location && r.kind == reference.kind) {
+            list.push(reference);
+        }
+    }
+
+    /// Records a reference to a definition at the current reference
+    /// context, if any.
+    pub(crate) fn record(&self, definition: &Entity) {
+        if definition.is::<InvalidationEntity>() {
+            return;
+        }
+        let context = self.0.reference_context.borrow().last().cloned();
+        if let Some(context) = context {
+            if let Some(pending) = self.0.pending_references.borrow_mut().as_mut() {
+                pending.push((definition.clone(), context));
+                return;
+            }
+            self.add_reference(definition, context);
+        }
+    }
+
+    /// Runs a lookup, keeping the references recorded during it only
+    /// if it succeeds. Nested lookups are kept or discarded along with
+    /// the outermost one.
+    pub(crate) fn discard_on_error<T, E>(&self, f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+        if self.0.pending_references.borrow().is_some() {
+            return f();
+        }
+        self.0.pending_references.replace(Some(vec![]));
+        let r = f();
+        let pending = self.0.pending_references.take().unwrap();
+        if r.is_ok() {
+            for (definition, reference) in pending {
+                self.add_reference(&definition, reference);
+            }
+        }
+        r
+    }
+
+    /// Returns all references to a definition, ordered by location.
+    /// If the definition is an entity after substitution, returns
+    /// the references to its origin.
+    pub fn of(&self, definition: &Entity) -> Vec<Reference> {
+        let mut r = self.0.references.borrow().get(&Self::origin_of(definition)).cloned().unwrap_or_default();
+        r.sort_by(|a, b| a.location.cmp(&b.location));
+        r
+    }
+
+    /// Returns the references to a definition of a given kind.
+    pub fn of_kind(&self, definition: &Entity, kind: ReferenceKind) -> Vec<Reference> {
+        self.of(definition).into_iter().filter(|r| r.kind == kind).collect()
+    }
+
+    /// Forgets the references located in a compilation unit, as before
+    /// verifying it again.
+    pub fn clear_compilation_unit(&self, compilation_unit: &Rc<CompilationUnit>) {
+        for list in self.0.references.borrow_mut().values_mut() {
+            list.retain(|r| !Rc::ptr_eq(&r.location.compilation_unit(), compilation_unit));
+        }
+    }
+
+    fn origin_of(entity: &Entity) -> Entity {
+        if entity.is_entity_after_substitution() {
+            Self::origin_of(&entity.origin())
+        } else {
+            entity.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Defines a public `x` variable in a package.
+    fn define_variable(db: &Database, package_name: &str) -> Entity {
+        let package = db.factory().create_package([package_name]);
+        let name = db.factory().create_qname(&package.public_ns().unwrap(), "x".into());
+        let slot = db.factory().create_variable_slot(&name, false, &db.any_type());
+        slot.set_parent(Some(package.clone()));
+        package.properties(db).set(name, slot.clone());
+        slot
+    }
+
+    fn location(cu: &Rc<CompilationUnit>, first_offset: usize) -> Location {
+        Location::with_offsets(cu, first_offset, first_offset + 1)
+    }
+
+    fn lookup_x(db: &Database, scope: &Entity) -> Result<Option<Entity>, PropertyLookupError> {
+        PropertyLookup(db).lookup_in_scope_chain(scope, None, &PropertyLookupKey::LocalName("x".into()))
+    }
+
+    #[test]
+    fn records_resolved_definition_in_context() {
+        let db = Database::new(DatabaseOptions::default());
+        let cu = CompilationUnit::new(None, "x; x".into());
+        let x = define_variable(&db, "p");
+        let scope = db.factory().create_package_scope(&db.factory().create_package(["q"]));
+        scope.import_list().push(db.factory().create_package_property_import(&x, None));
+
+        assert!(lookup_x(&db, &scope).unwrap().is_some());
+        assert!(References(&db).of(&x).is_empty());
+
+        References(&db).with_context(&location(&cu, 3), ReferenceKind::Read, || lookup_x(&db, &scope)).unwrap();
+        let references = References(&db).of(&x);
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].location, location(&cu, 3));
+        assert_eq!(references[0].kind, ReferenceKind::Read);
+    }
+
+    #[test]
+    fn ambiguous_lookup_records_nothing() {
+        let db = Database::new(DatabaseOptions::default());
+        let cu = CompilationUnit::new(None, "x".into());
+        let x1 = define_variable(&db, "p1");
+        let x2 = define_variable(&db, "p2");
+        let scope = db.factory().create_package_scope(&db.factory().create_package(["q"]));
+        scope.import_list().push(db.factory().create_package_property_import(&x1, None));
+        scope.import_list().push(db.factory().create_package_property_import(&x2, None));
+
+        let r = References(&db).with_context(&location(&cu, 0), ReferenceKind::Read, || lookup_x(&db, &scope));
+        assert!(matches!(r, Err(PropertyLookupError::AmbiguousReference(_))));
+        assert!(References(&db).of(&x1).is_empty());
+        assert!(References(&db).of(&x2).is_empty());
+    }
+}
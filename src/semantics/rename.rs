@@ -0,0 +1,449 @@
+use crate::ns::*;
+
+/// A replacement of source text.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SourceEdit {
+    pub location: Location,
+    pub new_text: String,
+}
+
+/// Semantic rename of definitions.
+///
+/// Renaming a method renames its whole override chain; renaming an interface
+/// method renames its implementations in every known implementor, and vice versa.
+/// Renaming a getter or setter renames its virtual slot.
+///
+/// Edits are produced for the declarations and for the references recorded
+/// in `References`, so the results are only complete once the program
+/// has been verified.
+pub struct Rename<'a>(pub &'a Database);
+
+impl<'a> Rename<'a> {
+    /// Checks a rename for conflicts and returns the source edits,
+    /// ordered by location.
+    pub fn rename(&self, definition: &Entity, new_name: &str) -> Result<Vec<SourceEdit>, RenameError> {
+        self.validate_name(new_name)?;
+
+        let definitions = self.definitions_to_rename(definition);
+
+        for definition in definitions.iter() {
+            if definition.try_name().is_err() || self.declaration_locations(definition).is_empty() {
+                return Err(RenameError::NotRenamable { definition: definition.clone() });
+            }
+        }
+        for definition in definitions.iter() {
+            self.check_names_conflict(definition, new_name, &definitions)?;
+            self.check_hierarchy_conflict(definition, new_name, &definitions)?;
+            self.check_scope_conflicts(definition, new_name, &definitions)?;
+        }
+
+        let mut edits: Vec<SourceEdit> = vec![];
+        for definition in definitions.iter() {
+            let old_name = definition.name().local_name();
+            let mut locations = self.declaration_locations(definition);
+            for entity in self.with_accessors(definition) {
+                locations.extend(References(self.0).of(&entity).into_iter().map(|r| r.location));
+            }
+            for location in locations {
+                let edit = SourceEdit { location: Self::name_location(&location, &old_name), new_text: new_name.to_owned() };
+                if !edits.contains(&edit) {
+                    edits.push(edit);
+                }
+            }
+        }
+        edits.sort_by(|a, b| a.location.cmp(&b.location));
+        Ok(edits)
+    }
+
+    /// Returns the definitions renamed along with a definition.
+    pub fn definitions_to_rename(&self, definition: &Entity) -> Vec<Entity> {
+        let mut r: Vec<Entity> = vec![];
+        let mut pending = vec![self.property_of(definition)];
+        while let Some(definition) = pending.pop() {
+            if r.contains(&definition) {
+                continue;
+            }
+            r.push(definition.clone());
+
+            // Override chain
+            for method in self.with_accessors(&definition).into_iter().filter(|m| m.is::<MethodSlot>()) {
+                if let Some(overriden) = method.overrides_method(self.0) {
+                    pending.push(self.property_of(&overriden));
+                }
+                for overrider in method.overriden_by(self.0).iter() {
+                    pending.push(self.property_of(&overrider));
+                }
+            }
+
+            let (Some(parent), Ok(name)) = (definition.parent(), definition.try_name()) else {
+                continue;
+            };
+            if !name.namespace().is_public_ns() {
+                continue;
+            }
+            let local_name = name.local_name();
+
+            // Implementations of an interface member
+            if parent.is::<InterfaceType>() {
+                for implementor in ClassHierarchy(self.0).all_implementors(&parent) {
+                    if let Some(implementation) = self.lookup_in_prototype_chain(&implementor, &local_name) {
+                        pending.push(implementation);
+                    }
+                }
+                for subinterface in parent.known_implementors().iter().filter(|i| i.is::<InterfaceType>()) {
+                    if let Ok(Some(member)) = subinterface.prototype(self.0).get_in_any_public_ns(&local_name) {
+                        pending.push(member);
+                    }
+                }
+            }
+
+            // Interface members implemented by a class member
+            if parent.is::<ClassType>() && !self.is_static_member(&definition, &parent) {
+                for class in parent.descending_class_hierarchy(self.0).collect::<Vec<_>>() {
+                    if !class.is::<ClassType>() {
+                        continue;
+                    }
+                    for itrfc in class.implements(self.0).iter() {
+                        let mut interfaces = itrfc.all_ascending_types(self.0);
+                        interfaces.push(itrfc.clone());
+                        for itrfc in interfaces {
+                            let itrfc = self.original_type(&itrfc);
+                            if let Ok(Some(member)) = itrfc.prototype(self.0).get_in_any_public_ns(&local_name) {
+                                pending.push(member);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        r
+    }
+
+    fn validate_name(&self, new_name: &str) -> Result<(), RenameError> {
+        let cu = CompilationUnit::new(None, new_name.to_owned());
+        let id = ParserFacade(&cu, ParserOptions::default()).parse_qualified_identifier();
+        if cu.invalidated() || id.to_identifier_name().map(|(name, _)| name != new_name).unwrap_or(true) {
+            return Err(RenameError::InvalidName { name: new_name.to_owned() });
+        }
+        Ok(())
+    }
+
+    /// The property a definition belongs to: the virtual slot for
+    /// a getter or setter, the origin for an entity after substitution,
+    /// and the definition itself otherwise.
+    fn property_of(&self, definition: &Entity) -> Entity {
+        let definition = if definition.is_entity_after_substitution() {
+            definition.origin()
+        } else {
+            definition.clone()
+        };
+        if definition.is::<MethodSlot>() {
+            if let Some(virtual_slot) = definition.of_virtual_slot(self.0) {
+                return virtual_slot;
+            }
+        }
+        definition
+    }
+
+    /// A definition along with its getter and setter, if it is a virtual slot.
+    fn with_accessors(&self, definition: &Entity) -> Vec<Entity> {
+        let mut r = vec![definition.clone()];
+        if definition.is::<VirtualSlot>() {
+            r.extend(definition.getter(self.0));
+            r.extend(definition.setter(self.0));
+        }
+        r
+    }
+
+    fn declaration_locations(&self, definition: &Entity) -> Vec<Location> {
+        let mut r: Vec<Location> = vec![];
+        for entity in self.with_accessors(definition) {
+            if let Ok(Some(location)) = entity.try_location() {
+                if !r.contains(&location) {
+                    r.push(location);
+                }
+            }
+        }
+        r
+    }
+
+    /// Narrows a location, such as that of a qualified name, to its trailing name.
+    fn name_location(location: &Location, old_name: &str) -> Location {
+        let text = location.text();
+        if text != old_name && text.ends_with(old_name) && location.last_offset() >= old_name.len() {
+            let last_offset = location.last_offset();
+            Location::with_offsets(&location.compilation_unit(), last_offset - old_name.len(), last_offset)
+        } else {
+            location.clone()
+        }
+    }
+
+    fn check_names_conflict(&self, definition: &Entity, new_name: &str, definitions: &[Entity]) -> Result<(), RenameError> {
+        let Some(parent) = definition.parent() else {
+            return Ok(());
+        };
+        let new_qname = self.0.factory().create_qname(&definition.name().namespace(), new_name.to_owned());
+        let mut tables = vec![parent.properties(self.0)];
+        if parent.is::<ClassType>() || parent.is::<EnumType>() || parent.is::<InterfaceType>() {
+            tables.push(parent.prototype(self.0));
+        }
+        for names in tables {
+            if let Some(existing) = names.get(&new_qname) {
+                if !definitions.contains(&self.property_of(&existing)) {
+                    return Err(RenameError::Conflict { definition: definition.clone(), existing });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that an instance member would not accidentally override, or be
+    /// overriden by, a member of a superclass, subclass, implemented interface
+    /// or implementor.
+    fn check_hierarchy_conflict(&self, definition: &Entity, new_name: &str, definitions: &[Entity]) -> Result<(), RenameError> {
+        let Some(parent) = definition.parent() else {
+            return Ok(());
+        };
+        let name = definition.name();
+        let mut related: Vec<Entity> = vec![];
+        if parent.is::<ClassType>() {
+            if self.is_static_member(definition, &parent) {
+                return Ok(());
+            }
+            related.extend(parent.descending_class_hierarchy(self.0).skip(1).map(|c| self.original_type(&c)));
+            related.extend(ClassHierarchy(self.0).all_descendants(&parent));
+            // Interfaces implemented by the class or its superclasses
+            for class in parent.descending_class_hierarchy(self.0).collect::<Vec<_>>() {
+                if !class.is::<ClassType>() {
+                    continue;
+                }
+                for itrfc in class.implements(self.0).iter() {
+                    related.push(self.original_type(&itrfc));
+                    related.extend(itrfc.all_ascending_types(self.0).iter().map(|t| self.original_type(t)));
+                }
+            }
+        } else if parent.is::<InterfaceType>() {
+            related.extend(parent.all_ascending_types(self.0).iter().map(|t| self.original_type(t)));
+            related.extend(ClassHierarchy(self.0).all_implementors(&parent));
+        } else {
+            return Ok(());
+        }
+        for class in related {
+            for (qname, existing) in class.prototype(self.0).borrow().iter() {
+                if qname.local_name() == new_name && self.namespaces_match(&qname.namespace(), &name.namespace())
+                && !definitions.contains(&self.property_of(existing)) {
+                    return Err(RenameError::Conflict { definition: definition.clone(), existing: existing.clone() });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that no reference made through the scope chain would
+    /// resolve to another definition visible with the new name
+    /// in the open namespace set of a scope.
+    ///
+    /// References without a scope are made through an object, as in `o.x`,
+    /// and are covered by the names and hierarchy checks. A definition of
+    /// a scope, such as a local variable, is also checked against the
+    /// enclosing scopes regardless of the recorded references, as it
+    /// would shadow a definition visible with the new name from them.
+    fn check_scope_conflicts(&self, definition: &Entity, new_name: &str, definitions: &[Entity]) -> Result<(), RenameError> {
+        let parent = definition.parent();
+        if let Some(parent) = parent.as_ref().filter(|p| p.is::<Scope>()) {
+            let location = self.declaration_locations(definition).remove(0);
+            let mut scope = parent.parent();
+            while let Some(scope_1) = scope {
+                if let Some(existing) = self.visible_in_scope(&scope_1, new_name) {
+                    if !definitions.contains(&self.property_of(&existing)) {
+                        return Err(RenameError::Shadowed { definition: definition.clone(), existing, location });
+                    }
+                }
+                scope = scope_1.parent();
+            }
+        }
+        for entity in self.with_accessors(definition) {
+            for reference in References(self.0).of(&entity) {
+                let Some(scope) = reference.scope.as_ref() else {
+                    continue;
+                };
+                let mut scope = Some(scope.clone());
+                while let Some(scope_1) = scope {
+                    if let Some(existing) = self.visible_in_scope(&scope_1, new_name) {
+                        if !definitions.contains(&self.property_of(&existing)) {
+                            return Err(RenameError::Shadowed { definition: definition.clone(), existing, location: reference.location.clone() });
+                        }
+                    }
+                    // Stop at the scope defining the renamed definition
+                    if parent.as_ref().map(|p| Self::scope_defines(&scope_1, p)).unwrap_or(false) {
+                        break;
+                    }
+                    scope = scope_1.parent();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn scope_defines(scope: &Entity, parent: &Entity) -> bool {
+        scope == parent
+            || ((scope.is::<ClassScope>() || scope.is::<EnumScope>() || scope.is::<InterfaceScope>()) && &scope.class() == parent)
+            || (scope.is::<PackageScope>() && &scope.package() == parent)
+    }
+
+    /// Returns a definition visible by a local name from a scope itself,
+    /// not from its parents, without marking anything used.
+    fn visible_in_scope(&self, scope: &Entity, local_name: &str) -> Option<Entity> {
+        let open_ns_set = scope.open_ns_set();
+        let found = |names: &Names| names.get_in_ns_set_or_any_public_ns(&open_ns_set, local_name).ok().flatten();
+
+        if let Some(r) = found(&scope.properties(self.0)) {
+            return Some(r);
+        }
+        if scope.is::<ClassScope>() || scope.is::<EnumScope>() || scope.is::<InterfaceScope>() {
+            let class = scope.class();
+            if class.is::<ClassType>() {
+                for class in class.descending_class_hierarchy(self.0).collect::<Vec<_>>() {
+                    if let Some(r) = found(&class.properties(self.0)).or_else(|| found(&class.prototype(self.0))) {
+                        return Some(r);
+                    }
+                }
+            } else if let Some(r) = found(&class.properties(self.0)).or_else(|| found(&class.prototype(self.0))) {
+                return Some(r);
+            }
+        }
+        if scope.is::<PackageScope>() {
+            if let Some(r) = found(&scope.package().properties(self.0)) {
+                return Some(r);
+            }
+        }
+        for import in scope.import_list().iter() {
+            if import.is::<PackageWildcardImport>() {
+                if let Some(r) = found(&import.package().properties(self.0)) {
+                    return Some(r);
+                }
+            } else if let Ok(property) = import.try_property() {
+                if property.try_name().map(|n| n.local_name() == local_name).unwrap_or(false) {
+                    return Some(property);
+                }
+            }
+        }
+        None
+    }
+
+    fn lookup_in_prototype_chain(&self, class: &Entity, local_name: &str) -> Option<Entity> {
+        for class in class.descending_class_hierarchy(self.0).collect::<Vec<_>>() {
+            if let Ok(Some(r)) = class.prototype(self.0).get_in_any_public_ns(local_name) {
+                return Some(self.property_of(&r));
+            }
+        }
+        None
+    }
+
+    fn is_static_member(&self, definition: &Entity, parent: &Entity) -> bool {
+        parent.properties(self.0).get(&definition.name()).as_ref() == Some(definition)
+    }
+
+    /// Indicates whether two namespaces qualify the same name across a
+    /// class hierarchy, where each class has its own protected namespace.
+    fn namespaces_match(&self, ns1: &Entity, ns2: &Entity) -> bool {
+        if ns1 == ns2 {
+            return true;
+        }
+        match (ns1.system_ns_kind(), ns2.system_ns_kind()) {
+            (Some(k1), Some(k2)) => k1 == k2 && k1 != SystemNamespaceKind::Private && k1 != SystemNamespaceKind::Internal,
+            _ => false,
+        }
+    }
+
+    fn original_type(&self, type_thing: &Entity) -> Entity {
+        if type_thing.is::<TypeAfterSubstitution>() {
+            type_thing.origin()
+        } else {
+            type_thing.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn public_qname(db: &Database, package: &Entity, local_name: &str) -> QName {
+        db.factory().create_qname(&package.public_ns().unwrap(), local_name.into())
+    }
+
+    /// Defines a variable in a package or scope.
+    fn define_variable(db: &Database, parent: &Entity, name: QName, location: Location) -> Entity {
+        let slot = db.factory().create_variable_slot(&name, false, &db.any_type());
+        slot.set_parent(Some(parent.clone()));
+        slot.set_location(Some(location));
+        parent.properties(db).set(name, slot.clone());
+        slot
+    }
+
+    #[test]
+    fn reference_in_context_without_scope_is_checked_for_shadowing() {
+        let db = Database::new(DatabaseOptions::default());
+        let cu = CompilationUnit::new(None, "x y x".into());
+        let p = db.factory().create_package(["p"]);
+        let x = define_variable(&db, &p, public_qname(&db, &p, "x"), Location::with_offsets(&cu, 0, 1));
+
+        let package_scope = db.factory().create_package_scope(&db.factory().create_package(["q"]));
+        package_scope.import_list().push(db.factory().create_package_property_import(&x, None));
+        let scope = db.factory().create_scope();
+        scope.set_parent(Some(package_scope));
+        let y = define_variable(&db, &scope, public_qname(&db, &p, "y"), Location::with_offsets(&cu, 2, 3));
+
+        References(&db).with_context(&Location::with_offsets(&cu, 4, 5), ReferenceKind::Read, || {
+            PropertyLookup(&db).lookup_in_scope_chain(&scope, None, &PropertyLookupKey::LocalName("x".into()))
+        }).unwrap();
+        assert_eq!(References(&db).of(&x)[0].scope, Some(scope.clone()));
+
+        let r = Rename(&db).rename(&x, "y");
+        assert!(matches!(r, Err(RenameError::Shadowed { existing, .. }) if existing == y));
+    }
+
+    #[test]
+    fn scope_definition_is_checked_against_enclosing_scopes() {
+        let db = Database::new(DatabaseOptions::default());
+        let cu = CompilationUnit::new(None, "y a".into());
+        let p = db.factory().create_package(["p"]);
+        let y = define_variable(&db, &p, public_qname(&db, &p, "y"), Location::with_offsets(&cu, 0, 1));
+
+        let package_scope = db.factory().create_package_scope(&db.factory().create_package(["q"]));
+        package_scope.import_list().push(db.factory().create_package_property_import(&y, None));
+        let scope = db.factory().create_scope();
+        scope.set_parent(Some(package_scope));
+        let a = define_variable(&db, &scope, public_qname(&db, &p, "a"), Location::with_offsets(&cu, 2, 3));
+
+        let r = Rename(&db).rename(&a, "y");
+        assert!(matches!(r, Err(RenameError::Shadowed { existing, .. }) if existing == y));
+        assert!(Rename(&db).rename(&a, "b").is_ok());
+    }
+
+    #[test]
+    fn class_member_is_checked_against_implemented_interfaces() {
+        let db = Database::new(DatabaseOptions::default());
+        let cu = CompilationUnit::new(None, "x".into());
+        let p = db.factory().create_package(["p"]);
+        let signature = db.factory().create_function_type(vec![], db.void_type());
+
+        let itrfc = db.factory().create_interface_type(public_qname(&db, &p, "I"));
+        let y_name = public_qname(&db, &p, "y");
+        let y = db.factory().create_method_slot(&y_name, &signature);
+        y.set_parent(Some(itrfc.clone()));
+        itrfc.prototype(&db).set(y_name, y.clone());
+
+        let class = db.factory().create_class_type(public_qname(&db, &p, "C"), &p.public_ns().unwrap());
+        class.implements(&db).push(itrfc.clone());
+        let x_name = public_qname(&db, &p, "x");
+        let x = db.factory().create_method_slot(&x_name, &signature);
+        x.set_parent(Some(class.clone()));
+        x.set_location(Some(Location::with_offsets(&cu, 0, 1)));
+        class.prototype(&db).set(x_name, x.clone());
+
+        let r = Rename(&db).rename(&x, "y");
+        assert!(matches!(r, Err(RenameError::Conflict { existing, .. }) if existing == y));
+    }
+}
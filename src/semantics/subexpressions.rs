@@ -0,0 +1,158 @@
+use crate::ns::*;
+
+/// Returns the direct subexpressions an expression evaluates, in
+/// evaluation order, including computed qualifiers, computed keys
+/// and expressions embedded in XML literals.
+///
+/// The body of a function expression is not included, and neither are
+/// type expressions, such as the type arguments of `o.<T>`.
+pub fn subexpressions(exp: &Rc<Expression>) -> Vec<Rc<Expression>> {
+    let mut r: Vec<Rc<Expression>> = vec![];
+    match exp.as_ref() {
+        Expression::QualifiedIdentifier(id) => qualified_identifier_subexpressions(id, &mut r),
+        Expression::Paren(e) => r.push(e.expression.clone()),
+        Expression::Xml(e) => xml_element_subexpressions(&e.element, &mut r),
+        Expression::XmlList(e) => xml_content_subexpressions(&e.content, &mut r),
+        Expression::ArrayLiteral(e) => element_subexpressions(&e.elements, &mut r),
+        Expression::VectorLiteral(e) => element_subexpressions(&e.elements, &mut r),
+        Expression::ObjectInitializer(e) => {
+            for field in e.fields.iter() {
+                match field.as_ref() {
+                    InitializerField::Field { name, value, .. } => {
+                        match &name.0 {
+                            FieldName::Identifier(id) => qualified_identifier_subexpressions(id, &mut r),
+                            FieldName::Brackets(key) => r.push(key.clone()),
+                            _ => {},
+                        }
+                        r.extend(value.iter().cloned());
+                    },
+                    InitializerField::Rest((exp, _)) => r.push(exp.clone()),
+                }
+            }
+        },
+        Expression::New(e) => {
+            r.push(e.base.clone());
+            r.extend(e.arguments.iter().flatten().cloned());
+        },
+        Expression::Member(e) => {
+            r.push(e.base.clone());
+            qualified_identifier_subexpressions(&e.identifier, &mut r);
+        },
+        Expression::ComputedMember(e) => {
+            r.push(e.base.clone());
+            r.push(e.key.clone());
+        },
+        Expression::Descendants(e) => {
+            r.push(e.base.clone());
+            qualified_identifier_subexpressions(&e.identifier, &mut r);
+        },
+        Expression::Filter(e) => {
+            r.push(e.base.clone());
+            r.push(e.test.clone());
+        },
+        Expression::Super(e) => r.extend(e.object.iter().flatten().cloned()),
+        Expression::Call(e) => {
+            r.push(e.base.clone());
+            r.extend(e.arguments.iter().cloned());
+        },
+        Expression::WithTypeArguments(e) => r.push(e.base.clone()),
+        Expression::Unary(e) => r.push(e.expression.clone()),
+        Expression::OptionalChaining(e) => {
+            r.push(e.base.clone());
+            r.push(e.expression.clone());
+        },
+        Expression::Binary(e) => {
+            r.push(e.left.clone());
+            r.push(e.right.clone());
+        },
+        Expression::Conditional(e) => {
+            r.push(e.test.clone());
+            r.push(e.consequent.clone());
+            r.push(e.alternative.clone());
+        },
+        Expression::Assignment(e) => {
+            r.push(e.left.clone());
+            r.push(e.right.clone());
+        },
+        Expression::Sequence(e) => {
+            r.push(e.left.clone());
+            r.push(e.right.clone());
+        },
+        _ => {},
+    }
+    r
+}
+
+/// Collects the references a destructuring pattern assigns to: the
+/// entities mapped to its identifiers and to the shorthand fields of
+/// its object patterns. Patterns that are not destructuring, such as
+/// member expressions, assign to no local and are skipped.
+pub fn destructuring_targets(db: &Database, pattern: &Rc<Expression>, r: &mut Vec<Entity>) {
+    match pattern.as_ref() {
+        Expression::QualifiedIdentifier(_) => r.extend(db.node_mapping().get(pattern)),
+        Expression::Paren(e) => destructuring_targets(db, &e.expression, r),
+        Expression::Unary(e) if e.operator == Operator::NonNull => destructuring_targets(db, &e.expression, r),
+        Expression::ArrayLiteral(e) => {
+            for el in e.elements.iter() {
+                match el {
+                    Element::Expression(exp) | Element::Rest((exp, _)) => destructuring_targets(db, exp, r),
+                    Element::Elision => {},
+                }
+            }
+        },
+        Expression::ObjectInitializer(e) => {
+            for field in e.fields.iter() {
+                match field.as_ref() {
+                    InitializerField::Field { value: Some(value), .. } => destructuring_targets(db, value, r),
+                    InitializerField::Field { value: None, .. } => r.extend(db.node_mapping().get(field)),
+                    InitializerField::Rest((exp, _)) => destructuring_targets(db, exp, r),
+                }
+            }
+        },
+        _ => {},
+    }
+}
+
+fn qualified_identifier_subexpressions(id: &QualifiedIdentifier, r: &mut Vec<Rc<Expression>>) {
+    r.extend(id.qualifier.iter().cloned());
+    if let QualifiedIdentifierIdentifier::Brackets(exp) = &id.id {
+        r.push(exp.clone());
+    }
+}
+
+fn element_subexpressions(elements: &[Element], r: &mut Vec<Rc<Expression>>) {
+    for el in elements.iter() {
+        match el {
+            Element::Expression(exp) | Element::Rest((exp, _)) => r.push(exp.clone()),
+            Element::Elision => {},
+        }
+    }
+}
+
+fn xml_element_subexpressions(element: &XmlElement, r: &mut Vec<Rc<Expression>>) {
+    if let XmlTagName::Expression(exp) = &element.name {
+        r.push(exp.clone());
+    }
+    for attribute in element.attributes.iter() {
+        if let XmlAttributeValue::Expression(exp) = &attribute.value {
+            r.push(exp.clone());
+        }
+    }
+    r.extend(element.attribute_expression.iter().cloned());
+    if let Some(content) = element.content.as_ref() {
+        xml_content_subexpressions(content, r);
+    }
+    if let Some(XmlTagName::Expression(exp)) = &element.closing_name {
+        r.push(exp.clone());
+    }
+}
+
+fn xml_content_subexpressions(content: &[Rc<XmlContent>], r: &mut Vec<Rc<Expression>>) {
+    for item in content.iter() {
+        match item.as_ref() {
+            XmlContent::Element(element) => xml_element_subexpressions(element, r),
+            XmlContent::Expression(exp) => r.push(exp.clone()),
+            _ => {},
+        }
+    }
+}
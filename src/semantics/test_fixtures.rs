@@ -0,0 +1,157 @@
+//! Fixtures shared by the tests of the semantic analyses.
+
+use crate::ns::*;
+
+/// Parses directives from source text.
+pub fn parse_directives(source: &str) -> Vec<Rc<Directive>> {
+    let cu = CompilationUnit::new(None, source.into());
+    ParserFacade(&cu, ParserOptions::default()).parse_directives(ParserDirectiveContext::Default)
+}
+
+/// Parses an expression from source text.
+pub fn parse_expression(source: &str) -> Rc<Expression> {
+    let cu = CompilationUnit::new(None, source.into());
+    ParserFacade(&cu, ParserOptions::default()).parse_expression()
+}
+
+/// Defines `Object`, `Boolean`, `String` and the numeric types
+/// in the top-level package.
+pub fn define_builtins(db: &Database) {
+    let top_level_package = db.top_level_package();
+    let public_ns = top_level_package.public_ns().unwrap();
+    let mut object_type: Option<Entity> = None;
+    for local_name in ["Object", "Boolean", "String", "Number", "int", "uint", "float", "long", "ulong", "BigInt"] {
+        let name = db.factory().create_qname(&public_ns, local_name.into());
+        let class = db.factory().create_class_type(name.clone(), &public_ns);
+        class.set_extends_class(object_type.clone());
+        top_level_package.properties(db).set(name, class.clone());
+        object_type.get_or_insert(class);
+    }
+}
+
+/// Creates the activation of a method `f():void` in the top-level package.
+pub fn define_activation(db: &Database) -> Entity {
+    let name = db.factory().create_qname(&db.top_level_package().public_ns().unwrap(), "f".into());
+    let method = db.factory().create_method_slot(&name, &db.factory().create_function_type(vec![], db.void_type()));
+    method.set_parent(Some(db.top_level_package()));
+    let activation = db.factory().create_activation(&method);
+    activation.set_parent(Some(db.top_level_package()));
+    activation
+}
+
+/// Defines a local variable of type `*` in an activation.
+pub fn define_local(db: &Database, activation: &Entity, local_name: &str) -> Entity {
+    let name = db.factory().create_qname(&db.top_level_package().public_ns().unwrap(), local_name.into());
+    let slot = db.factory().create_variable_slot(&name, false, &db.any_type());
+    slot.set_parent(Some(activation.clone()));
+    activation.properties(db).set(name, slot.clone());
+    slot
+}
+
+/// Maps each identifier of the directives, including shorthand fields
+/// of object initializers, to the entity `resolve` returns for its name.
+/// Nested function bodies are not visited.
+pub fn map_identifiers(db: &Database, directives: &[Rc<Directive>], resolve: &dyn Fn(&str) -> Option<Entity>) {
+    for directive in directives.iter() {
+        let (expressions, children) = directive_parts(directive);
+        for exp in expressions.iter() {
+            map_identifiers_in_expression(db, exp, resolve);
+        }
+        map_identifiers(db, &children, resolve);
+    }
+}
+
+/// Maps each identifier of an expression as `map_identifiers()` does.
+pub fn map_identifiers_in_expression(db: &Database, exp: &Rc<Expression>, resolve: &dyn Fn(&str) -> Option<Entity>) {
+    match exp.as_ref() {
+        Expression::QualifiedIdentifier(id) => {
+            if let Some(entity) = id.to_identifier_name().and_then(|(name, _)| resolve(&name)) {
+                db.node_mapping().set(exp, Some(entity));
+            }
+        },
+        Expression::ObjectInitializer(e) => {
+            for field in e.fields.iter() {
+                if let InitializerField::Field { value: None, .. } = field.as_ref() {
+                    if let Some(entity) = field.shorthand().and_then(|id| id.to_identifier_name()).and_then(|(name, _)| resolve(&name)) {
+                        db.node_mapping().set(field, Some(entity));
+                    }
+                }
+            }
+        },
+        _ => {},
+    }
+    for exp in subexpressions(exp) {
+        map_identifiers_in_expression(db, &exp, resolve);
+    }
+}
+
+/// Returns the expressions and the sub-directives of a directive.
+pub fn directive_parts(directive: &Rc<Directive>) -> (Vec<Rc<Expression>>, Vec<Rc<Directive>>) {
+    let bindings = |bindings: &[Rc<VariableBinding>]| -> Vec<Rc<Expression>> {
+        bindings.iter().flat_map(|b| [Some(b.destructuring.destructuring.clone()), b.initializer.clone()]).flatten().collect()
+    };
+    match directive.as_ref() {
+        Directive::ExpressionStatement(d) => (vec![d.expression.clone()], vec![]),
+        Directive::Block(d) => (vec![], d.directives.clone()),
+        Directive::LabeledStatement(d) => (vec![], vec![d.substatement.clone()]),
+        Directive::IfStatement(d) => (vec![d.test.clone()], [Some(d.consequent.clone()), d.alternative.clone()].into_iter().flatten().collect()),
+        Directive::WhileStatement(d) => (vec![d.test.clone()], vec![d.body.clone()]),
+        Directive::DoStatement(d) => (vec![d.test.clone()], vec![d.body.clone()]),
+        Directive::ForStatement(d) => {
+            let mut expressions = match d.init.as_ref() {
+                Some(ForInitializer::Expression(exp)) => vec![exp.clone()],
+                Some(ForInitializer::VariableDefinition(defn)) => bindings(&defn.bindings),
+                None => vec![],
+            };
+            expressions.extend(d.test.iter().chain(d.update.iter()).cloned());
+            (expressions, vec![d.body.clone()])
+        },
+        Directive::ForInStatement(d) => {
+            let mut expressions = match &d.left {
+                ForInBinding::Expression(exp) => vec![exp.clone()],
+                ForInBinding::VariableDefinition(defn) => bindings(&defn.bindings),
+            };
+            expressions.push(d.right.clone());
+            (expressions, vec![d.body.clone()])
+        },
+        Directive::SwitchStatement(d) => {
+            let mut expressions = vec![d.discriminant.clone()];
+            let mut children = vec![];
+            for case in d.cases.iter() {
+                for label in case.labels.iter() {
+                    if let CaseLabel::Case((exp, _)) = label {
+                        expressions.push(exp.clone());
+                    }
+                }
+                children.extend(case.directives.iter().cloned());
+            }
+            (expressions, children)
+        },
+        Directive::WithStatement(d) => (vec![d.object.clone()], vec![d.body.clone()]),
+        Directive::ReturnStatement(d) => (d.expression.iter().cloned().collect(), vec![]),
+        Directive::ThrowStatement(d) => (vec![d.expression.clone()], vec![]),
+        Directive::DefaultXmlNamespaceStatement(d) => (vec![d.right.clone()], vec![]),
+        Directive::VariableDefinition(d) => (bindings(&d.bindings), vec![]),
+        _ => (vec![], vec![]),
+    }
+}
+
+/// Returns the directives of a block statement.
+pub fn block_directives(directive: &Rc<Directive>) -> Vec<Rc<Directive>> {
+    match directive.as_ref() {
+        Directive::Block(d) => d.directives.clone(),
+        _ => panic!("expected a block"),
+    }
+}
+
+/// Adds a block to a control flow graph.
+pub fn add_block(cfg: &ControlFlowGraph, lines: Vec<Rc<Directive>>) -> ControlFlowBlock {
+    let block = ControlFlowBlock::new(lines);
+    cfg.blocks().push(block.clone());
+    block
+}
+
+/// Adds an edge to a control flow graph.
+pub fn add_edge(cfg: &ControlFlowGraph, from: &ControlFlowBlock, to: &ControlFlowBlock) {
+    cfg.edges().push(ControlFlowEdge { from: from.clone(), to: to.clone() });
+}
@@ -0,0 +1,281 @@
+use crate::ns::*;
+
+/// Operation for inferring the type arguments of a call to a generic
+/// function from the static types of its arguments.
+///
+/// ```ignore
+/// let substitute_types = TypeInference(&db).infer(&signature, &type_params, &argument_types)?;
+/// ```
+pub struct TypeInference<'a>(pub &'a Database);
+
+impl<'a> TypeInference<'a> {
+    /// Unifies the parameter types of a signature with the static types of
+    /// the call arguments, returning the inferred substitute types, in the
+    /// same order as `type_params`.
+    ///
+    /// `signature` is either a function type or a method slot.
+    pub fn infer(&self, signature: &Entity, type_params: &SharedArray<Entity>, argument_types: &[Entity]) -> Result<SharedArray<Entity>, TypeInferenceError> {
+        let signature = if signature.is::<MethodSlot>() {
+            signature.signature(self.0)
+        } else {
+            signature.clone()
+        };
+
+        // Defer if unresolved
+        signature.defer().map_err(|_| TypeInferenceError::Defer)?;
+
+        let mut inferred: Vec<Option<Entity>> = type_params.iter().map(|_| None).collect();
+        let params: Vec<Rc<SemanticFunctionTypeParameter>> = signature.params().iter().collect();
+
+        for (i, argument_type) in argument_types.iter().enumerate() {
+            let Some(param_type) = self.parameter_type_at(&params, i)? else {
+                continue;
+            };
+            self.unify(&param_type, argument_type, type_params, &mut inferred, false)?;
+        }
+
+        let mut r: SharedArray<Entity> = SharedArray::new();
+        for (type_param, t) in type_params.iter().zip(inferred) {
+            let Some(t) = t else {
+                return Err(TypeInferenceError::NotInferred { type_param });
+            };
+            r.push(t);
+        }
+
+        ApplyType(self.0).verify_substitute_types(type_params, &r).map_err(Self::substitution_error)?;
+
+        Ok(r)
+    }
+
+    /// Infers the type arguments of a call to a generic method and returns
+    /// the method after substitution of its own type parameters. If the
+    /// method is not generic, returns the method itself.
+    pub fn infer_method_call(&self, method: &Entity, argument_types: &[Entity]) -> Result<Entity, TypeInferenceError> {
+        let Some(type_params) = method.type_params() else {
+            return Ok(method.clone());
+        };
+        let substitute_types = self.infer(method, &type_params, argument_types)?;
+        self.0.factory().create_method_slot_after_type_arguments(method, &substitute_types).map_err(Self::substitution_error)
+    }
+
+    fn substitution_error(error: TypeSubstitutionError) -> TypeInferenceError {
+        match error {
+            TypeSubstitutionError::ConstraintNotSatisfied { type_param, constraint, substitute_type } =>
+                TypeInferenceError::ConstraintNotSatisfied { type_param, constraint, substitute_type },
+            TypeSubstitutionError::WrongParameterCount { expected, actual } =>
+                TypeInferenceError::WrongParameterCount { expected, actual },
+            TypeSubstitutionError::Defer => TypeInferenceError::Defer,
+        }
+    }
+
+    /// Returns the type a call argument at a given position is
+    /// assigned to, taking the rest parameter into account.
+    fn parameter_type_at(&self, params: &[Rc<SemanticFunctionTypeParameter>], i: usize) -> Result<Option<Entity>, TypeInferenceError> {
+        if let Some(param) = params.get(i) {
+            if param.kind != ParameterKind::Rest {
+                return Ok(Some(param.static_type.clone()));
+            }
+        }
+        let Some(rest) = params.last().filter(|p| p.kind == ParameterKind::Rest) else {
+            return Ok(None);
+        };
+        let element_type = rest.static_type.escape_of_nullable_or_non_nullable()
+            .array_element_type(self.0).map_err(|_| TypeInferenceError::Defer)?;
+        Ok(Some(element_type.unwrap_or(self.0.any_type())))
+    }
+
+    /// Unifies a parameter type with an argument type. `contravariant` indicates
+    /// that the types occur in a function parameter position, where an argument
+    /// is assignable if its parameter type is a supertype of the expected one.
+    fn unify(&self, param_type: &Entity, argument_type: &Entity, type_params: &SharedArray<Entity>, inferred: &mut [Option<Entity>], contravariant: bool) -> Result<(), TypeInferenceError> {
+        // Defer if unresolved
+        argument_type.defer().map_err(|_| TypeInferenceError::Defer)?;
+
+        if argument_type.is::<InvalidationEntity>() {
+            return Ok(());
+        }
+
+        if param_type.is::<TypeParameterType>() {
+            if let Some(i) = type_params.index_of(param_type) {
+                return self.bind(i, param_type, argument_type, inferred, contravariant);
+            }
+            return Ok(());
+        }
+
+        if param_type.is::<NullableType>() || param_type.is::<NonNullableType>() {
+            let argument_type = argument_type.escape_of_nullable_or_non_nullable();
+            return self.unify(&param_type.base(), &argument_type, type_params, inferred, contravariant);
+        }
+
+        let argument_type = argument_type.escape_of_nullable_or_non_nullable();
+
+        if param_type.is::<TupleType>() {
+            if argument_type.is::<TupleType>() && argument_type.element_types().length() == param_type.element_types().length() {
+                for (p, a) in param_type.element_types().iter().zip(argument_type.element_types().iter()) {
+                    self.unify(&p, &a, type_params, inferred, contravariant)?;
+                }
+            }
+            return Ok(());
+        }
+
+        if param_type.is::<FunctionType>() {
+            if argument_type.is::<FunctionType>() {
+                for (p, a) in param_type.params().iter().zip(argument_type.params().iter()) {
+                    self.unify(&p.static_type, &a.static_type, type_params, inferred, !contravariant)?;
+                }
+                self.unify(&param_type.result_type(), &argument_type.result_type(), type_params, inferred, contravariant)?;
+            }
+            return Ok(());
+        }
+
+        if param_type.is::<TypeAfterSubstitution>() {
+            // Look for the parameterized type in the argument type
+            // or in one of its ascending types.
+            let origin = param_type.origin();
+            let mut candidates = vec![argument_type.clone()];
+            candidates.extend(argument_type.all_ascending_types(self.0));
+            for t in candidates {
+                // Defer if unresolved
+                t.defer().map_err(|_| TypeInferenceError::Defer)?;
+
+                if t.type_after_sub_has_origin(&origin) {
+                    for (p, a) in param_type.substitute_types().iter().zip(t.substitute_types().iter()) {
+                        self.unify(&p, &a, type_params, inferred, contravariant)?;
+                    }
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Binds a type parameter to a type. If the type parameter is already
+    /// bound and one type is a subtype of the other, the bound type is widened,
+    /// or narrowed in a contravariant position; otherwise a conflict is reported.
+    fn bind(&self, i: usize, type_param: &Entity, t: &Entity, inferred: &mut [Option<Entity>], contravariant: bool) -> Result<(), TypeInferenceError> {
+        let Some(existing) = inferred[i].clone() else {
+            inferred[i] = Some(t.clone());
+            return Ok(());
+        };
+        if &existing == t {
+            return Ok(());
+        }
+        let (narrower, wider) = if contravariant { (t, &existing) } else { (&existing, t) };
+        if narrower.is_subtype_of(wider, self.0).map_err(|_| TypeInferenceError::Defer)? {
+            inferred[i] = Some(t.clone());
+            return Ok(());
+        }
+        if wider.is_subtype_of(narrower, self.0).map_err(|_| TypeInferenceError::Defer)? {
+            return Ok(());
+        }
+        Err(TypeInferenceError::Conflict {
+            type_param: type_param.clone(),
+            inferred_types: vec![existing, t.clone()],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn define_class(db: &Database, local_name: &str, base: Option<&Entity>) -> Entity {
+        let top_level_package = db.top_level_package();
+        let public_ns = top_level_package.public_ns().unwrap();
+        let name = db.factory().create_qname(&public_ns, local_name.into());
+        let class = db.factory().create_class_type(name.clone(), &public_ns);
+        class.set_extends_class(base.cloned());
+        top_level_package.properties(db).set(name, class.clone());
+        class
+    }
+
+    fn define_type_param(db: &Database, local_name: &str) -> Entity {
+        let public_ns = db.top_level_package().public_ns().unwrap();
+        db.factory().create_type_parameter_type(&db.factory().create_qname(&public_ns, local_name.into()))
+    }
+
+    fn function_type(db: &Database, param_types: Vec<Entity>, result_type: &Entity) -> Entity {
+        let params = param_types.into_iter().map(|static_type| Rc::new(SemanticFunctionTypeParameter {
+            kind: ParameterKind::Required,
+            static_type,
+        })).collect();
+        db.factory().create_function_type(params, result_type.clone())
+    }
+
+    #[test]
+    fn infers_through_nullable_and_tuple_types() {
+        let db = Database::new(DatabaseOptions::default());
+        let a = define_class(&db, "A", None);
+        let b = define_class(&db, "B", None);
+        let t = define_type_param(&db, "T");
+        let u = define_type_param(&db, "U");
+        let tuple = db.factory().create_tuple_type(vec![t.clone(), u.clone()]);
+        let signature = function_type(&db, vec![db.factory().create_nullable_type(&t), tuple], &db.void_type());
+        let argument_types = [db.factory().create_nullable_type(&a), db.factory().create_tuple_type(vec![a.clone(), b.clone()])];
+
+        let r = TypeInference(&db).infer(&signature, &shared_array![t, u], &argument_types).unwrap();
+        assert_eq!(r.iter().collect::<Vec<_>>(), vec![a.clone(), b]);
+    }
+
+    #[test]
+    fn widens_covariant_and_narrows_contravariant_positions() {
+        let db = Database::new(DatabaseOptions::default());
+        let a = define_class(&db, "A", None);
+        let b = define_class(&db, "B", Some(&a));
+        let t = define_type_param(&db, "T");
+
+        // function(x:T, y:T):void called with (B, A)
+        let signature = function_type(&db, vec![t.clone(), t.clone()], &db.void_type());
+        let r = TypeInference(&db).infer(&signature, &shared_array![t.clone()], &[b.clone(), a.clone()]).unwrap();
+        assert_eq!(r.get(0), Some(a.clone()));
+
+        // function(f:function(T):void, g:function(T):void):void called with
+        // (function(B):void, function(A):void)
+        let callback = function_type(&db, vec![t.clone()], &db.void_type());
+        let signature = function_type(&db, vec![callback.clone(), callback], &db.void_type());
+        let argument_types = [function_type(&db, vec![b.clone()], &db.void_type()), function_type(&db, vec![a.clone()], &db.void_type())];
+        let r = TypeInference(&db).infer(&signature, &shared_array![t], &argument_types).unwrap();
+        assert_eq!(r.get(0), Some(b));
+    }
+
+    #[test]
+    fn infers_from_ascending_type_after_substitution() {
+        let db = Database::new(DatabaseOptions::default());
+        let a = define_class(&db, "A", None);
+        let boxed = define_class(&db, "Box", None);
+        let e = define_type_param(&db, "E");
+        boxed.set_type_params(Some(shared_array![e]));
+        let box_a = db.factory().create_type_after_substitution(&boxed, &shared_array![a.clone()]);
+        let c = define_class(&db, "C", Some(&box_a));
+
+        let t = define_type_param(&db, "T");
+        let box_t = db.factory().create_type_after_substitution(&boxed, &shared_array![t.clone()]);
+        let signature = function_type(&db, vec![box_t], &db.void_type());
+        let r = TypeInference(&db).infer(&signature, &shared_array![t], &[c]).unwrap();
+        assert_eq!(r.get(0), Some(a));
+    }
+
+    #[test]
+    fn reports_conflicts_and_uninferred_type_parameters() {
+        let db = Database::new(DatabaseOptions::default());
+        let a = define_class(&db, "A", None);
+        let b = define_class(&db, "B", None);
+        let t = define_type_param(&db, "T");
+        let u = define_type_param(&db, "U");
+
+        let signature = function_type(&db, vec![t.clone(), t.clone()], &db.void_type());
+        let r = TypeInference(&db).infer(&signature, &shared_array![t.clone()], &[a.clone(), b]);
+        assert!(matches!(r, Err(TypeInferenceError::Conflict { type_param, .. }) if type_param == t));
+
+        let signature = function_type(&db, vec![t.clone()], &db.void_type());
+        let r = TypeInference(&db).infer(&signature, &shared_array![t, u.clone()], &[a]);
+        assert!(matches!(r, Err(TypeInferenceError::NotInferred { type_param }) if type_param == u));
+    }
+
+    #[test]
+    fn reports_wrong_parameter_count() {
+        let r = TypeInference::substitution_error(TypeSubstitutionError::WrongParameterCount { expected: 1, actual: 2 });
+        assert!(matches!(r, TypeInferenceError::WrongParameterCount { expected: 1, actual: 2 }));
+    }
+}
@@ -0,0 +1,284 @@
+use crate::ns::*;
+
+/// Resolves type expression and qualified name strings into entities,
+/// as in `"Vector.<flash.display.Sprite>?"`, `"function(int, ...Array):void"`
+/// or `"mx_internal::foo"`.
+///
+/// Names are resolved without a scope: a simple name refers to a
+/// top-level definition, and a dotted name refers to a definition
+/// in the longest matching package, optionally followed by static members.
+/// A namespace qualifier consisting of a simple name may also refer
+/// to the only namespace definition with that name in any package.
+///
+/// Locations in errors are relative to the given string.
+pub struct TypeParser<'a>(pub &'a Database);
+
+impl<'a> TypeParser<'a> {
+    /// Parses a type expression and resolves it to an interned type.
+    pub fn parse_type(&self, source: &str) -> Result<Entity, TypeParseError> {
+        let cu = CompilationUnit::new(None, source.to_owned());
+        let exp = ParserFacade(&cu, ParserOptions::default()).parse_type_expression();
+        Self::check_syntax(&cu)?;
+        self.resolve_type(&exp)
+    }
+
+    /// Parses a qualified name, as in `mx_internal::foo`, `public::foo` or `foo`.
+    /// An unqualified name is in the public namespace of the top-level package.
+    pub fn parse_qname(&self, source: &str) -> Result<QName, TypeParseError> {
+        let cu = CompilationUnit::new(None, source.to_owned());
+        let id = ParserFacade(&cu, ParserOptions::default()).parse_qualified_identifier();
+        Self::check_syntax(&cu)?;
+        let QualifiedIdentifierIdentifier::Id((local_name, location)) = &id.id else {
+            return Err(TypeParseError::Syntax { messages: vec!["Expected identifier".into()] });
+        };
+        if id.attribute || local_name == "*" {
+            return Err(TypeParseError::Syntax { messages: vec!["Expected identifier".into()] });
+        }
+        let ns = if let Some(qual) = id.qualifier.as_ref() {
+            self.resolve_namespace(qual)?
+        } else {
+            self.public_ns_of_top_level(location)?
+        };
+        Ok(self.0.factory().create_qname(&ns, local_name.clone()))
+    }
+
+    fn check_syntax(cu: &Rc<CompilationUnit>) -> Result<(), TypeParseError> {
+        if cu.invalidated() {
+            let messages = cu.diagnostics().iter().filter(|d| d.is_error()).map(|d| d.format_message_english()).collect();
+            return Err(TypeParseError::Syntax { messages });
+        }
+        Ok(())
+    }
+
+    fn resolve_type(&self, exp: &Rc<Expression>) -> Result<Entity, TypeParseError> {
+        match exp.as_ref() {
+            Expression::AnyType(_) => Ok(self.0.any_type()),
+            Expression::VoidType(_) => Ok(self.0.void_type()),
+            Expression::Paren(e) => self.resolve_type(&e.expression),
+            Expression::NullableType(e) => {
+                let base = self.resolve_type(&e.base)?;
+                Ok(self.0.factory().create_nullable_type(&base))
+            },
+            Expression::NonNullableType(e) => {
+                let base = self.resolve_type(&e.base)?;
+                Ok(self.0.factory().create_non_nullable_type(&base))
+            },
+            Expression::ArrayType(e) => {
+                let element_type = self.resolve_type(&e.expression)?;
+                let array_type = self.array_type(&e.location)?;
+                self.0.factory().try_create_type_after_substitution(&array_type, &shared_array![element_type])
+                    .map_err(TypeParseError::TypeSubstitution)
+            },
+            Expression::TupleType(e) => {
+                let mut element_types = vec![];
+                for element in e.expressions.iter() {
+                    element_types.push(self.resolve_type(element)?);
+                }
+                Ok(self.0.factory().create_tuple_type(element_types))
+            },
+            Expression::FunctionType(e) => {
+                let mut params = vec![];
+                for param in e.parameters.iter() {
+                    let static_type = if let Some(t) = param.type_expression.as_ref() {
+                        self.resolve_type(t)?
+                    } else {
+                        let array_type = self.array_type(&param.location)?;
+                        self.0.factory().try_create_type_after_substitution(&array_type, &shared_array![self.0.any_type()])
+                            .map_err(TypeParseError::TypeSubstitution)?
+                    };
+                    params.push(Rc::new(SemanticFunctionTypeParameter { kind: param.kind, static_type }));
+                }
+                let result_type = if let Some(t) = e.result_type.as_ref() {
+                    self.resolve_type(t)?
+                } else {
+                    self.0.any_type()
+                };
+                Ok(self.0.factory().create_function_type(params, result_type))
+            },
+            Expression::WithTypeArguments(e) => {
+                let origin = self.resolve_type(&e.base)?;
+                let mut substitute_types = shared_array![];
+                for argument in e.arguments.iter() {
+                    substitute_types.push(self.resolve_type(argument)?);
+                }
+                self.0.factory().try_create_type_after_substitution(&origin, &substitute_types)
+                    .map_err(TypeParseError::TypeSubstitution)
+            },
+            Expression::QualifiedIdentifier(_) | Expression::Member(_) => {
+                let (segment, r) = self.resolve_name(exp)?;
+                if !r.is::<Type>() {
+                    return Err(TypeParseError::NotAType { segment, location: exp.location() });
+                }
+                Ok(r)
+            },
+            _ => Err(TypeParseError::Syntax { messages: vec!["Expected type expression".into()] }),
+        }
+    }
+
+    /// Resolves a simple or dotted name, returning the name text along with the definition.
+    fn resolve_name(&self, exp: &Rc<Expression>) -> Result<(String, Entity), TypeParseError> {
+        let mut segments: Vec<(Option<Rc<Expression>>, String, Location)> = vec![];
+        Self::collect_segments(exp, &mut segments)?;
+
+        // Find the longest package prefix
+        let mut package = self.0.top_level_package();
+        let mut i = 0;
+        while i + 1 < segments.len() && segments[i].0.is_none() {
+            let Some(subpackage) = package.subpackages().get(&segments[i].1) else {
+                break;
+            };
+            package = subpackage;
+            i += 1;
+        }
+
+        let mut base = package;
+        let mut r: Option<Entity> = None;
+        let mut text = segments[..i].iter().map(|s| s.1.clone()).collect::<Vec<_>>().join(".");
+        for (qual, local_name, location) in &segments[i..] {
+            if !text.is_empty() {
+                text.push('.');
+            }
+            text.push_str(local_name);
+            let names = base.properties(self.0);
+            let found = if let Some(qual) = qual {
+                let ns = self.resolve_namespace(qual)?;
+                if ns.system_ns_kind() == Some(SystemNamespaceKind::Public) {
+                    names.get_in_system_ns_kind(SystemNamespaceKind::Public, local_name)
+                } else {
+                    Ok(names.get(&self.0.factory().create_qname(&ns, local_name.clone())))
+                }
+            } else {
+                names.get_in_any_public_ns(local_name)
+            };
+            let found = found.map_err(|_| TypeParseError::AmbiguousReference { segment: text.clone(), location: location.clone() })?;
+            // `Vector` is not defined at the top-level package.
+            let found = found.or_else(|| if r.is_none() && base == self.0.top_level_package() && local_name == "Vector" && qual.is_none() {
+                Some(self.0.vector_type()).filter(|t| !t.is::<UnresolvedEntity>())
+            } else {
+                None
+            });
+            let Some(found) = found else {
+                return Err(TypeParseError::Unresolved { segment: text, location: location.clone() });
+            };
+            let found = found.resolve_alias();
+            base = found.clone();
+            r = Some(found);
+        }
+
+        Ok((text, r.unwrap()))
+    }
+
+    fn collect_segments(exp: &Rc<Expression>, segments: &mut Vec<(Option<Rc<Expression>>, String, Location)>) -> Result<(), TypeParseError> {
+        let id = match exp.as_ref() {
+            Expression::QualifiedIdentifier(id) => id,
+            Expression::Member(e) => {
+                Self::collect_segments(&e.base, segments)?;
+                &e.identifier
+            },
+            _ => return Err(TypeParseError::Syntax { messages: vec!["Expected name".into()] }),
+        };
+        match &id.id {
+            QualifiedIdentifierIdentifier::Id((name, location)) if !id.attribute && name != "*" => {
+                segments.push((id.qualifier.clone(), name.clone(), location.clone()));
+                Ok(())
+            },
+            _ => Err(TypeParseError::Syntax { messages: vec!["Expected identifier".into()] }),
+        }
+    }
+
+    fn resolve_namespace(&self, qual: &Rc<Expression>) -> Result<Entity, TypeParseError> {
+        match qual.as_ref() {
+            Expression::ReservedNamespace(ReservedNamespaceExpression::Public(location)) => {
+                return self.public_ns_of_top_level(location);
+            },
+            Expression::ReservedNamespace(ns) => {
+                return Err(TypeParseError::NotANamespace { segment: ns.to_string(), location: ns.location() });
+            },
+            _ => {},
+        }
+        let r = match self.resolve_name(qual) {
+            Ok((segment, r)) => (segment, r),
+            Err(TypeParseError::Unresolved { segment, location }) => {
+                if segment.contains('.') {
+                    return Err(TypeParseError::Unresolved { segment, location });
+                }
+                let mut found = vec![];
+                self.find_namespaces_named(&self.0.top_level_package(), &segment, &mut found);
+                if found.len() > 1 {
+                    return Err(TypeParseError::AmbiguousReference { segment, location });
+                }
+                let Some(ns) = found.pop() else {
+                    return Err(TypeParseError::Unresolved { segment, location });
+                };
+                (segment, ns)
+            },
+            Err(e) => return Err(e),
+        };
+        let (segment, r) = r;
+        if r.is::<Namespace>() {
+            Ok(r)
+        } else if r.is::<NamespaceConstant>() {
+            Ok(r.referenced_ns())
+        } else {
+            Err(TypeParseError::NotANamespace { segment, location: qual.location() })
+        }
+    }
+
+    /// Collects namespace definitions with a given local name from a package
+    /// and its subpackages.
+    fn find_namespaces_named(&self, package: &Entity, local_name: &str, found: &mut Vec<Entity>) {
+        for (name, r) in package.properties(self.0).borrow().iter() {
+            let r = r.resolve_alias();
+            if name.local_name() == local_name && r.is::<Namespace>() && !found.contains(&r) {
+                found.push(r);
+            }
+        }
+        for subpackage in package.subpackages().borrow().values() {
+            self.find_namespaces_named(subpackage, local_name, found);
+        }
+    }
+
+    fn public_ns_of_top_level(&self, location: &Location) -> Result<Entity, TypeParseError> {
+        self.0.top_level_package().public_ns()
+            .ok_or(TypeParseError::Unresolved { segment: "public".into(), location: location.clone() })
+    }
+
+    fn array_type(&self, location: &Location) -> Result<Entity, TypeParseError> {
+        let t = self.0.array_type();
+        if t.is::<UnresolvedEntity>() {
+            return Err(TypeParseError::Unresolved { segment: "Array".into(), location: location.clone() });
+        }
+        Ok(t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Defines a non-generic class in the top-level package.
+    fn define_class(db: &Database, local_name: &str) -> Entity {
+        let top_level_package = db.top_level_package();
+        let public_ns = top_level_package.public_ns().unwrap();
+        let name = db.factory().create_qname(&public_ns, local_name.into());
+        let class = db.factory().create_class_type(name.clone(), &public_ns);
+        top_level_package.properties(db).set(name, class.clone());
+        class
+    }
+
+    #[test]
+    fn resolves_non_generic_type() {
+        let db = Database::new(DatabaseOptions::default());
+        let string_type = define_class(&db, "String");
+        assert_eq!(TypeParser(&db).parse_type("String").unwrap(), string_type);
+    }
+
+    #[test]
+    fn rejects_type_arguments_to_non_generic_type() {
+        let db = Database::new(DatabaseOptions::default());
+        define_class(&db, "String");
+        define_class(&db, "int");
+        let r = TypeParser(&db).parse_type("String.<int>");
+        assert!(matches!(r, Err(TypeParseError::TypeSubstitution(TypeSubstitutionError::WrongParameterCount { expected: 0, actual: 1 }))));
+    }
+}
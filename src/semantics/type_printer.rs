@@ -0,0 +1,398 @@
+use crate::ns::*;
+
+/// Options for `TypePrinter`.
+#[derive(Clone)]
+pub struct TypePrinterOptions {
+    /// Whether to print fully qualified names. Default: `true`.
+    ///
+    /// If `false`, a definition is printed by its local name when
+    /// that name refers to no other definition from `scope`, and
+    /// fully qualified otherwise.
+    pub qualify: bool,
+
+    /// The scope whose scope chain, imports and enclosing package
+    /// determine which definitions may be printed by their local name.
+    /// Without a scope, only top-level definitions are printed by their
+    /// local name when `qualify` is `false`.
+    pub scope: Option<Entity>,
+
+    /// Whether to print the namespace of names, including system namespaces,
+    /// as in `private::x` or `http://www.adobe.com/2006/flex/mx/internal::x`.
+    /// Default: `false`.
+    pub namespace_qualifiers: bool,
+
+    /// Whether to print parameter names in method signatures. Default: `true`.
+    pub param_names: bool,
+
+    /// Function types and method signatures whose single-line form is longer
+    /// than this number of characters are printed with one parameter per line.
+    /// Default: `None`.
+    pub multiline_width: Option<usize>,
+}
+
+impl Default for TypePrinterOptions {
+    fn default() -> Self {
+        Self {
+            qualify: true,
+            scope: None,
+            namespace_qualifiers: false,
+            param_names: true,
+            multiline_width: None,
+        }
+    }
+}
+
+/// Printer of types and definitions, used for diagnostics
+/// and IDE hovers.
+pub struct TypePrinter<'a> {
+    db: &'a Database,
+    options: TypePrinterOptions,
+}
+
+impl<'a> TypePrinter<'a> {
+    pub fn new(db: &'a Database, options: TypePrinterOptions) -> Self {
+        Self { db, options }
+    }
+
+    /// Prints a type.
+    pub fn print_type(&self, t: &Entity) -> String {
+        if t.is::<Alias>() {
+            return self.print_type(&t.alias_of());
+        }
+        if t.is::<FunctionType>() {
+            return self.print_function_type(t);
+        }
+        if t.is::<NullableType>() {
+            let base = t.base();
+            return if base.is::<FunctionType>() {
+                format!("?{}", self.print_type(&base))
+            } else {
+                format!("{}?", self.print_type(&base))
+            };
+        }
+        if t.is::<NonNullableType>() {
+            let base = t.base();
+            return if base.is::<FunctionType>() {
+                format!("({})!", self.print_type(&base))
+            } else {
+                format!("{}!", self.print_type(&base))
+            };
+        }
+        if t.is::<TupleType>() {
+            return format!("[{}]", self.print_type_list(&t.element_types()));
+        }
+        if t.is::<TypeAfterSubstitution>() {
+            return format!("{}.<{}>", self.print_name(&t.origin()), self.print_type_list(&t.substitute_types()));
+        }
+        if t.is::<ClassType>() || t.is::<InterfaceType>() {
+            let type_params = t.type_params().map(|list| format!(".<{}>", self.print_type_list(&list))).unwrap_or_default();
+            return self.print_name(t) + &type_params;
+        }
+        if t.is::<EnumType>() {
+            return self.print_name(t);
+        }
+        t.to_string()
+    }
+
+    /// Prints a definition, such as a variable, a property accessor, a method
+    /// or a type definition, with its static type or signature.
+    pub fn print_definition(&self, definition: &Entity) -> String {
+        let definition = definition.resolve_alias();
+        let r#static = if definition.is_static() { "static " } else { "" };
+        if definition.is::<VariableSlot>() {
+            let keyword = if definition.read_only(self.db) { "const" } else { "var" };
+            let static_type = definition.static_type(self.db);
+            return format!("{}{} {} : {}", r#static, keyword, self.print_member_name(&definition), self.print_type(&static_type));
+        }
+        if definition.is::<VirtualSlot>() {
+            let static_type = self.print_type(&definition.static_type(self.db));
+            let name = self.print_member_name(&definition);
+            return if definition.getter(self.db).is_some() || definition.setter(self.db).is_none() {
+                format!("{}function get {}() : {}", r#static, name, static_type)
+            } else {
+                format!("{}function set {}(value : {}) : void", r#static, name, static_type)
+            };
+        }
+        if definition.is::<MethodSlot>() {
+            let type_params = definition.type_params().map(|list| format!(".<{}>", self.print_type_list(&list))).unwrap_or_default();
+            let head = format!("{}function {}{}", r#static, self.print_member_name(&definition), type_params);
+            let Ok(signature) = definition.signature(self.db).defer() else {
+                return head + "(...)";
+            };
+            let param_names: Vec<String> = if self.options.param_names {
+                definition.param_names().iter().collect()
+            } else {
+                vec![]
+            };
+            return self.print_signature(&head, &signature, &param_names);
+        }
+        if definition.is::<ClassType>() {
+            return format!("class {}", self.print_type(&definition));
+        }
+        if definition.is::<InterfaceType>() {
+            return format!("interface {}", self.print_type(&definition));
+        }
+        if definition.is::<EnumType>() {
+            return format!("enum {}", self.print_type(&definition));
+        }
+        if definition.is::<Type>() {
+            return self.print_type(&definition);
+        }
+        definition.to_string()
+    }
+
+    fn print_type_list(&self, list: &SharedArray<Entity>) -> String {
+        list.iter().map(|t| self.print_type(&t)).collect::<Vec<_>>().join(", ")
+    }
+
+    fn print_function_type(&self, t: &Entity) -> String {
+        let type_params = t.type_params().map(|list| format!(".<{}>", self.print_type_list(&list))).unwrap_or_default();
+        self.print_signature(&format!("function{type_params}"), t, &[])
+    }
+
+    /// Prints the parameters and result type of a function type after `head`.
+    fn print_signature(&self, head: &str, signature: &Entity, param_names: &[String]) -> String {
+        let params: Vec<String> = signature.params().iter().enumerate().map(|(i, param)| {
+            let static_type = self.print_type(&param.static_type);
+            match (param.kind, param_names.get(i).filter(|name| !name.is_empty())) {
+                (ParameterKind::Required, None) => static_type,
+                (ParameterKind::Optional, None) => format!("{static_type}="),
+                (ParameterKind::Rest, None) => format!("...{static_type}"),
+                (ParameterKind::Required, Some(name)) => format!("{name} : {static_type}"),
+                (ParameterKind::Optional, Some(name)) => format!("{name} : {static_type}="),
+                (ParameterKind::Rest, Some(name)) => format!("...{name} : {static_type}"),
+            }
+        }).collect();
+        let result_type = self.print_type(&signature.result_type());
+        let single_line = format!("{head}({}) : {result_type}", params.join(", "));
+        match self.options.multiline_width {
+            Some(width) if single_line.len() > width && !params.is_empty() => {
+                let params: Vec<String> = params.iter().map(|p| format!("    {p}")).collect();
+                format!("{head}(\n{}\n) : {result_type}", params.join(",\n"))
+            },
+            _ => single_line,
+        }
+    }
+
+    /// Prints the name of a type definition.
+    fn print_name(&self, t: &Entity) -> String {
+        // `__AS3__.vec.Vector` is printed as `Vector`
+        if t == &self.db.vector_type() {
+            return self.print_qname(&t.name());
+        }
+        if !self.options.qualify && self.is_visible_by_local_name(t) {
+            return self.print_qname(&t.name());
+        }
+        let mut r: Vec<String> = vec![];
+        let mut p = Some(t.clone());
+        while let Some(p1) = p {
+            let name = if p1.is::<Package>() {
+                p1.local_name()
+            } else {
+                self.print_qname(&p1.name())
+            };
+            if !name.is_empty() {
+                r.insert(0, name);
+            }
+            p = p1.parent().filter(|p| !p.is::<Scope>());
+        }
+        r.join(".")
+    }
+
+    /// Prints the name of a member definition. Members are qualified
+    /// by their enclosing type only when `qualify` is `true`.
+    fn print_member_name(&self, definition: &Entity) -> String {
+        let name = self.print_qname(&definition.name());
+        let parent = definition.parent().filter(|p| !p.is::<Scope>());
+        match parent {
+            Some(parent) if self.options.qualify && !(parent.is::<Package>() && parent.local_name().is_empty()) => {
+                let parent = if parent.is::<Package>() { parent.fully_qualified_name() } else { self.print_name(&parent) };
+                format!("{parent}.{name}")
+            },
+            _ => name,
+        }
+    }
+
+    fn print_qname(&self, name: &QName) -> String {
+        if !self.options.namespace_qualifiers {
+            return name.to_string();
+        }
+        let ns = name.namespace();
+        format!("{}::{}", if ns.is::<SystemNamespace>() { ns.to_string() } else { ns.uri() }, name.local_name())
+    }
+
+    /// Indicates whether a type definition is the only definition
+    /// its local name refers to from the printer's scope.
+    fn is_visible_by_local_name(&self, t: &Entity) -> bool {
+        let Ok(name) = t.try_name() else {
+            return false;
+        };
+        let local_name = name.local_name();
+        let mut found: Vec<Entity> = vec![];
+
+        let add_from_names = |names: &Names, found: &mut Vec<Entity>| {
+            for (qname, entity) in names.borrow().iter() {
+                if qname.local_name() == local_name {
+                    let entity = entity.resolve_alias();
+                    if !found.contains(&entity) {
+                        found.push(entity);
+                    }
+                }
+            }
+        };
+
+        add_from_names(&self.db.top_level_package().properties(self.db), &mut found);
+
+        if let Some(scope) = self.options.scope.as_ref() {
+            for scope in scope.descending_scope_hierarchy() {
+                add_from_names(&scope.properties(self.db), &mut found);
+                if scope.is::<PackageScope>() {
+                    add_from_names(&scope.package().properties(self.db), &mut found);
+                }
+                for import in scope.import_list().iter() {
+                    if import.is::<PackageWildcardImport>() {
+                        add_from_names(&import.package().properties(self.db), &mut found);
+                    } else if let Ok(property) = import.try_property() {
+                        if property.try_name().map(|n| n.local_name() == local_name).unwrap_or(false) {
+                            let property = property.resolve_alias();
+                            if !found.contains(&property) {
+                                found.push(property);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        found.len() == 1 && &found[0] == t
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    fn define_class(db: &Database, package: &Entity, local_name: &str) -> Entity {
+        let public_ns = package.public_ns().unwrap();
+        let name = db.factory().create_qname(&public_ns, local_name.into());
+        let class = db.factory().create_class_type(name.clone(), &public_ns);
+        class.set_parent(Some(package.clone()));
+        package.properties(db).set(name, class.clone());
+        class
+    }
+
+    fn printer(db: &Database, options: TypePrinterOptions) -> TypePrinter<'_> {
+        TypePrinter::new(db, options)
+    }
+
+    fn unqualified(scope: Option<Entity>) -> TypePrinterOptions {
+        TypePrinterOptions { qualify: false, scope, ..TypePrinterOptions::default() }
+    }
+
+    #[test]
+    fn prints_vector_by_its_local_name() {
+        let db = Database::new(DatabaseOptions::default());
+        define_class(&db, &db.top_level_package(), "int");
+        let vector = define_class(&db, &db.as3_vec_package(), "Vector");
+        let public_ns = db.top_level_package().public_ns().unwrap();
+        let type_param = db.factory().create_type_parameter_type(&db.factory().create_qname(&public_ns, "T".into()));
+        vector.set_type_params(Some(shared_array![type_param]));
+        let vectors = define_class(&db, &db.as3_vec_package(), "Vectors");
+        let vector_of_int = db.factory().create_type_after_substitution(&vector, &shared_array![db.int_type()]);
+
+        let p = printer(&db, TypePrinterOptions::default());
+        assert_eq!(p.print_type(&vector), "Vector.<T>");
+        assert_eq!(p.print_type(&vector_of_int), "Vector.<int>");
+        assert_eq!(p.print_type(&vectors), "__AS3__.vec.Vectors");
+    }
+
+    #[test]
+    fn qualifies_names_from_scope_and_imports() {
+        let db = Database::new(DatabaseOptions::default());
+        let com_a = db.factory().create_package(["com", "a"]);
+        let com_b = db.factory().create_package(["com", "b"]);
+        let a = define_class(&db, &com_a, "A");
+        let b = define_class(&db, &com_a, "B");
+        define_class(&db, &com_b, "A");
+        let t = define_class(&db, &db.top_level_package(), "T");
+
+        assert_eq!(printer(&db, TypePrinterOptions::default()).print_type(&a), "com.a.A");
+        assert_eq!(printer(&db, unqualified(None)).print_type(&a), "com.a.A");
+        assert_eq!(printer(&db, unqualified(None)).print_type(&t), "T");
+
+        // Wildcard import
+        let scope = db.factory().create_scope();
+        scope.import_list().push(db.factory().create_package_wildcard_import(&com_a, None));
+        assert_eq!(printer(&db, unqualified(Some(scope.clone()))).print_type(&a), "A");
+
+        // Ambiguous with another wildcard import
+        scope.import_list().push(db.factory().create_package_wildcard_import(&com_b, None));
+        assert_eq!(printer(&db, unqualified(Some(scope.clone()))).print_type(&a), "com.a.A");
+        assert_eq!(printer(&db, unqualified(Some(scope))).print_type(&b), "B");
+
+        // Property import in an outer scope
+        let outer = db.factory().create_scope();
+        outer.import_list().push(db.factory().create_package_property_import(&b, None));
+        let inner = db.factory().create_scope();
+        inner.set_parent(Some(outer));
+        assert_eq!(printer(&db, unqualified(Some(inner.clone()))).print_type(&b), "B");
+        assert_eq!(printer(&db, unqualified(Some(inner))).print_type(&a), "com.a.A");
+    }
+
+    #[test]
+    fn prints_namespace_qualifiers() {
+        let db = Database::new(DatabaseOptions::default());
+        let a = define_class(&db, &db.top_level_package(), "A");
+        let private_name = db.factory().create_qname(&db.factory().create_private_ns(Some(a.clone())), "x".into());
+        let x = db.factory().create_variable_slot(&private_name, true, &db.any_type());
+        x.set_parent(Some(a.clone()));
+        let mx_internal = db.factory().create_user_ns("http://www.adobe.com/2006/flex/mx/internal".into());
+        let y = db.factory().create_variable_slot(&db.factory().create_qname(&mx_internal, "y".into()), false, &db.any_type());
+        y.set_parent(Some(a.clone()));
+
+        let options = TypePrinterOptions { namespace_qualifiers: true, ..unqualified(None) };
+        assert_eq!(printer(&db, options.clone()).print_definition(&x), "const private::x : *");
+        assert_eq!(printer(&db, options).print_definition(&y), "var http://www.adobe.com/2006/flex/mx/internal::y : *");
+        assert_eq!(printer(&db, TypePrinterOptions::default()).print_definition(&x), "const A.x : *");
+    }
+
+    /// Defines `f(a : int, b : String=, ...rest : Array) : void` in the top-level package.
+    fn define_method(db: &Database) -> Entity {
+        let top_level_package = db.top_level_package();
+        for name in ["int", "String", "Array"] {
+            define_class(db, &top_level_package, name);
+        }
+        let params = [(ParameterKind::Required, db.int_type()), (ParameterKind::Optional, db.string_type()), (ParameterKind::Rest, db.array_type())]
+            .into_iter()
+            .map(|(kind, static_type)| Rc::new(SemanticFunctionTypeParameter { kind, static_type }))
+            .collect();
+        let signature = db.factory().create_function_type(params, db.void_type());
+        let name = db.factory().create_qname(&top_level_package.public_ns().unwrap(), "f".into());
+        let method = db.factory().create_method_slot(&name, &signature);
+        method.set_parent(Some(top_level_package));
+        method.set_param_names(shared_array!["a".to_owned(), "b".to_owned(), "rest".to_owned()]);
+        method
+    }
+
+    #[test]
+    fn prints_parameter_names() {
+        let db = Database::new(DatabaseOptions::default());
+        let f = define_method(&db);
+        assert_eq!(printer(&db, TypePrinterOptions::default()).print_definition(&f), "function f(a : int, b : String=, ...rest : Array) : void");
+        let options = TypePrinterOptions { param_names: false, ..TypePrinterOptions::default() };
+        assert_eq!(printer(&db, options).print_definition(&f), "function f(int, String=, ...Array) : void");
+        assert_eq!(printer(&db, TypePrinterOptions::default()).print_type(&f.signature(&db)), "function(int, String=, ...Array) : void");
+    }
+
+    #[test]
+    fn prints_long_signatures_in_multiple_lines() {
+        let db = Database::new(DatabaseOptions::default());
+        let f = define_method(&db);
+        let options = TypePrinterOptions { multiline_width: Some(40), ..TypePrinterOptions::default() };
+        assert_eq!(printer(&db, options.clone()).print_definition(&f), "function f(\n    a : int,\n    b : String=,\n    ...rest : Array\n) : void");
+        let g = db.factory().create_function_type(vec![], db.void_type());
+        assert_eq!(printer(&db, options).print_type(&g), "function() : void");
+        let options = TypePrinterOptions { multiline_width: Some(60), ..TypePrinterOptions::default() };
+        assert_eq!(printer(&db, options).print_definition(&f), "function f(a : int, b : String=, ...rest : Array) : void");
+    }
+}
@@ -28,7 +28,11 @@ impl<'a> Unused<'a> {
         if property.is::<InvalidationEntity>() {
             return;
         }
-        let qn = property.name();
+        // Things without a name, such as imports, are always tracked
+        let Ok(qn) = property.try_name() else {
+            self.0.remove_unused_thing(property);
+            return;
+        };
         if !qn.in_public_or_protected_ns() {
             if property.is_entity_after_substitution() {
                 self.mark_used(&property.origin());